@@ -0,0 +1,14 @@
+use crate::{Component, Entity};
+
+/// The entities that are children of this entity. Thomas maintains this component for you, and removes it once the
+/// entity no longer has any children.
+#[derive(Component, Debug)]
+pub struct Children {
+    pub(crate) entities: Vec<Entity>,
+}
+impl Children {
+    /// The entity's direct children, in the order they became children of the entity.
+    pub fn entities(&self) -> &Vec<Entity> {
+        &self.entities
+    }
+}
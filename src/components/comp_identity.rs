@@ -2,6 +2,8 @@ use crate::Component;
 
 /// Identifying information that can be used to give entities meaningful identifying factors.
 #[derive(Component, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[component(serialize)]
 pub struct Identity {
   pub id: String,
   pub name: String,
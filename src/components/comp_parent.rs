@@ -0,0 +1,17 @@
+use crate::{Component, Entity};
+
+/// Marks an entity as the child of another entity. Thomas maintains this component for you: use
+/// `GameCommand::SetParent` and `GameCommand::RemoveParent` to change an entity's parent rather than adding or removing
+/// this component yourself.
+///
+/// A child's `TerminalTransform` is its offset from its parent, so children follow their parent around the world.
+#[derive(Component, Debug)]
+pub struct Parent {
+    pub(crate) entity: Entity,
+}
+impl Parent {
+    /// The entity's parent.
+    pub fn entity(&self) -> &Entity {
+        &self.entity
+    }
+}
@@ -2,6 +2,8 @@ use crate::{Component, Coords3d};
 
 /// Positional data for a 3D world.
 #[derive(Component, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[component(serialize)]
 pub struct Transform {
   pub coords: Coords3d,
 }
\ No newline at end of file
@@ -2,6 +2,8 @@ use crate::{Component, Coords2d};
 
 /// Positional data for a 2D world.
 #[derive(Component, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[component(serialize)]
 pub struct Transform2d {
     pub coords: Coords2d,
 }
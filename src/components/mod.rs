@@ -7,6 +7,12 @@ pub use comp_transform2d::*;
 mod comp_identity;
 pub use comp_identity::*;
 
+mod comp_parent;
+pub use comp_parent::*;
+
+mod comp_children;
+pub use comp_children::*;
+
 mod terminal;
 pub use terminal::*;
 
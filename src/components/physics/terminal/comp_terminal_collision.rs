@@ -53,14 +53,14 @@ mod tests {
             let collision = TerminalCollision {
                 bodies: [
                     (
-                        Entity(0),
+                        Entity(0, 0),
                         TerminalCollider {
                             is_active: true,
                             layer: Layer(0),
                         },
                     ),
                     (
-                        Entity(1),
+                        Entity(1, 0),
                         TerminalCollider {
                             is_active: true,
                             layer: Layer(1),
@@ -78,14 +78,14 @@ mod tests {
             let collision = TerminalCollision {
                 bodies: [
                     (
-                        Entity(0),
+                        Entity(0, 0),
                         TerminalCollider {
                             is_active: true,
                             layer: Layer(0),
                         },
                     ),
                     (
-                        Entity(1),
+                        Entity(1, 0),
                         TerminalCollider {
                             is_active: true,
                             layer: Layer(0),
@@ -103,14 +103,14 @@ mod tests {
             let collision = TerminalCollision {
                 bodies: [
                     (
-                        Entity(0),
+                        Entity(0, 0),
                         TerminalCollider {
                             is_active: true,
                             layer: Layer(0),
                         },
                     ),
                     (
-                        Entity(1),
+                        Entity(1, 0),
                         TerminalCollider {
                             is_active: true,
                             layer: Layer(1),
@@ -127,14 +127,14 @@ mod tests {
             let collision = TerminalCollision {
                 bodies: [
                     (
-                        Entity(0),
+                        Entity(0, 0),
                         TerminalCollider {
                             is_active: true,
                             layer: Layer(0),
                         },
                     ),
                     (
-                        Entity(1),
+                        Entity(1, 0),
                         TerminalCollider {
                             is_active: true,
                             layer: Layer(2),
@@ -151,14 +151,14 @@ mod tests {
             let collision = TerminalCollision {
                 bodies: [
                     (
-                        Entity(0),
+                        Entity(0, 0),
                         TerminalCollider {
                             is_active: true,
                             layer: Layer(3),
                         },
                     ),
                     (
-                        Entity(1),
+                        Entity(1, 0),
                         TerminalCollider {
                             is_active: true,
                             layer: Layer(2),
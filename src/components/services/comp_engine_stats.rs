@@ -1,9 +1,209 @@
-use crate::{Component, Timer};
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Duration,
+};
+
+use crate::{Resource, Timer};
+
+/// How many of the most recent times something was done are kept by a `Timing`.
+pub const TIMING_WINDOW_SIZE: usize = 60;
 
 /// Represents stats tracked by the engine to report on its performance.
-#[derive(Component)]
+///
+/// Along with the FPS, the time taken by every `System` that runs is tracked, as well as the time taken to process the
+/// command queue after each event and to draw each frame. Times are measured in real time, regardless of the game's
+/// `Clock`.
+#[derive(Resource)]
 pub struct EngineStats {
     pub fps: u64,
     pub(crate) frame_timer: Timer,
     pub(crate) frame_counter: u64,
+    systems: HashMap<(String, String), SystemProfile>,
+    command_processing: HashMap<String, Timing>,
+    rendering: Timing,
+}
+impl EngineStats {
+    pub(crate) fn new(frame_timer: Timer) -> Self {
+        Self {
+            fps: 0,
+            frame_timer,
+            frame_counter: 0,
+            systems: HashMap::new(),
+            command_processing: HashMap::new(),
+            rendering: Timing::new(),
+        }
+    }
+
+    /// The profiles of every `System` that's run since the stats were added, in no particular order.
+    pub fn systems(&self) -> impl Iterator<Item = &SystemProfile> {
+        self.systems.values()
+    }
+
+    /// The profile of the `System` with the name in the event. Systems without a name are named after the event and
+    /// their position in it, like `update #3`.
+    pub fn system(&self, event_name: &str, system_name: &str) -> Option<&SystemProfile> {
+        self.systems
+            .get(&(event_name.to_string(), system_name.to_string()))
+    }
+
+    /// How long it takes to process the commands issued by the systems in the event.
+    pub fn command_processing(&self, event_name: &str) -> Option<&Timing> {
+        self.command_processing.get(event_name)
+    }
+
+    /// How long the renderer takes to draw a frame.
+    pub fn rendering(&self) -> &Timing {
+        &self.rendering
+    }
+
+    pub(crate) fn record_system(
+        &mut self,
+        event_name: &str,
+        system_name: String,
+        query_duration: Duration,
+        operator_duration: Duration,
+    ) {
+        let profile = self
+            .systems
+            .entry((event_name.to_string(), system_name.clone()))
+            .or_insert_with(|| SystemProfile {
+                event_name: event_name.to_string(),
+                name: system_name,
+                queries: Timing::new(),
+                operator: Timing::new(),
+            });
+
+        profile.queries.record(query_duration);
+        profile.operator.record(operator_duration);
+    }
+
+    pub(crate) fn record_command_processing(&mut self, event_name: &str, duration: Duration) {
+        self.command_processing
+            .entry(event_name.to_string())
+            .or_default()
+            .record(duration);
+    }
+
+    pub(crate) fn record_rendering(&mut self, duration: Duration) {
+        self.rendering.record(duration);
+    }
+}
+
+/// How long a `System` takes to run, split into the time taken to evaluate its queries and the time taken by its
+/// operator.
+#[derive(Clone, Debug)]
+pub struct SystemProfile {
+    event_name: String,
+    name: String,
+    queries: Timing,
+    operator: Timing,
+}
+impl SystemProfile {
+    pub fn event_name(&self) -> &str {
+        &self.event_name
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn queries(&self) -> &Timing {
+        &self.queries
+    }
+
+    pub fn operator(&self) -> &Timing {
+        &self.operator
+    }
+
+    /// The average time the `System` takes to run, queries and operator together.
+    pub fn average(&self) -> Duration {
+        self.queries.average() + self.operator.average()
+    }
+}
+
+/// The durations of the last `TIMING_WINDOW_SIZE` times something was done.
+#[derive(Clone, Debug, Default)]
+pub struct Timing {
+    samples: VecDeque<Duration>,
+}
+impl Timing {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn record(&mut self, duration: Duration) {
+        if self.samples.len() == TIMING_WINDOW_SIZE {
+            self.samples.pop_front();
+        }
+
+        self.samples.push_back(duration);
+    }
+
+    /// The average duration. Zero if nothing's been recorded yet.
+    pub fn average(&self) -> Duration {
+        if self.samples.is_empty() {
+            return Duration::ZERO;
+        }
+
+        self.samples.iter().sum::<Duration>() / self.samples.len() as u32
+    }
+
+    /// The longest duration. Zero if nothing's been recorded yet.
+    pub fn max(&self) -> Duration {
+        self.samples.iter().max().copied().unwrap_or_default()
+    }
+
+    /// The most recent duration, if anything's been recorded.
+    pub fn last(&self) -> Option<Duration> {
+        self.samples.back().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    mod test_timing {
+        use super::*;
+
+        #[test]
+        fn is_zero_when_nothing_has_been_recorded() {
+            let timing = Timing::new();
+
+            assert_eq!(timing.average(), Duration::ZERO);
+            assert_eq!(timing.max(), Duration::ZERO);
+            assert_eq!(timing.last(), None);
+        }
+
+        #[test]
+        fn averages_the_recorded_durations() {
+            let mut timing = Timing::new();
+
+            timing.record(millis(2));
+            timing.record(millis(6));
+            timing.record(millis(4));
+
+            assert_eq!(timing.average(), millis(4));
+            assert_eq!(timing.max(), millis(6));
+            assert_eq!(timing.last(), Some(millis(4)));
+        }
+
+        #[test]
+        fn forgets_durations_outside_the_window() {
+            let mut timing = Timing::new();
+
+            timing.record(millis(100));
+
+            for _ in 0..TIMING_WINDOW_SIZE {
+                timing.record(millis(1));
+            }
+
+            assert_eq!(timing.average(), millis(1));
+            assert_eq!(timing.max(), millis(1));
+        }
+    }
 }
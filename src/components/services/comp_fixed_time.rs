@@ -0,0 +1,163 @@
+use std::time::Duration;
+
+use crate::Resource;
+
+/// The default time between fixed updates: 20 milliseconds, or 50 fixed updates per second.
+pub const DEFAULT_FIXED_STEP: Duration = Duration::from_millis(20);
+/// The default cap on how many fixed updates can run in a single frame.
+pub const DEFAULT_MAX_FIXED_STEPS_PER_FRAME: u32 = 5;
+
+/// Controls the fixed-update event. Every frame, the time that's passed is added to an accumulator, and the
+/// fixed-update event runs once for every full step in the accumulator. Systems in the fixed-update event can then
+/// treat every run as taking exactly `step` time, which keeps movement and collisions consistent regardless of frame
+/// rate.
+///
+/// If a frame takes so long that more than `max_steps_per_frame` steps have built up, the extra steps are dropped.
+/// That slows the game down rather than letting it fall further and further behind trying to catch up.
+///
+/// Thomas adds a `FixedTime` with the default step for you. To use a different step, insert your own:
+/// ```
+/// use std::time::Duration;
+/// use thomas::{FixedTime, Game, GameOptions};
+///
+/// Game::new(GameOptions {
+///     press_escape_to_quit: true,
+///     max_frame_rate: 60,
+/// })
+/// .insert_resource(FixedTime::new(Duration::from_millis(10)));
+/// ```
+#[derive(Resource)]
+pub struct FixedTime {
+    step: Duration,
+    max_steps_per_frame: u32,
+    accumulated: Duration,
+}
+impl FixedTime {
+    /// # Panics
+    /// If `step` is zero.
+    pub fn new(step: Duration) -> Self {
+        if step.is_zero() {
+            panic!("FixedTime's step must be greater than zero.");
+        }
+
+        Self {
+            step,
+            max_steps_per_frame: DEFAULT_MAX_FIXED_STEPS_PER_FRAME,
+            accumulated: Duration::ZERO,
+        }
+    }
+
+    /// Sets the most fixed updates that can run in a single frame.
+    pub fn with_max_steps_per_frame(mut self, max_steps_per_frame: u32) -> Self {
+        self.max_steps_per_frame = max_steps_per_frame;
+
+        self
+    }
+
+    /// The time every fixed update represents.
+    pub fn step(&self) -> Duration {
+        self.step
+    }
+
+    /// Like `step`, but in seconds.
+    pub fn step_seconds(&self) -> f64 {
+        self.step.as_secs_f64()
+    }
+
+    pub fn max_steps_per_frame(&self) -> u32 {
+        self.max_steps_per_frame
+    }
+
+    /// How far the game is between the last fixed update and the next one, from 0 up to (but not including) 1. Use it
+    /// to smoothly draw things between where they were after the last fixed update and where they'll be after the next.
+    pub fn alpha(&self) -> f64 {
+        self.accumulated.as_secs_f64() / self.step.as_secs_f64()
+    }
+
+    /// Adds the time that passed over the last frame and takes out as many full steps as can run this frame. Returns
+    /// the number of fixed updates to run.
+    pub(crate) fn advance(&mut self, delta: Duration) -> u32 {
+        self.accumulated += delta;
+
+        let step_nanos = self.step.as_nanos();
+        let due_steps = self.accumulated.as_nanos() / step_nanos;
+
+        // Every due step is taken out, so any beyond the cap are dropped rather than carried into the next frame.
+        self.accumulated = Duration::from_nanos((self.accumulated.as_nanos() % step_nanos) as u64);
+
+        due_steps.min(self.max_steps_per_frame as u128) as u32
+    }
+}
+impl Default for FixedTime {
+    fn default() -> Self {
+        Self::new(DEFAULT_FIXED_STEP)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod test_advance {
+        use super::*;
+
+        #[test]
+        fn runs_a_step_for_every_full_step_that_has_passed() {
+            let mut fixed_time = FixedTime::new(Duration::from_millis(10));
+
+            assert_eq!(fixed_time.advance(Duration::from_millis(25)), 2);
+        }
+
+        #[test]
+        fn carries_leftover_time_into_the_next_frame() {
+            let mut fixed_time = FixedTime::new(Duration::from_millis(10));
+
+            assert_eq!(fixed_time.advance(Duration::from_millis(6)), 0);
+            assert_eq!(fixed_time.advance(Duration::from_millis(6)), 1);
+        }
+
+        #[test]
+        fn drops_steps_beyond_the_cap() {
+            let mut fixed_time =
+                FixedTime::new(Duration::from_millis(10)).with_max_steps_per_frame(3);
+
+            assert_eq!(fixed_time.advance(Duration::from_millis(105)), 3);
+            assert_eq!(fixed_time.advance(Duration::ZERO), 0);
+        }
+
+        #[test]
+        fn drops_a_huge_backlog_without_working_through_it() {
+            let mut fixed_time =
+                FixedTime::new(Duration::from_nanos(1)).with_max_steps_per_frame(3);
+
+            assert_eq!(
+                fixed_time.advance(Duration::from_secs(60 * 60 * 24 * 365)),
+                3
+            );
+            assert_eq!(fixed_time.advance(Duration::ZERO), 0);
+        }
+    }
+
+    mod test_alpha {
+        use super::*;
+
+        #[test]
+        fn is_the_fraction_of_a_step_left_over() {
+            let mut fixed_time = FixedTime::new(Duration::from_millis(10));
+
+            fixed_time.advance(Duration::from_millis(25));
+
+            assert!((fixed_time.alpha() - 0.5).abs() < f64::EPSILON);
+        }
+    }
+
+    mod test_new {
+        use super::*;
+
+        #[test]
+        #[should_panic(expected = "FixedTime's step must be greater than zero.")]
+        fn panics_with_a_zero_step() {
+            FixedTime::new(Duration::ZERO);
+        }
+    }
+}
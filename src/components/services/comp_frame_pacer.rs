@@ -0,0 +1,161 @@
+use std::time::Duration;
+
+use crate::{Clock, Resource, Shared};
+
+/// Keeps the game from running more frames per second than `GameOptions::max_frame_rate`. Thomas adds this resource
+/// for you; you can read it to see how well the game is keeping up with its target frame rate.
+///
+/// Between frames, the pacer waits on the game's clock for whatever's left of the frame's time budget. On the
+/// `RealClock`, that means sleeping for most of it, so the game doesn't keep a CPU core busy while it waits.
+#[derive(Resource)]
+pub struct FramePacer {
+    clock: Shared<dyn Clock>,
+    target_frame_time: Option<Duration>,
+    frame_start: Option<Duration>,
+    last_frame_work_time: Duration,
+    missed_frames: u64,
+}
+impl FramePacer {
+    /// Makes a pacer that aims for `max_frame_rate` frames per second. A `max_frame_rate` of 0 means the frame rate is
+    /// uncapped and the pacer never waits.
+    pub(crate) fn new(max_frame_rate: u16, clock: impl Clock + 'static) -> Self {
+        Self {
+            clock: Shared::new(clock),
+            target_frame_time: if max_frame_rate > 0 {
+                Some(Duration::from_secs(1) / max_frame_rate as u32)
+            } else {
+                None
+            },
+            frame_start: None,
+            last_frame_work_time: Duration::ZERO,
+            missed_frames: 0,
+        }
+    }
+
+    /// How long each frame should take, or `None` if the frame rate is uncapped.
+    pub fn target_frame_time(&self) -> Option<Duration> {
+        self.target_frame_time
+    }
+
+    /// How long the last frame took to run, not counting the time spent waiting for the frame to end.
+    pub fn last_frame_work_time(&self) -> Duration {
+        self.last_frame_work_time
+    }
+
+    /// How many frames have taken longer than the target frame time since the game started.
+    pub fn missed_frames(&self) -> u64 {
+        self.missed_frames
+    }
+
+    /// Marks the start of a new frame. Returns how long it's been since the previous frame started, or zero if this is
+    /// the first frame.
+    pub(crate) fn start_frame(&mut self) -> Duration {
+        let now = self.clock.now();
+        let previous_frame_time = self
+            .frame_start
+            .map_or(Duration::ZERO, |frame_start| now - frame_start);
+
+        self.frame_start = Some(now);
+
+        previous_frame_time
+    }
+
+    /// Waits until the current frame has taken up its target frame time. If the frame already took longer than that,
+    /// it counts as a missed frame and there's no wait.
+    pub(crate) fn wait_for_frame_end(&mut self) {
+        let frame_start = match self.frame_start {
+            Some(frame_start) => frame_start,
+            None => return,
+        };
+
+        self.last_frame_work_time = self.clock.now().saturating_sub(frame_start);
+
+        if let Some(target_frame_time) = self.target_frame_time {
+            if self.last_frame_work_time > target_frame_time {
+                self.missed_frames += 1;
+
+                return;
+            }
+
+            self.clock.wait_until(frame_start + target_frame_time);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ManualClock, RealClock};
+    use std::thread;
+
+    mod test_new {
+        use super::*;
+
+        #[test]
+        fn target_frame_time_is_exact() {
+            assert_eq!(
+                FramePacer::new(60, RealClock::new()).target_frame_time(),
+                Some(Duration::from_nanos(16_666_666))
+            );
+        }
+
+        #[test]
+        fn no_target_when_the_frame_rate_is_uncapped() {
+            assert_eq!(
+                FramePacer::new(0, RealClock::new()).target_frame_time(),
+                None
+            );
+        }
+    }
+
+    mod test_wait_for_frame_end {
+        use super::*;
+
+        #[test]
+        fn waits_out_the_rest_of_the_frame() {
+            let mut pacer = FramePacer::new(100, RealClock::new());
+
+            pacer.start_frame();
+            pacer.wait_for_frame_end();
+
+            assert!(pacer.start_frame() >= Duration::from_millis(10));
+            assert_eq!(pacer.missed_frames(), 0);
+        }
+
+        #[test]
+        fn counts_frames_that_take_too_long_as_missed() {
+            let mut pacer = FramePacer::new(200, RealClock::new());
+
+            pacer.start_frame();
+            thread::sleep(Duration::from_millis(10));
+            pacer.wait_for_frame_end();
+
+            assert_eq!(pacer.missed_frames(), 1);
+            assert!(pacer.last_frame_work_time() >= Duration::from_millis(10));
+        }
+
+        #[test]
+        fn frames_take_exactly_the_target_time_on_a_manual_clock() {
+            let clock = ManualClock::new();
+            let mut pacer = FramePacer::new(50, clock.clone());
+
+            pacer.start_frame();
+            clock.advance(Duration::from_millis(5));
+            pacer.wait_for_frame_end();
+
+            assert_eq!(pacer.start_frame(), Duration::from_millis(20));
+            assert_eq!(pacer.last_frame_work_time(), Duration::from_millis(5));
+        }
+    }
+
+    mod test_start_frame {
+        use super::*;
+
+        #[test]
+        fn is_zero_for_the_first_frame() {
+            let mut pacer = FramePacer::new(60, RealClock::new());
+
+            assert_eq!(pacer.start_frame(), Duration::ZERO);
+        }
+    }
+}
@@ -1,9 +1,58 @@
-use crate::Component;
+use crate::{ParallelSafe, Resource};
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use device_query::{DeviceQuery, DeviceState, Keycode};
 
+/// Somewhere `Input` reads the state of the keyboard from. `Input` asks its source which keys are pressed once at the
+/// start of every frame.
+pub trait InputSource: ParallelSafe {
+    /// The keys that are pressed down right now.
+    fn pressed_keys(&mut self) -> Vec<Keycode>;
+}
+
+/// Reads the keyboard of the machine the game is running on. This is the source `Input` uses by default.
+pub struct DeviceInputSource {
+    device_state: DeviceStateHandle,
+}
+impl DeviceInputSource {
+    pub fn new() -> Self {
+        Self {
+            device_state: DeviceStateHandle(DeviceState::new()),
+        }
+    }
+}
+impl InputSource for DeviceInputSource {
+    fn pressed_keys(&mut self) -> Vec<Keycode> {
+        self.device_state.0.get_keys()
+    }
+}
+
+/// On some platforms, `DeviceState` holds a connection to the display server that isn't marked as thread-safe, even
+/// though it's fine to use from any one thread at a time.
+struct DeviceStateHandle(DeviceState);
+// SAFETY: The handle is only reachable through `InputSource::pressed_keys`, which takes `&mut self`, so the connection
+// is never used from two threads at once.
+#[cfg(feature = "parallel")]
+unsafe impl Send for DeviceStateHandle {}
+// SAFETY: Nothing can be done with a shared reference to the handle.
+#[cfg(feature = "parallel")]
+unsafe impl Sync for DeviceStateHandle {}
+impl Default for DeviceInputSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A source that never has any keys pressed. Used when there's no display to read the keyboard from, like when running
+/// headless.
+pub struct NoInputSource {}
+impl InputSource for NoInputSource {
+    fn pressed_keys(&mut self) -> Vec<Keycode> {
+        vec![]
+    }
+}
+
 #[derive(Clone, PartialEq)]
 enum KeyState {
     Up,
@@ -17,19 +66,44 @@ struct KeyStateData {
 }
 
 /// Represents received user input.
-#[derive(Component)]
+///
+/// Besides the keys pressed on the keyboard (or whatever the `InputSource` is), keys can be pressed virtually with
+/// `press_key`, `release_key` and `tap_key`. Virtual presses are treated exactly like real ones, so they're a way for
+/// tests, bots and demo modes to drive a game:
+/// ```
+/// use thomas::{Input, Keycode, System};
+///
+/// System::new(vec![], |_, _, world| {
+///     world.resource_mut::<Input>().tap_key(Keycode::Enter);
+/// });
+/// ```
+#[derive(Resource)]
 pub struct Input {
     keylogger: HashMap<Keycode, KeyStateData>,
-    device_state: DeviceState,
+    source: Box<dyn InputSource>,
+    virtually_pressed_keys: HashSet<Keycode>,
+    virtually_tapped_keys: HashSet<Keycode>,
 }
 impl Input {
     pub fn new() -> Self {
+        Self::with_source(DeviceInputSource::new())
+    }
+
+    /// Makes an `Input` that reads which keys are pressed from the provided source rather than from the keyboard.
+    pub fn with_source(source: impl InputSource + 'static) -> Self {
         Input {
             keylogger: HashMap::new(),
-            device_state: DeviceState::new(),
+            source: Box::new(source),
+            virtually_pressed_keys: HashSet::new(),
+            virtually_tapped_keys: HashSet::new(),
         }
     }
 
+    /// Makes an `Input` that isn't connected to any device, so no keys are ever pressed.
+    pub(crate) fn new_disconnected() -> Self {
+        Self::with_source(NoInputSource {})
+    }
+
     /// Whether the key was pressed down this frame.
     pub fn is_key_down(&self, keycode: &Keycode) -> bool {
         if let Some(key_state_data) = self.keylogger.get(keycode) {
@@ -108,8 +182,37 @@ impl Input {
             && self.is_chord_pressed(keycodes)
     }
 
+    /// Virtually presses the key. From the next frame on, the key acts as though it's being held down until it's
+    /// released with `release_key`.
+    pub fn press_key(&mut self, keycode: Keycode) {
+        self.virtually_pressed_keys.insert(keycode);
+    }
+
+    /// Virtually releases a key pressed with `press_key`. From the next frame on, the key acts as though it's been let
+    /// go, unless it's still being held down on the keyboard.
+    pub fn release_key(&mut self, keycode: Keycode) {
+        self.virtually_pressed_keys.remove(&keycode);
+    }
+
+    /// Virtually presses the key for a single frame. The key is down on the next frame, and released on the frame after.
+    pub fn tap_key(&mut self, keycode: Keycode) {
+        self.virtually_tapped_keys.insert(keycode);
+    }
+
+    /// Every key that's currently pressed, in no particular order.
+    pub(crate) fn pressed_keys(&self) -> Vec<Keycode> {
+        self.keylogger
+            .iter()
+            .filter(|(_, key_state_data)| key_state_data.current_state == KeyState::Down)
+            .map(|(keycode, _)| *keycode)
+            .collect()
+    }
+
     pub(crate) fn update(&mut self) {
-        let current_keys = self.device_state.get_keys();
+        let mut current_keys = self.source.pressed_keys();
+
+        current_keys.extend(self.virtually_pressed_keys.iter());
+        current_keys.extend(self.virtually_tapped_keys.drain());
 
         self.keylogger.iter_mut().for_each(|(_, key_state_data)| {
             key_state_data.prev_state = key_state_data.current_state.clone();
@@ -154,4 +257,56 @@ mod tests {
             assert!(KeyState::Up != KeyState::Down);
         }
     }
+
+    mod test_press_key {
+        use super::*;
+
+        #[test]
+        fn key_is_down_on_the_next_update_and_pressed_until_released() {
+            let mut input = Input::new_disconnected();
+
+            input.press_key(Keycode::Space);
+            assert!(!input.is_key_pressed(&Keycode::Space));
+
+            input.update();
+            assert!(input.is_key_down(&Keycode::Space));
+
+            input.update();
+            assert!(!input.is_key_down(&Keycode::Space));
+            assert!(input.is_key_pressed(&Keycode::Space));
+
+            input.release_key(Keycode::Space);
+            input.update();
+            assert!(input.is_key_up(&Keycode::Space));
+            assert!(!input.is_key_pressed(&Keycode::Space));
+        }
+
+        #[test]
+        fn counts_towards_chords() {
+            let mut input = Input::new_disconnected();
+
+            input.press_key(Keycode::LControl);
+            input.press_key(Keycode::C);
+            input.update();
+
+            assert!(input.is_chord_pressed_exclusively(&[&Keycode::LControl, &Keycode::C]));
+        }
+    }
+
+    mod test_tap_key {
+        use super::*;
+
+        #[test]
+        fn key_is_only_pressed_for_one_update() {
+            let mut input = Input::new_disconnected();
+
+            input.tap_key(Keycode::Enter);
+
+            input.update();
+            assert!(input.is_key_down(&Keycode::Enter));
+
+            input.update();
+            assert!(input.is_key_up(&Keycode::Enter));
+        }
+    }
 }
@@ -1,25 +1,119 @@
-use std::time::Instant;
+use std::time::Duration;
 
-use crate::Component;
+use crate::{Clock, RealClock, Resource, Shared};
 
-#[derive(Component)]
+/// Keeps track of how time is passing in the game. Thomas adds this resource for you and moves it forward at the start
+/// of every frame, so everything that reads it during a frame sees the same values.
+///
+/// Time can be slowed down, sped up, or paused with `set_time_scale` and `pause`. Scaling affects `delta` and
+/// `elapsed` (and the fixed-update event, which runs on scaled time), but not their `unscaled_` counterparts, so things
+/// like menus can keep moving while gameplay is paused.
+#[derive(Resource)]
 pub struct Time {
-    last_frame_time: Instant,
+    clock: Shared<dyn Clock>,
+    delta: Duration,
+    unscaled_delta: Duration,
+    elapsed: Duration,
+    unscaled_elapsed: Duration,
+    frame_count: u64,
+    time_scale: f64,
+    is_paused: bool,
 }
 impl Time {
     pub fn new() -> Self {
-        Time {
-            last_frame_time: Instant::now(),
+        Self::with_clock(RealClock::new())
+    }
+
+    pub(crate) fn with_clock(clock: impl Clock + 'static) -> Self {
+        Self {
+            clock: Shared::new(clock),
+            delta: Duration::ZERO,
+            unscaled_delta: Duration::ZERO,
+            elapsed: Duration::ZERO,
+            unscaled_elapsed: Duration::ZERO,
+            frame_count: 0,
+            time_scale: 1.0,
+            is_paused: false,
         }
     }
 
-    /// The time in milliseconds that's passed since the last update.
+    /// The time in milliseconds that passed during the last frame, after scaling.
     pub fn delta_time(&self) -> u128 {
-        self.last_frame_time.elapsed().as_millis()
+        self.delta.as_millis()
+    }
+
+    /// The time that passed during the last frame, after scaling. This is zero while time is paused.
+    pub fn delta(&self) -> Duration {
+        self.delta
     }
 
-    pub(crate) fn update(&mut self) {
-        self.last_frame_time = Instant::now();
+    /// The time that passed during the last frame, ignoring the time scale and whether time is paused.
+    pub fn unscaled_delta(&self) -> Duration {
+        self.unscaled_delta
+    }
+
+    /// The total scaled time that's passed since the game started.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// The total time that's passed since the game started, ignoring the time scale and pauses.
+    pub fn unscaled_elapsed(&self) -> Duration {
+        self.unscaled_elapsed
+    }
+
+    /// How many frames have started since the game started. This is 1 during the first frame.
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    /// How fast time passes relative to the clock. 1.0 is normal speed, 0.5 is half speed, and so on.
+    pub fn time_scale(&self) -> f64 {
+        self.time_scale
+    }
+
+    /// Sets how fast time passes relative to the clock. The new scale takes effect from the next frame.
+    ///
+    /// # Panics
+    /// If `time_scale` is negative or isn't finite.
+    pub fn set_time_scale(&mut self, time_scale: f64) {
+        if !time_scale.is_finite() || time_scale < 0.0 {
+            panic!("Time scale must be a finite, non-negative number, but got {time_scale}.");
+        }
+
+        self.time_scale = time_scale;
+    }
+
+    /// Stops scaled time from passing until `resume` is called. The pause takes effect from the next frame.
+    pub fn pause(&mut self) {
+        self.is_paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.is_paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.is_paused
+    }
+
+    /// The clock the game is running on. Give it to `Timer::with_clock` to make timers that keep time with the game.
+    pub fn clock(&self) -> Shared<dyn Clock> {
+        Shared::clone(&self.clock)
+    }
+
+    /// Moves on to the next frame, treating `frame_duration` as the time that passed during the last one.
+    pub(crate) fn advance(&mut self, frame_duration: Duration) {
+        self.unscaled_delta = frame_duration;
+        self.delta = if self.is_paused {
+            Duration::ZERO
+        } else {
+            frame_duration.mul_f64(self.time_scale)
+        };
+
+        self.unscaled_elapsed += self.unscaled_delta;
+        self.elapsed += self.delta;
+        self.frame_count += 1;
     }
 }
 
@@ -27,20 +121,62 @@ impl Time {
 mod tests {
     use super::*;
 
-    mod delta_time {
+    mod test_advance {
         use super::*;
 
-        use std::{thread, time::Duration};
+        #[test]
+        fn keeps_sub_millisecond_precision() {
+            let mut time = Time::new();
+
+            time.advance(Duration::from_micros(16_667));
+
+            assert_eq!(time.delta(), Duration::from_micros(16_667));
+            assert_eq!(time.delta_time(), 16);
+        }
+
+        #[test]
+        fn accumulates_elapsed_time_and_frames() {
+            let mut time = Time::new();
+
+            time.advance(Duration::from_millis(10));
+            time.advance(Duration::from_millis(15));
+
+            assert_eq!(time.elapsed(), Duration::from_millis(25));
+            assert_eq!(time.frame_count(), 2);
+        }
+
+        #[test]
+        fn applies_the_time_scale() {
+            let mut time = Time::new();
+
+            time.set_time_scale(0.5);
+            time.advance(Duration::from_millis(10));
+
+            assert_eq!(time.delta(), Duration::from_millis(5));
+            assert_eq!(time.unscaled_delta(), Duration::from_millis(10));
+        }
 
         #[test]
-        fn has_difference_after_elapsed_time() {
+        fn scaled_time_does_not_pass_while_paused() {
             let mut time = Time::new();
 
-            time.update();
+            time.advance(Duration::from_millis(10));
+            time.pause();
+            time.advance(Duration::from_millis(10));
+
+            assert_eq!(time.delta(), Duration::ZERO);
+            assert_eq!(time.elapsed(), Duration::from_millis(10));
+            assert_eq!(time.unscaled_elapsed(), Duration::from_millis(20));
+        }
+    }
 
-            thread::sleep(Duration::from_millis(5));
+    mod test_set_time_scale {
+        use super::*;
 
-            assert!(time.delta_time() >= 5);
+        #[test]
+        #[should_panic]
+        fn panics_on_a_negative_scale() {
+            Time::new().set_time_scale(-1.0);
         }
     }
 }
@@ -4,5 +4,11 @@ pub use comp_input::*;
 mod comp_time;
 pub use comp_time::*;
 
+mod comp_fixed_time;
+pub use comp_fixed_time::*;
+
+mod comp_frame_pacer;
+pub use comp_frame_pacer::*;
+
 mod comp_engine_stats;
 pub use comp_engine_stats::*;
\ No newline at end of file
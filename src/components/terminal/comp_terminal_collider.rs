@@ -2,6 +2,8 @@ use crate::{Component, Layer};
 
 /// Marks that an entity in the world is capable of colliding with other `TerminalCollider`s.
 #[derive(Component, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[component(serialize)]
 pub struct TerminalCollider {
   /// The collision layer this collider is on. The layer can be used by a collision processing system to know
   /// what two kinds of things are colliding.
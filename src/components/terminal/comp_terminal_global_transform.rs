@@ -0,0 +1,14 @@
+use crate::{Component, IntCoords2d};
+
+/// Where a child entity actually is in the terminal world. A child's `TerminalTransform` is its offset from its parent;
+/// Thomas adds this component to every child and keeps it up to date with the child's world position before the
+/// terminal renderer draws each frame.
+#[derive(Component, Debug)]
+pub struct GlobalTerminalTransform {
+    pub(crate) coords: IntCoords2d,
+}
+impl GlobalTerminalTransform {
+    pub fn coords(&self) -> &IntCoords2d {
+        &self.coords
+    }
+}
@@ -4,6 +4,8 @@ use crate::Rgb;
 
 /// Data to describe how to render something in the terminal.
 #[derive(Component, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[component(serialize)]
 pub struct TerminalRenderer {
     pub display: char,
     pub layer: Layer,
@@ -1,7 +1,27 @@
-use crate::{Component, IntCoords2d};
+use crate::{Component, GlobalTerminalTransform, IntCoords2d, StoredComponentList};
 
 /// Positional data for a world in the terminal where it's 2D and strictly gridded.
+///
+/// If the entity is a child of another entity, its `coords` are its offset from its parent rather than its position in
+/// the world.
 #[derive(Component, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[component(serialize)]
 pub struct TerminalTransform {
   pub coords: IntCoords2d,
-}
\ No newline at end of file
+}
+impl TerminalTransform {
+  /// Where an entity is in the world, given its components. For a child, this is its `GlobalTerminalTransform`;
+  /// otherwise, it's its `TerminalTransform`. Include `GlobalTerminalTransform` in your query with `has_optional` so
+  /// children are positioned correctly.
+  ///
+  /// # Panics
+  /// If the components have neither a `GlobalTerminalTransform` nor a `TerminalTransform`.
+  pub fn world_coords(components: &StoredComponentList) -> IntCoords2d {
+    if let Some(global_transform) = components.try_get::<GlobalTerminalTransform>() {
+      return global_transform.coords;
+    }
+
+    components.get::<TerminalTransform>().coords
+  }
+}
@@ -6,3 +6,6 @@ pub use comp_terminal_collider::*;
 
 mod comp_terminal_transform;
 pub use comp_terminal_transform::*;
+
+mod comp_terminal_global_transform;
+pub use comp_terminal_global_transform::*;
@@ -5,6 +5,8 @@ use crate::{Component, UiAnchor, Alignment, IntCoords2d, Rgb};
 /// This text is positioned relative to a camera (like the main camera). If you want text that has a fixed position
 /// in the world, use `WorldText`.
 #[derive(Component, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[component(serialize)]
 pub struct Text {
   pub value: String,
   pub anchor: UiAnchor,
@@ -4,6 +4,8 @@ use crate::{Alignment, Component, IntCoords2d, Rgb};
 /// 
 /// If you want text that's rendered in a camera's screen space, use `Text`.
 #[derive(Component)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[component(serialize)]
 pub struct WorldText {
     pub value: String,
     pub justification: Alignment,
@@ -0,0 +1,286 @@
+use std::{any::TypeId, collections::HashMap};
+
+use crate::{ComponentTicks, Entity, Shared, StoredComponent, TrackedComponent};
+
+/// An `Archetype` is a table of every entity in the world that has exactly the same set of components. Each
+/// component in the set gets its own column, and each entity in the archetype is a row across those columns.
+///
+/// Grouping entities this way means a query only needs to decide whether an _archetype_ matches, after which every
+/// entity in that archetype is a candidate without any further lookups.
+pub(crate) struct Archetype {
+    entities: Vec<Entity>,
+    columns: HashMap<TypeId, Vec<TrackedComponent>>,
+}
+impl Archetype {
+    /// Makes a new, empty `Archetype` for the provided set of component types.
+    pub fn new(component_ids: Vec<TypeId>) -> Self {
+        let columns = component_ids
+            .iter()
+            .map(|component_id| (*component_id, vec![]))
+            .collect();
+
+        Self {
+            entities: vec![],
+            columns,
+        }
+    }
+
+    pub fn entities(&self) -> &Vec<Entity> {
+        &self.entities
+    }
+
+    pub fn has_component(&self, component_id: &TypeId) -> bool {
+        self.columns.contains_key(component_id)
+    }
+
+    /// Whether every one of the provided components is part of this archetype.
+    pub fn has_all_components(&self, component_ids: &[TypeId]) -> bool {
+        component_ids
+            .iter()
+            .all(|component_id| self.has_component(component_id))
+    }
+
+    /// Whether at least one of the provided components is part of this archetype.
+    pub fn has_any_components(&self, component_ids: &[TypeId]) -> bool {
+        component_ids
+            .iter()
+            .any(|component_id| self.has_component(component_id))
+    }
+
+    /// Every component of the entity in the row.
+    pub fn get_components_in_row(&self, row: usize) -> Vec<&StoredComponent> {
+        self.columns
+            .values()
+            .filter_map(|column| column.get(row))
+            .map(|tracked_component| &tracked_component.component)
+            .collect()
+    }
+
+    pub fn get_component(&self, row: usize, component_id: &TypeId) -> Option<&StoredComponent> {
+        self.get_tracked_component(row, component_id)
+            .map(|tracked_component| &tracked_component.component)
+    }
+
+    pub fn get_component_ticks(
+        &self,
+        row: usize,
+        component_id: &TypeId,
+    ) -> Option<&Shared<ComponentTicks>> {
+        self.get_tracked_component(row, component_id)
+            .map(|tracked_component| &tracked_component.ticks)
+    }
+
+    pub fn get_tracked_component(
+        &self,
+        row: usize,
+        component_id: &TypeId,
+    ) -> Option<&TrackedComponent> {
+        if let Some(column) = self.columns.get(component_id) {
+            return column.get(row);
+        }
+
+        None
+    }
+
+    /// Adds a row for the entity to the archetype, returning the row the entity now occupies.
+    ///
+    /// # Panics
+    /// If the provided components don't exactly match the components of the archetype.
+    pub fn push(
+        &mut self,
+        entity: Entity,
+        mut components: HashMap<TypeId, TrackedComponent>,
+    ) -> usize {
+        assert!(
+            components.len() == self.columns.len(),
+            "Entity's components must match the archetype's components exactly."
+        );
+
+        for (component_id, column) in &mut self.columns {
+            column.push(components.remove(component_id).unwrap_or_else(|| {
+                panic!(
+                    "Entity has component {:?} required by the archetype.",
+                    component_id
+                )
+            }));
+        }
+
+        self.entities.push(entity);
+
+        self.entities.len() - 1
+    }
+
+    /// Removes the row from the archetype, giving back the components that were in it. To keep the columns packed,
+    /// the last row in the archetype is moved into the removed row. If that happens, the entity that was moved is
+    /// also returned so its location can be updated.
+    pub fn swap_remove(
+        &mut self,
+        row: usize,
+    ) -> (HashMap<TypeId, TrackedComponent>, Option<Entity>) {
+        let components = self
+            .columns
+            .iter_mut()
+            .map(|(component_id, column)| (*component_id, column.swap_remove(row)))
+            .collect();
+
+        self.entities.swap_remove(row);
+
+        (components, self.entities.get(row).copied())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Component, SharedCell};
+
+    #[derive(Component)]
+    struct TestComponent {
+        prop1: u8,
+    }
+
+    #[derive(Component)]
+    struct EmptyComponent {}
+
+    fn make_components(prop1: u8) -> HashMap<TypeId, TrackedComponent> {
+        HashMap::from([
+            (
+                TestComponent::id(),
+                TrackedComponent::new(
+                    Shared::new(SharedCell::new(
+                        Box::new(TestComponent { prop1 }) as Box<dyn Component>
+                    )),
+                    0,
+                ),
+            ),
+            (
+                EmptyComponent::id(),
+                TrackedComponent::new(
+                    Shared::new(SharedCell::new(
+                        Box::new(EmptyComponent {}) as Box<dyn Component>
+                    )),
+                    0,
+                ),
+            ),
+        ])
+    }
+
+    fn make_archetype() -> Archetype {
+        Archetype::new(vec![TestComponent::id(), EmptyComponent::id()])
+    }
+
+    mod test_has_all_components {
+        use super::*;
+
+        #[test]
+        fn is_true_when_all_components_are_present() {
+            let archetype = make_archetype();
+
+            assert!(archetype.has_all_components(&[TestComponent::id(), EmptyComponent::id()]));
+        }
+
+        #[test]
+        fn is_false_when_any_component_is_missing() {
+            let archetype = Archetype::new(vec![TestComponent::id()]);
+
+            assert!(!archetype.has_all_components(&[TestComponent::id(), EmptyComponent::id()]));
+        }
+    }
+
+    mod test_has_any_components {
+        use super::*;
+
+        #[test]
+        fn is_true_when_one_component_is_present() {
+            let archetype = Archetype::new(vec![TestComponent::id()]);
+
+            assert!(archetype.has_any_components(&[TestComponent::id(), EmptyComponent::id()]));
+        }
+
+        #[test]
+        fn is_false_when_no_components_are_present() {
+            let archetype = Archetype::new(vec![]);
+
+            assert!(!archetype.has_any_components(&[TestComponent::id(), EmptyComponent::id()]));
+        }
+    }
+
+    mod test_push {
+        use super::*;
+
+        #[test]
+        fn returns_the_row_of_the_added_entity() {
+            let mut archetype = make_archetype();
+
+            assert_eq!(archetype.push(Entity(0, 0), make_components(1)), 0);
+            assert_eq!(archetype.push(Entity(1, 0), make_components(2)), 1);
+            assert_eq!(archetype.entities().len(), 2);
+        }
+
+        #[test]
+        #[should_panic(
+            expected = "Entity's components must match the archetype's components exactly."
+        )]
+        fn panics_when_the_components_do_not_match_the_archetype() {
+            let mut archetype = Archetype::new(vec![TestComponent::id()]);
+
+            archetype.push(Entity(0, 0), make_components(1));
+        }
+    }
+
+    mod test_swap_remove {
+        use super::*;
+
+        #[test]
+        fn gives_back_the_removed_components() {
+            let mut archetype = make_archetype();
+
+            archetype.push(Entity(0, 0), make_components(1));
+
+            let (components, moved_entity) = archetype.swap_remove(0);
+
+            assert!(moved_entity.is_none());
+            assert_eq!(archetype.entities().len(), 0);
+            assert_eq!(components.len(), 2);
+            assert_eq!(
+                TestComponent::cast(
+                    components
+                        .get(&TestComponent::id())
+                        .unwrap()
+                        .component
+                        .borrow()
+                        .as_ref()
+                )
+                .unwrap()
+                .prop1,
+                1
+            );
+        }
+
+        #[test]
+        fn moves_the_last_row_into_the_removed_row() {
+            let mut archetype = make_archetype();
+
+            archetype.push(Entity(0, 0), make_components(1));
+            archetype.push(Entity(1, 0), make_components(2));
+            archetype.push(Entity(2, 0), make_components(3));
+
+            let (_, moved_entity) = archetype.swap_remove(0);
+
+            assert_eq!(moved_entity, Some(Entity(2, 0)));
+            assert_eq!(archetype.entities()[0], Entity(2, 0));
+            assert_eq!(
+                TestComponent::cast(
+                    archetype
+                        .get_component(0, &TestComponent::id())
+                        .unwrap()
+                        .borrow()
+                        .as_ref()
+                )
+                .unwrap()
+                .prop1,
+                3
+            );
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::{Entity, Shared, StoredComponent};
+
+/// Tracks when a component was added to its entity and when it was last changed. Both are recorded as change ticks:
+/// a counter that moves forward every time a `System` runs and every time the command queue is processed.
+pub(crate) struct ComponentTicks {
+    added: AtomicU64,
+    changed: AtomicU64,
+}
+impl ComponentTicks {
+    pub fn new(tick: u64) -> Self {
+        Self {
+            added: AtomicU64::new(tick),
+            changed: AtomicU64::new(tick),
+        }
+    }
+
+    pub fn is_added_since(&self, tick: u64) -> bool {
+        self.added.load(Ordering::Relaxed) > tick
+    }
+
+    pub fn is_changed_since(&self, tick: u64) -> bool {
+        self.changed.load(Ordering::Relaxed) > tick
+    }
+
+    pub fn set_changed(&self, tick: u64) {
+        self.changed.store(tick, Ordering::Relaxed);
+    }
+}
+
+/// A component as it's stored in the world, alongside its change ticks.
+#[derive(Clone)]
+pub(crate) struct TrackedComponent {
+    pub component: StoredComponent,
+    pub ticks: Shared<ComponentTicks>,
+}
+impl TrackedComponent {
+    pub fn new(component: StoredComponent, tick: u64) -> Self {
+        Self {
+            component,
+            ticks: Shared::new(ComponentTicks::new(tick)),
+        }
+    }
+}
+
+/// A component that was removed from an entity, either directly or because its entity was destroyed.
+pub(crate) struct RemovedComponent {
+    pub entity: Entity,
+    pub component: StoredComponent,
+    pub removed_tick: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod test_is_added_since {
+        use super::*;
+
+        #[test]
+        fn is_true_when_added_after_the_tick() {
+            assert!(ComponentTicks::new(5).is_added_since(4));
+        }
+
+        #[test]
+        fn is_false_when_added_at_or_before_the_tick() {
+            assert!(!ComponentTicks::new(5).is_added_since(5));
+            assert!(!ComponentTicks::new(5).is_added_since(6));
+        }
+    }
+
+    mod test_set_changed {
+        use super::*;
+
+        #[test]
+        fn marks_the_component_as_changed_without_affecting_when_it_was_added() {
+            let ticks = ComponentTicks::new(2);
+
+            ticks.set_changed(7);
+
+            assert!(ticks.is_changed_since(6));
+            assert!(!ticks.is_changed_since(7));
+            assert!(!ticks.is_added_since(2));
+        }
+    }
+}
@@ -1,11 +1,13 @@
-use std::any::Any;
+use std::any::{Any, TypeId};
+
+use crate::{FieldInfo, FieldValue, ParallelSafe};
 
 /// A `Component` is essentially a data bucket and is one of the core aspects of ECS. A `Component` houses _no_ logic and
 /// is simply meant to be a repository for related data. In Thomas, you should never be implementing the `Component` trait
 /// directly, but rather deriving it to create your own custom components:
 /// ```
 /// use thomas::Component;
-/// 
+///
 /// #[derive(Component)]
 /// pub struct Player {
 ///     pub is_main_player: bool,
@@ -14,8 +16,51 @@ use std::any::Any;
 /// In general, `Component`s should largely be open so that they can be mutated by `System`s. However, your design is
 /// ultimately up to you. You may find it useful to hide some details of a complex component to force controlled mutation
 /// facilitated by a method on your custom component.
-pub trait Component {
+///
+/// Components can be generic, and can be enums as well as structs:
+/// ```
+/// use thomas::Component;
+///
+/// #[derive(Component)]
+/// pub struct Health<T> {
+///     pub value: T,
+/// }
+///
+/// #[derive(Component)]
+/// pub enum Faction {
+///     Friendly,
+///     Hostile,
+/// }
+/// ```
+/// Components are told apart by their type, so `Health<u32>` and `Health<f32>` are different components, as are
+/// two components with the same name in different modules.
+///
+/// Deriving `Component` also lets tools like inspectors and debug consoles work with any component's fields without
+/// knowing its type, through `fields`, `field` and `set_field`. Fields whose types implement `ReflectField`, which
+/// includes numbers, `bool`, `char`, `String`, coordinates, `Layer`, `Rgb` and `Option`s of them, can be read and
+/// written this way:
+/// ```
+/// use thomas::{Component, FieldValue};
+///
+/// #[derive(Component)]
+/// pub struct Health {
+///     pub value: u32,
+/// }
+///
+/// let mut health = Health { value: 10 };
+///
+/// health.set_field("value", FieldValue::UInt(5));
+///
+/// assert_eq!(health.field("value"), Some(FieldValue::UInt(5)));
+/// ```
+pub trait Component: ParallelSafe {
+    /// The full type name of the component, including its module path and any type arguments, meant for diagnostics.
+    /// Use `id` to tell components apart.
     fn name() -> &'static str
+    where
+        Self: Sized;
+    /// The unique identity of the component type.
+    fn id() -> TypeId
     where
         Self: Sized;
     fn is_component_type(comp: &dyn Component) -> bool
@@ -29,6 +74,183 @@ pub trait Component {
         Self: Sized;
 
     fn component_name(&self) -> &'static str;
+    fn component_id(&self) -> TypeId;
     fn as_any(&self) -> &dyn Any;
     fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    /// The component's fields, in the order they're declared. Enums don't list any fields.
+    fn fields(&self) -> &'static [FieldInfo];
+    /// The value of the named field. Gives `None` if there's no field with the name, or its type doesn't implement
+    /// `ReflectField`.
+    fn field(&self, name: &str) -> Option<FieldValue>;
+    /// Sets the named field to the value. Gives whether the field was set, which it isn't if there's no field with the
+    /// name, its type doesn't implement `ReflectField`, or the value can't be made into its type.
+    fn set_field(&mut self, name: &str, value: FieldValue) -> bool;
+
+    /// Serializes the component for a `WorldSnapshot`. Gives `None` for components that can't be serialized. Mark a
+    /// component with `#[component(serialize)]` when deriving `Component` to make it serializable; it also needs to
+    /// implement serde's `Serialize` and `Deserialize`.
+    #[cfg(feature = "serde")]
+    fn to_snapshot_value(&self) -> Option<crate::SnapshotResult<crate::SnapshotValue>> {
+        None
+    }
+}
+
+/// Strips the module paths out of a type name, including those of any type arguments, so
+/// `my_game::stats::Health<my_game::stats::Points>` becomes `Health<Points>`.
+pub(crate) fn short_type_name(type_name: &str) -> String {
+    let mut short_name = String::new();
+    let mut path = String::new();
+
+    for character in type_name.chars() {
+        if character.is_alphanumeric() || character == '_' || character == ':' {
+            path.push(character);
+        } else {
+            short_name.push_str(path.rsplit("::").next().unwrap_or_default());
+            path.clear();
+            short_name.push(character);
+        }
+    }
+
+    short_name.push_str(path.rsplit("::").next().unwrap_or_default());
+
+    short_name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::short_type_name;
+    use crate::Component;
+
+    #[derive(Component)]
+    struct Health<T> {
+        value: T,
+    }
+
+    #[derive(Component)]
+    enum Faction {
+        Friendly,
+        Hostile,
+    }
+
+    mod first {
+        use crate::Component;
+
+        #[derive(Component)]
+        pub struct Position {}
+    }
+
+    mod second {
+        use crate::Component;
+
+        #[derive(Component)]
+        pub struct Position {}
+    }
+
+    mod test_id {
+        use super::*;
+
+        #[test]
+        fn is_different_for_components_with_the_same_name_in_different_modules() {
+            assert_ne!(first::Position::id(), second::Position::id());
+        }
+
+        #[test]
+        fn is_different_for_different_type_arguments_of_a_generic_component() {
+            assert_ne!(Health::<u32>::id(), Health::<f32>::id());
+        }
+
+        #[test]
+        fn matches_the_component_id_of_an_instance() {
+            assert_eq!(Health { value: 5 }.component_id(), Health::<i32>::id());
+            assert_eq!(Faction::Hostile.component_id(), Faction::id());
+        }
+    }
+
+    mod test_name {
+        use super::*;
+
+        #[test]
+        fn includes_the_module_path() {
+            assert_eq!(
+                first::Position::name(),
+                "thomas::core::component::tests::first::Position"
+            );
+            assert_ne!(first::Position::name(), second::Position::name());
+        }
+
+        #[test]
+        fn includes_the_type_arguments_of_a_generic_component() {
+            assert_eq!(
+                Health::<u32>::name(),
+                "thomas::core::component::tests::Health<u32>"
+            );
+            assert_ne!(Health::<u32>::name(), Health::<i32>::name());
+        }
+
+        #[test]
+        fn matches_the_component_name_of_an_instance() {
+            assert_eq!(
+                Health { value: 5_u8 }.component_name(),
+                Health::<u8>::name()
+            );
+        }
+    }
+
+    mod test_short_type_name {
+        use super::*;
+
+        #[test]
+        fn strips_the_module_path() {
+            assert_eq!(short_type_name(first::Position::name()), "Position");
+        }
+
+        #[test]
+        fn strips_the_module_paths_of_type_arguments() {
+            assert_eq!(
+                short_type_name("my_game::Health<my_game::stats::Points, u32>"),
+                "Health<Points, u32>"
+            );
+        }
+
+        #[test]
+        fn leaves_names_without_a_path_alone() {
+            assert_eq!(short_type_name("Faction"), "Faction");
+        }
+    }
+
+    mod test_cast {
+        use super::*;
+
+        #[test]
+        fn works_for_generic_components() {
+            let comp: Box<dyn Component> = Box::new(Health { value: 10_u8 });
+
+            assert!(Health::<u8>::cast(comp.as_ref()).is_some());
+            assert_eq!(Health::<u8>::cast(comp.as_ref()).unwrap().value, 10);
+            assert!(Health::<u16>::cast(comp.as_ref()).is_none());
+        }
+
+        #[test]
+        fn works_for_enum_components() {
+            let comp: Box<dyn Component> = Box::new(Faction::Friendly);
+
+            assert!(matches!(
+                Faction::cast(comp.as_ref()),
+                Some(Faction::Friendly)
+            ));
+        }
+    }
+
+    mod test_is_component_type {
+        use super::*;
+
+        #[test]
+        fn is_false_for_a_same_named_component_from_another_module() {
+            let comp: Box<dyn Component> = Box::new(first::Position {});
+
+            assert!(first::Position::is_component_type(comp.as_ref()));
+            assert!(!second::Position::is_component_type(comp.as_ref()));
+        }
+    }
 }
@@ -0,0 +1,152 @@
+use std::{any::TypeId, collections::HashMap};
+
+use serde::de::DeserializeOwned;
+
+use crate::{
+    Component, Identity, SnapshotError, SnapshotResult, SnapshotValue, TerminalCollider,
+    TerminalRenderer, TerminalTransform, Text, Transform, Transform2d, WorldText,
+};
+
+type DeserializeComponentFn = fn(SnapshotValue) -> SnapshotResult<Box<dyn Component>>;
+
+/// Keeps track of the components that can be read back out of snapshots and prefabs. Each registered component has a
+/// name that identifies it in saved data, and a way to turn that data back into the component.
+///
+/// Thomas's own serializable components are registered from the start.
+pub(crate) struct ComponentRegistry {
+    names: HashMap<TypeId, String>,
+    deserializers: HashMap<String, DeserializeComponentFn>,
+}
+impl ComponentRegistry {
+    pub fn new() -> Self {
+        let mut registry = Self {
+            names: HashMap::new(),
+            deserializers: HashMap::new(),
+        };
+
+        registry.register::<Identity>("Identity");
+        registry.register::<Transform>("Transform");
+        registry.register::<Transform2d>("Transform2d");
+        registry.register::<TerminalTransform>("TerminalTransform");
+        registry.register::<TerminalRenderer>("TerminalRenderer");
+        registry.register::<TerminalCollider>("TerminalCollider");
+        registry.register::<Text>("Text");
+        registry.register::<WorldText>("WorldText");
+
+        registry
+    }
+
+    /// Registers the component under the provided name.
+    ///
+    /// # Panics
+    /// If a different type of component is already registered under the name.
+    pub fn register<T>(&mut self, name: &str)
+    where
+        T: Component + DeserializeOwned + 'static,
+    {
+        if self
+            .names
+            .iter()
+            .any(|(component_id, existing_name)| existing_name == name && *component_id != T::id())
+        {
+            panic!(
+                "A different component is already registered with the name {name}. Give one of them its own name with \
+                 Game::register_component_with_name."
+            );
+        }
+
+        self.names.insert(T::id(), name.to_string());
+        self.deserializers
+            .insert(name.to_string(), deserialize_component::<T>);
+    }
+
+    /// The name the component was registered with, if it was registered.
+    pub fn name_of(&self, component_id: &TypeId) -> Option<&str> {
+        self.names.get(component_id).map(|name| name.as_str())
+    }
+
+    /// Turns the value back into the component registered with the name.
+    pub fn deserialize(
+        &self,
+        name: &str,
+        value: SnapshotValue,
+    ) -> SnapshotResult<Box<dyn Component>> {
+        let deserialize = self.deserializers.get(name).ok_or_else(|| {
+            SnapshotError::new(format!("No component is registered with the name {name}."))
+        })?;
+
+        deserialize(value)
+    }
+}
+
+fn deserialize_component<T>(value: SnapshotValue) -> SnapshotResult<Box<dyn Component>>
+where
+    T: Component + DeserializeOwned + 'static,
+{
+    Ok(Box::new(serde_json::from_value::<T>(value)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Component;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Component, Serialize, Deserialize, Debug, PartialEq)]
+    #[component(serialize)]
+    struct Health {
+        value: u32,
+    }
+
+    #[derive(Component, Serialize, Deserialize)]
+    #[component(serialize)]
+    struct Mana {
+        value: u32,
+    }
+
+    mod test_register {
+        use super::*;
+
+        #[test]
+        fn registering_the_same_component_again_is_allowed() {
+            let mut registry = ComponentRegistry::new();
+
+            registry.register::<Health>("health");
+            registry.register::<Health>("health");
+
+            assert_eq!(registry.name_of(&Health::id()), Some("health"));
+        }
+
+        #[test]
+        #[should_panic]
+        fn panics_when_the_name_is_taken_by_another_component() {
+            let mut registry = ComponentRegistry::new();
+
+            registry.register::<Health>("stat");
+            registry.register::<Mana>("stat");
+        }
+    }
+
+    mod test_deserialize {
+        use super::*;
+
+        #[test]
+        fn gives_back_the_serialized_component() {
+            let mut registry = ComponentRegistry::new();
+
+            registry.register::<Health>("health");
+
+            let value = Health { value: 7 }.to_snapshot_value().unwrap().unwrap();
+            let component = registry.deserialize("health", value).unwrap();
+
+            assert_eq!(Health::cast(component.as_ref()), Some(&Health { value: 7 }));
+        }
+
+        #[test]
+        fn fails_for_unregistered_names() {
+            let registry = ComponentRegistry::new();
+
+            assert!(registry.deserialize("health", SnapshotValue::Null).is_err());
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use std::{
+    sync::{Arc, Mutex, MutexGuard},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{ParallelSafe, Shared};
+
+/// How close to a wake-up time the `RealClock` stops sleeping and starts spinning. Sleeping can overshoot by a little
+/// depending on the OS scheduler, so the last sliver of the wait is spun out precisely instead.
+pub const REAL_CLOCK_SPIN_THRESHOLD: Duration = Duration::from_millis(2);
+
+/// A source of time. Everything in Thomas that measures time (`Time`, `Timer`, and the `FramePacer`) reads it from a
+/// `Clock`, so swapping the clock changes how time passes for the whole game.
+///
+/// Times given by a clock are measured from whenever the clock started, and only mean something relative to other
+/// times from the same clock.
+pub trait Clock: ParallelSafe {
+    /// How much time has passed since the clock started.
+    fn now(&self) -> Duration;
+
+    /// Waits until the clock reads at least `time`. Returns right away if that time has already passed.
+    fn wait_until(&self, time: Duration);
+}
+
+impl<C: Clock + ?Sized> Clock for Shared<C> {
+    fn now(&self) -> Duration {
+        (**self).now()
+    }
+
+    fn wait_until(&self, time: Duration) {
+        (**self).wait_until(time)
+    }
+}
+
+/// A `Clock` that follows real time. This is the clock games use unless they're given another with
+/// `Game::with_clock`.
+pub struct RealClock {
+    start_time: Instant,
+}
+impl RealClock {
+    pub fn new() -> Self {
+        Self {
+            start_time: Instant::now(),
+        }
+    }
+}
+impl Clock for RealClock {
+    fn now(&self) -> Duration {
+        self.start_time.elapsed()
+    }
+
+    /// Sleeps for most of the wait, and spins for the last `REAL_CLOCK_SPIN_THRESHOLD` of it so the wait doesn't keep
+    /// a CPU core busy, but still ends on time.
+    fn wait_until(&self, time: Duration) {
+        let remaining_time = time.saturating_sub(self.now());
+
+        if remaining_time > REAL_CLOCK_SPIN_THRESHOLD {
+            thread::sleep(remaining_time - REAL_CLOCK_SPIN_THRESHOLD);
+        }
+
+        while self.now() < time {
+            std::hint::spin_loop();
+        }
+    }
+}
+impl Default for RealClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A `Clock` that only moves forward when it's told to. Waiting on a `ManualClock` jumps it straight to the time
+/// being waited for, so a game running on one plays out exactly the same way every time, as fast as it can.
+///
+/// Clones of a `ManualClock` share the same time, so you can hold onto a clone to move the clock along after giving it
+/// to a `Game`:
+/// ```
+/// use std::time::Duration;
+/// use thomas::{Clock, ManualClock, Timer};
+///
+/// let clock = ManualClock::new();
+/// let mut timer = Timer::with_clock(clock.clone());
+///
+/// timer.start();
+/// clock.advance(Duration::from_millis(250));
+///
+/// assert_eq!(timer.elapsed(), Duration::from_millis(250));
+/// assert_eq!(clock.now(), Duration::from_millis(250));
+/// ```
+#[derive(Clone)]
+pub struct ManualClock {
+    now: Arc<Mutex<Duration>>,
+}
+impl ManualClock {
+    /// Makes a clock that reads zero until it's moved forward.
+    pub fn new() -> Self {
+        Self {
+            now: Arc::new(Mutex::new(Duration::ZERO)),
+        }
+    }
+
+    /// Moves the clock forward by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        *self.lock_now() += duration;
+    }
+
+    fn lock_now(&self) -> MutexGuard<'_, Duration> {
+        self.now
+            .lock()
+            .expect("The clock's time can always be read.")
+    }
+}
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        *self.lock_now()
+    }
+
+    fn wait_until(&self, time: Duration) {
+        let mut now = self.lock_now();
+
+        if time > *now {
+            *now = time;
+        }
+    }
+}
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod test_real_clock {
+        use super::*;
+
+        #[test]
+        fn wait_until_waits_for_the_time_to_pass() {
+            let clock = RealClock::new();
+
+            clock.wait_until(Duration::from_millis(5));
+
+            assert!(clock.now() >= Duration::from_millis(5));
+        }
+    }
+
+    mod test_manual_clock {
+        use super::*;
+
+        #[test]
+        fn clones_share_the_same_time() {
+            let clock = ManualClock::new();
+            let clone = clock.clone();
+
+            clone.advance(Duration::from_millis(3));
+
+            assert_eq!(clock.now(), Duration::from_millis(3));
+        }
+
+        #[test]
+        fn wait_until_jumps_to_the_time() {
+            let clock = ManualClock::new();
+
+            clock.wait_until(Duration::from_millis(7));
+
+            assert_eq!(clock.now(), Duration::from_millis(7));
+        }
+
+        #[test]
+        fn wait_until_does_not_go_backward() {
+            let clock = ManualClock::new();
+
+            clock.advance(Duration::from_millis(10));
+            clock.wait_until(Duration::from_millis(4));
+
+            assert_eq!(clock.now(), Duration::from_millis(10));
+        }
+    }
+}
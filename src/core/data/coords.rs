@@ -1,6 +1,7 @@
 use std::ops::{Add, AddAssign, Sub, SubAssign};
 
 #[derive(Copy, Clone, PartialEq, PartialOrd, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct IntCoords2d {
     x: i64,
     y: i64,
@@ -199,6 +200,7 @@ impl SubAssign for IntCoords3d {
 }
 
 #[derive(Copy, Clone, PartialEq, PartialOrd, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Coords2d {
     x: f64,
     y: f64,
@@ -281,6 +283,7 @@ impl SubAssign for Coords2d {
 }
 
 #[derive(Copy, Clone, PartialEq, PartialOrd, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Coords3d {
     coords2d: Coords2d,
     z: f64,
@@ -3,6 +3,7 @@
 /// In the context of 2D rendering, layers can define what should be visible if two things are occupying the same
 /// space.
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Layer(pub i32);
 impl Layer {
     pub fn base() -> Self {
@@ -16,5 +16,8 @@ pub use priority::*;
 mod ui;
 pub use ui::*;
 
+mod clock;
+pub use clock::*;
+
 mod timer;
 pub use timer::*;
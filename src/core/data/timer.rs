@@ -1,33 +1,44 @@
-use std::time::Instant;
+use std::time::Duration;
 
-/// A way to track the passage of real time.
+use crate::{Clock, RealClock, Shared};
+
+/// A way to track the passage of time. Timers follow real time unless they're made with `with_clock`.
 #[derive(Clone)]
 pub struct Timer {
-    start_time: Instant,
+    clock: Shared<dyn Clock>,
+    start_time: Duration,
     is_running: bool,
 }
 impl Timer {
     /// Creates a new `Timer` instance that isn't started. A `Timer` must be started before it'll give any readings
     /// on elapsed time.
     pub fn new() -> Self {
-        Self {
-            start_time: Instant::now(),
-            is_running: false,
-        }
+        Self::with_clock(RealClock::new())
     }
 
     /// Creates a new `Timer` and starts it.
     pub fn start_new() -> Self {
+        let mut timer = Self::new();
+
+        timer.start();
+
+        timer
+    }
+
+    /// Creates a new `Timer` that isn't started and measures time with the provided clock. To have a timer keep time
+    /// with the game, give it the game's clock from `Time::clock`.
+    pub fn with_clock(clock: impl Clock + 'static) -> Self {
         Self {
-            start_time: Instant::now(),
-            is_running: true,
+            start_time: clock.now(),
+            clock: Shared::new(clock),
+            is_running: false,
         }
     }
 
     /// Starts the timer. This must be done before the timer will start giving you measured
     /// time on calls to elapsed methods. Has no effect on a timer that's already running.
     pub fn start(&mut self) {
-        self.start_time = Instant::now();
+        self.start_time = self.clock.now();
         self.is_running = true;
     }
 
@@ -39,24 +50,24 @@ impl Timer {
     /// Resets the timer such that its elapsed time at the moment of this call would be 0.
     /// The timer continues to run after this call.
     pub fn restart(&mut self) {
-        self.start_time = Instant::now();
+        self.start_time = self.clock.now();
         self.is_running = true;
     }
 
-    pub fn elapsed_seconds(&self) -> u64 {
+    pub fn elapsed(&self) -> Duration {
         if self.is_running {
-            self.start_time.elapsed().as_secs()
+            self.clock.now().saturating_sub(self.start_time)
         } else {
-            0
+            Duration::ZERO
         }
     }
 
+    pub fn elapsed_seconds(&self) -> u64 {
+        self.elapsed().as_secs()
+    }
+
     pub fn elapsed_millis(&self) -> u128 {
-        if self.is_running {
-            self.start_time.elapsed().as_millis()
-        } else {
-            0
-        }
+        self.elapsed().as_millis()
     }
 
     /// Whether the timer is currently running. A Timer must be running to report on elapsed time.
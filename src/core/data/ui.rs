@@ -1,6 +1,7 @@
 /// Where the UI element is anchored on the screen. The anchor represents where the element is positioned by default
 /// when it has no offset.
 #[derive(PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum UiAnchor {
     TopLeft,
     MiddleTop,
@@ -14,6 +15,7 @@ pub enum UiAnchor {
 }
 
 #[derive(PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Alignment {
     Left,
     Middle,
@@ -22,6 +24,7 @@ pub enum Alignment {
 
 /// Represents an RGB color.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rgb(pub u8, pub u8, pub u8);
 impl Rgb {
     /// The red value of the color.
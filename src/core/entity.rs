@@ -1,31 +1,45 @@
-use std::{ops::Deref, sync::atomic::AtomicU64};
+use std::sync::atomic::AtomicU64;
 
 /// An `Entity` represents a thing in your game world and is one of the core aspects of ECS. Functionally, 
 /// you can think of an `Entity` as its ID. Entities are associated with `Component`s to define what data that `Entity`
 /// has. Though it's likely you'll use `Entity` references provided to you, you should never be creating an `Entity` yourself.
 /// 
 /// `Entity` ID generation happens automatically for you. When an `Entity` is removed from the world, its ID is recycled.
-/// For the purposes of a user of Thomas, you can largely ignore an `Entity`'s exact ID. In fact, you shouldn't be trying
-/// to hold onto them in an effort to single out a particular `Entity` in the game world. If you need to always be able
+/// To keep a recycled ID from being mistaken for the `Entity` that used to have it, every `Entity` also carries a
+/// generation that's bumped each time its ID is reused. Two `Entity`s are only equal if both their ID and generation
+/// match, so an `Entity` you've held onto (say, in a custom component) will never start pointing at some unrelated
+/// `Entity` that happened to get the same ID. You can use `World::is_alive` to check whether an `Entity` you've held
+/// onto is still in the world.
+///
+/// For the purposes of a user of Thomas, you can largely ignore an `Entity`'s exact ID. If you need to always be able
 /// to single out a particular `Entity` for use in one of your systems, consider using a custom `Component` attached to that
-/// `Entity` that's unique to that `Entity`, or use the `Identity` component to give that `Entity` identifiers that are meaningful 
+/// `Entity` that's unique to that `Entity`, or use the `Identity` component to give that `Entity` identifiers that are meaningful
 /// for your game.
 #[derive(PartialEq, Eq, Hash, PartialOrd, Ord, Copy, Clone, Debug)]
-pub struct Entity(pub(crate) u64);
+pub struct Entity(pub(crate) u64, pub(crate) u32);
 impl Entity {
     pub(crate) fn new() -> Self {
         static ID_COUNTER: AtomicU64 = AtomicU64::new(0);
 
         let id = ID_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
 
-        Self(id)
+        Self(id, 0)
+    }
+
+    /// Makes an `Entity` that reuses this `Entity`'s ID, but is a new generation of it.
+    pub(crate) fn next_generation(&self) -> Self {
+        Self(self.0, self.1.wrapping_add(1))
+    }
+
+    /// The ID of the `Entity`. IDs are recycled, so the ID alone doesn't uniquely identify an `Entity` over the life
+    /// of the game. Use the `Entity` itself for that.
+    pub fn id(&self) -> u64 {
+        self.0
     }
-}
-impl Deref for Entity {
-    type Target = u64;
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
+    /// How many times the ID of this `Entity` has been reused.
+    pub fn generation(&self) -> u32 {
+        self.1
     }
 }
 
@@ -44,4 +58,25 @@ mod tests {
             assert_ne!(e1.0, e2.0);
         }
     }
+
+    mod test_next_generation {
+        use super::*;
+
+        #[test]
+        fn keeps_the_id_and_bumps_the_generation() {
+            let entity = Entity(3, 1);
+
+            let next = entity.next_generation();
+
+            assert_eq!(next.id(), 3);
+            assert_eq!(next.generation(), 2);
+        }
+
+        #[test]
+        fn is_not_equal_to_the_previous_generation() {
+            let entity = Entity(3, 0);
+
+            assert_ne!(entity, entity.next_generation());
+        }
+    }
 }
@@ -1,23 +1,66 @@
-use std::{
-    cell::{Ref, RefCell, RefMut},
-    collections::{HashMap, HashSet},
-    hash::Hash,
-    rc::Rc,
+#[cfg(feature = "parallel")]
+use std::cell::Cell;
+use std::{any::TypeId, collections::HashMap};
+
+use crate::{
+    Archetype, Component, ComponentQueryData, ComponentTicks, Entity, Query, QueryResult,
+    QueryResultList, Ref, RefMut, RemovedComponent, Shared, SharedCell, TrackedComponent,
 };
 
-use crate::{Component, ComponentQueryData, Entity, Query, QueryResult, QueryResultList};
+pub type StoredComponent = Shared<SharedCell<Box<dyn Component>>>;
+
+#[cfg(feature = "parallel")]
+thread_local! {
+    static PARALLEL_SYSTEM_LAST_RUN_TICK: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+/// Runs the operator of a `System` that last ran at `last_run_tick` on the current thread, alongside other systems
+/// running on other threads.
+#[cfg(feature = "parallel")]
+pub(crate) fn run_in_parallel_system<R>(last_run_tick: u64, operator: impl FnOnce() -> R) -> R {
+    PARALLEL_SYSTEM_LAST_RUN_TICK.with(|tick| tick.set(Some(last_run_tick)));
 
-pub type StoredComponent = Rc<RefCell<Box<dyn Component>>>;
-type EntitiesToComponents = HashMap<Entity, HashMap<String, StoredComponent>>;
-type ComponentsToEntities = HashMap<String, HashSet<Entity>>;
+    let result = operator();
+
+    PARALLEL_SYSTEM_LAST_RUN_TICK.with(|tick| tick.set(None));
+
+    result
+}
 
 /// A list of components that are currently stored in the game world.
+///
+/// Mutably borrowing a component through the list marks that component as changed, which is what `Query::changed`
+/// uses to find changed components.
 pub struct StoredComponentList {
     components: Vec<StoredComponent>,
+    ticks: Vec<Shared<ComponentTicks>>,
+    change_tick: u64,
 }
 impl StoredComponentList {
     pub fn new(components: Vec<StoredComponent>) -> Self {
-        Self { components }
+        Self {
+            components,
+            ticks: vec![],
+            change_tick: 0,
+        }
+    }
+
+    /// Makes a list whose components are marked as changed at the provided tick when they're mutably borrowed.
+    pub(crate) fn new_tracked(
+        tracked_components: Vec<&TrackedComponent>,
+        change_tick: u64,
+    ) -> Self {
+        Self {
+            components: tracked_components
+                .iter()
+                .map(|tracked_component| Shared::clone(&tracked_component.component))
+                .collect(),
+            ticks: tracked_components
+                .iter()
+                .map(|tracked_component| Shared::clone(&tracked_component.ticks))
+                .collect(),
+            change_tick,
+        }
     }
 
     pub fn len(&self) -> usize {
@@ -49,8 +92,12 @@ impl StoredComponentList {
     where
         T: Component + 'static,
     {
-        for component in &self.components {
+        for (index, component) in self.components.iter().enumerate() {
             if component.try_borrow().is_ok() && (**component.borrow()).as_any().is::<T>() {
+                if let Some(ticks) = self.ticks.get(index) {
+                    ticks.set_changed(self.change_tick);
+                }
+
                 return Some(RefMut::map(component.borrow_mut(), |component| {
                     (**component).as_any_mut().downcast_mut::<T>().unwrap()
                 }));
@@ -94,170 +141,455 @@ impl StoredComponentList {
 /// game world, its entities, and their components. Queries can be run against the `EntityManager` to produce matches
 /// that can be used by systems.
 ///
-/// For speed of retrieval, the `EntityManager` internally uses maps and sets to track all the entities in the world and
-/// their components, as well as all components in the world and the entities that have them.
+/// Internally, entities are grouped into `Archetype`s by their exact set of components. When an entity gains or loses
+/// a component, it moves to the archetype for its new set of components. This lets queries skip straight to the
+/// archetypes that could possibly match rather than checking every entity in the world.
+///
+/// The `EntityManager` also keeps the change tick used for change detection. The tick moves forward before every
+/// `System` runs and before the command queue is processed, and components remember the tick they were added and last
+/// changed at. A `System` can then find what's new to it by comparing those ticks to the tick it last ran at.
 pub(crate) struct EntityManager {
-    entities_to_components: EntitiesToComponents,
-    components_to_entities: ComponentsToEntities,
+    archetypes: Vec<Archetype>,
+    archetype_lookup: HashMap<Vec<TypeId>, usize>,
+    entity_locations: HashMap<Entity, EntityLocation>,
     available_entity_ids: Vec<Entity>,
+    change_tick: u64,
+    last_run_tick: u64,
+    frame_start_tick: u64,
+    removed_components: Vec<RemovedComponent>,
 }
 impl EntityManager {
     pub fn new() -> Self {
         Self {
-            entities_to_components: HashMap::new(),
-            components_to_entities: HashMap::new(),
+            archetypes: vec![],
+            archetype_lookup: HashMap::new(),
+            entity_locations: HashMap::new(),
             available_entity_ids: vec![],
+            // Starts ahead of the tick every `System` is considered to have last run at, so everything in the world
+            // is new to a `System` the first time it runs.
+            change_tick: 1,
+            last_run_tick: 0,
+            frame_start_tick: 0,
+            removed_components: vec![],
         }
     }
 
-    /// Adds an entity to the world, reusing any available entity IDs before falling back to creating a new one.
-    /// Returns a copy of the created `Entity`.
-    pub fn add_entity(&mut self, components: Vec<Box<dyn Component>>) -> Entity {
-        let entity = self.get_next_entity();
+    /// Moves the change tick forward, returning the new tick.
+    pub fn increment_change_tick(&mut self) -> u64 {
+        self.change_tick += 1;
 
-        for component in &components {
-            if self
-                .components_to_entities
-                .contains_key(component.component_name())
-            {
-                if let Some(entity_set) = self
-                    .components_to_entities
-                    .get_mut(component.component_name())
-                {
-                    entity_set.insert(entity);
-                }
-            } else {
-                let mut entity_set = HashSet::new();
-                entity_set.insert(entity);
+        self.change_tick
+    }
 
-                self.components_to_entities
-                    .insert(component.component_name().to_string(), entity_set);
-            }
-        }
+    /// Prepares for a `System` that last ran at `last_run_tick` to run. Queries run after this only consider changes
+    /// made since `last_run_tick`. Returns the tick the `System` runs at, which is what it should provide as its
+    /// `last_run_tick` the next time it runs.
+    pub fn start_system_run(&mut self, last_run_tick: u64) -> u64 {
+        self.last_run_tick = last_run_tick;
 
-        let mut component_map = HashMap::new();
+        self.increment_change_tick()
+    }
 
-        for component in components {
-            component_map.insert(
-                component.component_name().to_string(),
-                Rc::new(RefCell::new(component)),
-            );
+    /// Marks the start of a new frame. Removed components are kept around for a full frame after they're removed so
+    /// every `System` has the chance to see them. Any that were removed before the previous frame started are dropped.
+    pub fn start_frame(&mut self) {
+        let previous_frame_start_tick = self.frame_start_tick;
+
+        self.removed_components.retain(|removed_component| {
+            removed_component.removed_tick >= previous_frame_start_tick
+        });
+
+        self.frame_start_tick = self.increment_change_tick();
+    }
+
+    /// The components of type `T` that were removed since the running `System` last ran, along with the entity each was
+    /// removed from.
+    pub fn removed_components<T>(&self) -> Vec<(Entity, Ref<'_, T>)>
+    where
+        T: Component + 'static,
+    {
+        self.removed_components
+            .iter()
+            .filter(|removed_component| {
+                removed_component.removed_tick > self.running_system_last_run_tick()
+                    && removed_component.component.borrow().component_id() == T::id()
+            })
+            .map(|removed_component| {
+                (
+                    removed_component.entity,
+                    Ref::map(removed_component.component.borrow(), |component| {
+                        T::cast(component.as_ref()).unwrap()
+                    }),
+                )
+            })
+            .collect()
+    }
+
+    /// The tick the running `System` last ran at. Systems running alongside others on a thread pool each have their own,
+    /// since they can't all share the one the `EntityManager` keeps.
+    fn running_system_last_run_tick(&self) -> u64 {
+        #[cfg(feature = "parallel")]
+        if let Some(last_run_tick) = PARALLEL_SYSTEM_LAST_RUN_TICK.with(Cell::get) {
+            return last_run_tick;
         }
 
-        self.entities_to_components.insert(entity, component_map);
+        self.last_run_tick
+    }
+
+    /// Adds an entity to the world, reusing any available entity IDs before falling back to creating a new one. A reused
+    /// ID is given a new generation so the new `Entity` can't be confused with the one that previously had that ID.
+    /// Returns a copy of the created `Entity`.
+    pub fn add_entity(&mut self, components: Vec<Box<dyn Component>>) -> Entity {
+        let entity = self.get_next_entity();
+
+        let component_map: HashMap<TypeId, TrackedComponent> = components
+            .into_iter()
+            .map(|component| {
+                (
+                    component.component_id(),
+                    TrackedComponent::new(
+                        Shared::new(SharedCell::new(component)),
+                        self.change_tick,
+                    ),
+                )
+            })
+            .collect();
+
+        self.place_entity(entity, component_map);
 
-        return entity;
+        entity
     }
 
-    /// Removes an entity from the world, freeing its ID for reuse.
-    pub fn remove_entity(&mut self, entity: &Entity) {
-        if let Some((removed_entity, component_map)) =
-            self.entities_to_components.remove_entry(entity)
-        {
-            for component in component_map.values() {
-                if let Some(entity_set) = self
-                    .components_to_entities
-                    .get_mut(component.borrow().component_name())
-                {
-                    entity_set.remove(entity);
-                }
+    /// Removes an entity from the world, freeing its ID for reuse. Returns whether the entity was removed. Nothing
+    /// happens if the entity isn't alive.
+    pub fn remove_entity(&mut self, entity: &Entity) -> bool {
+        if self.is_alive(entity) {
+            for (_, tracked_component) in self.take_entity(entity) {
+                self.record_removed_component(*entity, tracked_component);
             }
 
-            self.available_entity_ids.push(removed_entity);
+            self.available_entity_ids.push(*entity);
+
+            return true;
         }
-    }
 
-    pub fn add_component_to_entity(&mut self, entity: &Entity, component: Box<dyn Component>) {
-        if let Some(component_map) = self.entities_to_components.get_mut(&entity) {
-            if !entity_has_component(&self.components_to_entities, &entity, &component) {
-                let component_name = component.component_name();
+        false
+    }
 
-                component_map.insert(component_name.to_string(), Rc::new(RefCell::new(component)));
+    /// Adds the component to the entity. Returns whether the component was added. Nothing happens if the entity
+    /// isn't alive or already has a component of that type.
+    pub fn add_component_to_entity(
+        &mut self,
+        entity: &Entity,
+        component: Box<dyn Component>,
+    ) -> bool {
+        if let Some(location) = self.entity_locations.get(entity) {
+            if !self.archetypes[location.archetype_index].has_component(&component.component_id()) {
+                let mut component_map = self.take_entity(entity);
+
+                component_map.insert(
+                    component.component_id(),
+                    TrackedComponent::new(
+                        Shared::new(SharedCell::new(component)),
+                        self.change_tick,
+                    ),
+                );
 
-                if let Some(entity_set) = self.components_to_entities.get_mut(component_name) {
-                    entity_set.insert(*entity);
-                } else {
-                    let mut entity_set = HashSet::new();
-                    entity_set.insert(*entity);
+                self.place_entity(*entity, component_map);
 
-                    self.components_to_entities
-                        .insert(component_name.to_string(), entity_set);
-                }
+                return true;
             }
         }
+
+        false
     }
 
-    pub fn remove_component_from_entity(&mut self, entity: &Entity, component_name: &'static str) {
-        if let Some(component_map) = self.entities_to_components.get_mut(&entity) {
-            if component_map.remove(component_name).is_some() {
-                if let Some(entity_set) = self.components_to_entities.get_mut(component_name) {
-                    entity_set.remove(entity);
+    /// Removes the component from the entity. Returns whether the component was removed. Nothing happens if the
+    /// entity isn't alive or doesn't have the component.
+    pub fn remove_component_from_entity(&mut self, entity: &Entity, component_id: TypeId) -> bool {
+        if let Some(location) = self.entity_locations.get(entity) {
+            if self.archetypes[location.archetype_index].has_component(&component_id) {
+                let mut component_map = self.take_entity(entity);
+
+                if let Some(tracked_component) = component_map.remove(&component_id) {
+                    self.record_removed_component(*entity, tracked_component);
                 }
+
+                self.place_entity(*entity, component_map);
+
+                return true;
             }
         }
+
+        false
+    }
+
+    /// Whether the entity is currently in the world. An `Entity` whose ID has since been given to a newer generation
+    /// is not alive.
+    pub fn is_alive(&self, entity: &Entity) -> bool {
+        self.entity_locations.contains_key(entity)
+    }
+
+    /// Whether the entity has the component. Always `false` if the entity isn't alive.
+    pub fn has_component(&self, entity: &Entity, component_id: &TypeId) -> bool {
+        self.get_tracked_component_on_entity(entity, component_id)
+            .is_some()
+    }
+
+    /// Borrows the entity's component of type `T`. Returns `None` if the entity isn't alive, doesn't have the
+    /// component, or the component is already mutably borrowed.
+    pub fn get_component<T>(&self, entity: &Entity) -> Option<Ref<'_, T>>
+    where
+        T: Component + 'static,
+    {
+        let component = self
+            .get_tracked_component_on_entity(entity, &T::id())?
+            .component
+            .try_borrow()
+            .ok()?;
+
+        Some(Ref::map(component, |component| {
+            T::cast(component.as_ref()).unwrap()
+        }))
+    }
+
+    /// Like `get_component`, but mutably borrows the component. The component is marked as changed.
+    pub fn get_component_mut<T>(&self, entity: &Entity) -> Option<RefMut<'_, T>>
+    where
+        T: Component + 'static,
+    {
+        let tracked_component = self.get_tracked_component_on_entity(entity, &T::id())?;
+        let component = tracked_component.component.try_borrow_mut().ok()?;
+
+        tracked_component.ticks.set_changed(self.change_tick);
+
+        Some(RefMut::map(component, |component| {
+            T::cast_mut(component.as_mut()).unwrap()
+        }))
+    }
+
+    /// Every entity in the world, sorted by ID. IDs of destroyed entities are reused, so this isn't necessarily the
+    /// order the entities were created in.
+    pub fn entities(&self) -> Vec<Entity> {
+        let mut entities: Vec<Entity> = self.entity_locations.keys().copied().collect();
+
+        entities.sort();
+
+        entities
+    }
+
+    /// Every component of the entity, or nothing if the entity isn't alive.
+    pub fn get_all_components(&self, entity: &Entity) -> Vec<StoredComponent> {
+        self.entity_locations
+            .get(entity)
+            .map(|location| {
+                self.archetypes[location.archetype_index]
+                    .get_components_in_row(location.row)
+                    .into_iter()
+                    .map(Shared::clone)
+                    .collect()
+            })
+            .unwrap_or_default()
     }
 
     /// Allows a `Query` to be run against the `EntityManager`, producing a `QueryResultList` reflecting the matches in the
     /// current state of the game world.
     pub fn query(&self, query: &Query) -> QueryResultList {
-        let allowed_component_names = query.allowed_component_names();
-        let forbidden_component_names = query.forbidden_component_names();
-        let entities_with_forbidden_components = Self::get_entities_with_components(
-            &self.components_to_entities,
-            &forbidden_component_names,
-        );
+        let allowed_component_ids = query.allowed_component_ids();
+        let forbidden_component_ids = query.forbidden_component_ids();
+        let any_of_component_groups = query.any_of_component_groups();
+        let added_component_ids = query.added_component_ids();
+        let changed_component_ids = query.changed_component_ids();
+
+        if allowed_component_ids.is_empty()
+            && any_of_component_groups.is_empty()
+            && query.allowed_entities().is_none()
+        {
+            return QueryResultList::new(vec![]);
+        }
 
-        let matches = Self::get_entities_with_components(
-            &self.components_to_entities,
-            &allowed_component_names,
-        )
-        .into_iter()
-        .filter_map(|entity_with_desired_components| {
-            if entities_with_forbidden_components.contains(&entity_with_desired_components)
-                || !Self::entity_components_pass_all_predicates(
-                    &self.entities_to_components,
-                    &entity_with_desired_components,
-                    &query.allowed_components(),
+        let mut fetched_component_ids: Vec<TypeId> = allowed_component_ids
+            .iter()
+            .chain(query.optional_component_ids())
+            .chain(any_of_component_groups.iter().flatten())
+            .copied()
+            .collect();
+        fetched_component_ids.sort();
+        fetched_component_ids.dedup();
+
+        let archetype_matches = |archetype: &Archetype| {
+            archetype.has_all_components(&allowed_component_ids)
+                && !archetype.has_any_components(&forbidden_component_ids)
+                && any_of_component_groups
+                    .iter()
+                    .all(|component_ids| archetype.has_any_components(component_ids))
+        };
+        let get_match = |archetype: &Archetype, row: usize, entity: &Entity| {
+            if Self::row_passes_all_predicates(archetype, row, query.allowed_components())
+                && self.row_passes_change_filters(
+                    archetype,
+                    row,
+                    added_component_ids,
+                    changed_component_ids,
                 )
             {
-                None
-            } else {
                 Some(QueryResult {
-                    entity: entity_with_desired_components,
-                    components: Self::get_components_on_entity(
-                        &self.entities_to_components,
-                        &entity_with_desired_components,
-                        &allowed_component_names,
-                    ),
+                    entity: *entity,
+                    components: self.get_components_in_row(archetype, row, &fetched_component_ids),
                 })
+            } else {
+                None
             }
-        })
-        .collect();
+        };
+
+        let matches = match query.allowed_entities() {
+            // Only the provided entities can match, so they're looked up directly rather than searching the world.
+            Some(allowed_entities) => {
+                let mut allowed_entities: Vec<&Entity> = allowed_entities.iter().collect();
+                allowed_entities.sort();
+
+                allowed_entities
+                    .into_iter()
+                    .filter_map(|entity| {
+                        let location = self.entity_locations.get(entity)?;
+                        let archetype = &self.archetypes[location.archetype_index];
+
+                        if archetype_matches(archetype) {
+                            get_match(archetype, location.row, entity)
+                        } else {
+                            None
+                        }
+                    })
+                    .collect()
+            }
+            None => self
+                .archetypes
+                .iter()
+                .filter(|archetype| archetype_matches(archetype))
+                .flat_map(|archetype| {
+                    archetype
+                        .entities()
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(row, entity)| get_match(archetype, row, entity))
+                        .collect::<Vec<QueryResult>>()
+                })
+                .collect(),
+        };
 
         QueryResultList::new(matches)
     }
 
     fn get_next_entity(&mut self) -> Entity {
-        if self.available_entity_ids.len() > 0 {
-            self.available_entity_ids.pop().unwrap()
+        if let Some(entity) = self.available_entity_ids.pop() {
+            entity.next_generation()
         } else {
             Entity::new()
         }
     }
 
-    fn entity_components_pass_all_predicates(
-        entities_to_components: &EntitiesToComponents,
+    /// Gets the index of the archetype for the provided set of component types, creating the archetype if it doesn't exist yet.
+    fn get_or_add_archetype(&mut self, mut component_ids: Vec<TypeId>) -> usize {
+        component_ids.sort();
+        component_ids.dedup();
+
+        if let Some(archetype_index) = self.archetype_lookup.get(&component_ids) {
+            return *archetype_index;
+        }
+
+        let archetype_index = self.archetypes.len();
+
+        self.archetypes.push(Archetype::new(component_ids.clone()));
+        self.archetype_lookup.insert(component_ids, archetype_index);
+
+        archetype_index
+    }
+
+    /// Puts the entity and its components into the archetype matching its components.
+    fn place_entity(&mut self, entity: Entity, component_map: HashMap<TypeId, TrackedComponent>) {
+        let archetype_index = self.get_or_add_archetype(component_map.keys().copied().collect());
+
+        let row = self.archetypes[archetype_index].push(entity, component_map);
+
+        self.entity_locations.insert(
+            entity,
+            EntityLocation {
+                archetype_index,
+                row,
+            },
+        );
+    }
+
+    /// Takes the entity out of its archetype, giving back the components that it had. The entity no longer has a
+    /// location in the world after this call.
+    ///
+    /// # Panics
+    /// If the entity is not in the world.
+    fn take_entity(&mut self, entity: &Entity) -> HashMap<TypeId, TrackedComponent> {
+        let location = self
+            .entity_locations
+            .remove(entity)
+            .expect("Entity being taken exists in the world.");
+
+        let (component_map, moved_entity) =
+            self.archetypes[location.archetype_index].swap_remove(location.row);
+
+        if let Some(moved_entity) = moved_entity {
+            self.entity_locations.insert(moved_entity, location);
+        }
+
+        component_map
+    }
+
+    fn get_tracked_component_on_entity(
+        &self,
         entity: &Entity,
-        component_query_data_list: &Vec<ComponentQueryData>,
+        component_id: &TypeId,
+    ) -> Option<&TrackedComponent> {
+        let location = self.entity_locations.get(entity)?;
+
+        self.archetypes[location.archetype_index].get_tracked_component(location.row, component_id)
+    }
+
+    fn record_removed_component(&mut self, entity: Entity, tracked_component: TrackedComponent) {
+        self.removed_components.push(RemovedComponent {
+            entity,
+            component: tracked_component.component,
+            removed_tick: self.change_tick,
+        });
+    }
+
+    fn row_passes_change_filters(
+        &self,
+        archetype: &Archetype,
+        row: usize,
+        added_component_ids: &[TypeId],
+        changed_component_ids: &[TypeId],
+    ) -> bool {
+        let last_run_tick = self.running_system_last_run_tick();
+        let passes_filter = |component_ids: &[TypeId], filter: fn(&ComponentTicks, u64) -> bool| {
+            component_ids.iter().all(|component_id| {
+                archetype
+                    .get_component_ticks(row, component_id)
+                    .is_some_and(|ticks| filter(ticks, last_run_tick))
+            })
+        };
+
+        passes_filter(added_component_ids, ComponentTicks::is_added_since)
+            && passes_filter(changed_component_ids, ComponentTicks::is_changed_since)
+    }
+
+    fn row_passes_all_predicates(
+        archetype: &Archetype,
+        row: usize,
+        component_query_data_list: &[ComponentQueryData],
     ) -> bool {
         component_query_data_list
             .iter()
             .all(|component_query_data| {
-                if let Some(component) = Self::get_component_on_entity(
-                    entities_to_components,
-                    entity,
-                    component_query_data.component_name(),
-                ) {
+                if let Some(component) =
+                    archetype.get_component(row, component_query_data.component_id())
+                {
                     if let Some(where_predicate) = component_query_data.where_predicate() {
                         return where_predicate(&**component.borrow());
                     } else {
@@ -269,100 +601,29 @@ impl EntityManager {
             })
     }
 
-    fn get_entities_with_component(
-        components_to_entities: &ComponentsToEntities,
-        component_name: &'static str,
-    ) -> Vec<Entity> {
-        if let Some(entities_with_component) = components_to_entities.get(component_name) {
-            return entities_with_component
-                .iter()
-                .map(|entity_ref| *entity_ref)
-                .collect();
-        }
-
-        vec![]
-    }
-
-    fn get_entities_with_components(
-        components_to_entities: &ComponentsToEntities,
-        component_names: &Vec<&'static str>,
-    ) -> Vec<Entity> {
-        let mut entity_lists: Vec<Vec<Entity>> = component_names
-            .iter()
-            .map(|component_name| {
-                Self::get_entities_with_component(components_to_entities, component_name)
-            })
-            .collect();
-
-        // TODO: This isn't the most efficient. Multiple conditional retrieval could likely be
-        // sped up with the introduction of automatic archetype management.
-        if entity_lists.len() == 1 {
-            entity_lists.pop().unwrap()
-        } else {
-            intersection(&entity_lists)
-                .into_iter()
-                .map(|entity_ref| *entity_ref)
-                .collect()
-        }
-    }
-
-    fn get_component_on_entity(
-        entities_to_components: &EntitiesToComponents,
-        entity: &Entity,
-        component_name: &'static str,
-    ) -> Option<StoredComponent> {
-        if let Some(stored_components) = entities_to_components.get(entity) {
-            if let Some(stored_component) = stored_components.get(component_name) {
-                return Some(Rc::clone(stored_component));
-            }
-        }
-
-        None
-    }
-
-    fn get_components_on_entity(
-        entities_to_components: &EntitiesToComponents,
-        entity: &Entity,
-        component_names: &Vec<&'static str>,
+    /// Gets the components in the row for each of the provided component types that the archetype has. Types the
+    /// archetype doesn't have are skipped.
+    fn get_components_in_row(
+        &self,
+        archetype: &Archetype,
+        row: usize,
+        component_ids: &[TypeId],
     ) -> StoredComponentList {
-        StoredComponentList::new(
-            component_names
+        StoredComponentList::new_tracked(
+            component_ids
                 .iter()
-                .filter_map(|component_name| {
-                    Self::get_component_on_entity(&entities_to_components, entity, component_name)
-                })
+                .filter_map(|component_id| archetype.get_tracked_component(row, component_id))
                 .collect(),
+            self.change_tick,
         )
     }
 }
 
-fn entity_has_component(
-    components_to_entities: &ComponentsToEntities,
-    entity: &Entity,
-    component: &Box<dyn Component>,
-) -> bool {
-    if let Some(entity_set) = components_to_entities.get(component.component_name()) {
-        return entity_set.contains(&entity);
-    }
-
-    false
-}
-
-fn intersection<T: Hash + Eq + PartialEq>(vectors: &Vec<Vec<T>>) -> Vec<&T> {
-    let mut values_tracker: HashSet<&T> = HashSet::new();
-    let mut intersecting_values = vec![];
-
-    for values_vector in vectors {
-        for value in values_vector {
-            if values_tracker.contains(value) {
-                intersecting_values.push(value);
-            } else {
-                values_tracker.insert(value);
-            }
-        }
-    }
-
-    intersecting_values
+/// Where an entity's components live: the archetype it belongs to and its row in that archetype.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct EntityLocation {
+    archetype_index: usize,
+    row: usize,
 }
 
 #[cfg(test)]
@@ -409,7 +670,7 @@ mod tests {
 
         #[test]
         #[should_panic(
-            expected = "Component EmptyComponent was not present, or you're trying to borrow it while it's already mutably borrowed."
+            expected = "Component thomas::core::entity_manager::tests::EmptyComponent was not present, or you're trying to borrow it while it's already mutably borrowed."
         )]
         fn panics_when_trying_to_borrow_the_same_component_mutably_more_than_once() {
             let mut em = EntityManager::new();
@@ -426,7 +687,7 @@ mod tests {
 
         #[test]
         #[should_panic(
-            expected = "Component TestComponent was not present, or you're trying to borrow it while it's already mutably borrowed."
+            expected = "Component thomas::core::entity_manager::tests::TestComponent was not present, or you're trying to borrow it while it's already mutably borrowed."
         )]
         fn panics_when_trying_to_borrow_a_component_that_is_not_present() {
             let mut em = EntityManager::new();
@@ -441,20 +702,35 @@ mod tests {
         }
     }
 
+    fn get_component_on_entity(
+        em: &EntityManager,
+        entity: &Entity,
+        component_id: TypeId,
+    ) -> Option<StoredComponent> {
+        let location = em.entity_locations.get(entity)?;
+
+        em.archetypes[location.archetype_index]
+            .get_component(location.row, &component_id)
+            .map(Shared::clone)
+    }
+
     mod test_add_entity {
         use super::*;
 
         #[test]
-        fn returns_entity_and_adds_to_maps() {
+        fn returns_entity_and_places_it_in_an_archetype() {
             let mut em = EntityManager::new();
 
             let result = em.add_entity(vec![]);
 
-            let component_map = em.entities_to_components.get(&result);
+            let location = em.entity_locations.get(&result);
 
-            assert!(component_map.is_some());
-            assert!(component_map.unwrap().is_empty());
-            assert!(em.components_to_entities.is_empty());
+            assert!(location.is_some());
+            assert_eq!(em.archetypes.len(), 1);
+            assert_eq!(
+                em.archetypes[location.unwrap().archetype_index].entities(),
+                &vec![result]
+            );
         }
 
         #[test]
@@ -463,16 +739,12 @@ mod tests {
 
             let result = em.add_entity(vec![Box::new(TestComponent { prop1: 1 })]);
 
-            let component_map = em
-                .entities_to_components
-                .get(&result)
-                .expect("The component map was added for the entity");
-
-            let comp = component_map.get(TestComponent::name()).unwrap().borrow();
+            let comp = get_component_on_entity(&em, &result, TestComponent::id())
+                .expect("The component was added for the entity");
+            let comp = comp.borrow();
             let test_component =
                 TestComponent::cast(comp.as_ref()).expect("Component is TestComponent");
 
-            assert!(component_map.get(TestComponent::name()).is_some());
             assert_eq!(test_component.prop1, 1);
         }
 
@@ -486,22 +758,12 @@ mod tests {
                 Box::new(AnotherTestComponent { prop1: 5 }),
             ]);
 
-            let component_map = em
-                .entities_to_components
-                .get(&result)
-                .expect("The component map was added for the entity");
-
             let (comp1, comp2, comp3) = (
-                component_map.get(TestComponent::name()).unwrap().borrow(),
-                component_map
-                    .get(OtherTestComponent::name())
-                    .unwrap()
-                    .borrow(),
-                component_map
-                    .get(AnotherTestComponent::name())
-                    .unwrap()
-                    .borrow(),
+                get_component_on_entity(&em, &result, TestComponent::id()).unwrap(),
+                get_component_on_entity(&em, &result, OtherTestComponent::id()).unwrap(),
+                get_component_on_entity(&em, &result, AnotherTestComponent::id()).unwrap(),
             );
+            let (comp1, comp2, comp3) = (comp1.borrow(), comp2.borrow(), comp3.borrow());
 
             let test_component =
                 TestComponent::cast(comp1.as_ref()).expect("Component is TestComponent");
@@ -510,26 +772,56 @@ mod tests {
             let another_test_component = AnotherTestComponent::cast(comp3.as_ref())
                 .expect("Component is AnotherTestComponent");
 
-            assert!(component_map.get(TestComponent::name()).is_some());
             assert_eq!(test_component.prop1, 1);
-
-            assert!(component_map.get(OtherTestComponent::name()).is_some());
             assert_eq!(other_test_component.prop1, 3);
-
-            assert!(component_map.get(AnotherTestComponent::name()).is_some());
             assert_eq!(another_test_component.prop1, 5);
         }
 
+        #[test]
+        fn entities_with_the_same_components_share_an_archetype() {
+            let mut em = EntityManager::new();
+
+            let entity1 = em.add_entity(vec![
+                Box::new(TestComponent { prop1: 1 }),
+                Box::new(EmptyComponent {}),
+            ]);
+            let entity2 = em.add_entity(vec![
+                Box::new(EmptyComponent {}),
+                Box::new(TestComponent { prop1: 2 }),
+            ]);
+            em.add_entity(vec![Box::new(TestComponent { prop1: 3 })]);
+
+            assert_eq!(em.archetypes.len(), 2);
+            assert_eq!(
+                em.entity_locations.get(&entity1).unwrap().archetype_index,
+                em.entity_locations.get(&entity2).unwrap().archetype_index
+            );
+        }
+
         #[test]
         fn ids_are_reused_when_available() {
             let mut em = EntityManager::new();
-            em.available_entity_ids.push(Entity(1000));
+            em.available_entity_ids.push(Entity(1000, 0));
 
             let entity = em.add_entity(vec![]);
 
-            assert_eq!(entity, Entity(1000));
+            assert_eq!(entity.id(), 1000);
             assert_eq!(em.available_entity_ids.len(), 0);
         }
+
+        #[test]
+        fn reused_ids_get_a_new_generation() {
+            let mut em = EntityManager::new();
+
+            let old_entity = em.add_entity(vec![]);
+            em.remove_entity(&old_entity);
+
+            let new_entity = em.add_entity(vec![]);
+
+            assert_eq!(new_entity.id(), old_entity.id());
+            assert_eq!(new_entity.generation(), old_entity.generation() + 1);
+            assert_ne!(new_entity, old_entity);
+        }
     }
 
     mod test_remove_entity {
@@ -539,36 +831,15 @@ mod tests {
         fn removing_a_nonexistent_entity_does_nothing() {
             let mut em = EntityManager::new();
 
-            em.components_to_entities.insert(
-                TestComponent::name().to_string(),
-                HashSet::from([Entity(1)]),
-            );
-            em.entities_to_components.insert(
-                Entity(1),
-                HashMap::from([(
-                    TestComponent::name().to_string(),
-                    Rc::new(RefCell::new(
-                        Box::new(TestComponent { prop1: 1 }) as Box<dyn Component>
-                    )),
-                )]),
-            );
-
-            em.remove_entity(&Entity(2));
+            let entity = em.add_entity(vec![Box::new(TestComponent { prop1: 1 })]);
 
-            let entity_set = em
-                .components_to_entities
-                .get(TestComponent::name())
-                .unwrap();
-            let component_map = em.entities_to_components.get(&Entity(1)).unwrap();
+            em.remove_entity(&Entity(entity.0 + 1, 0));
 
-            assert_eq!(entity_set.len(), 1);
-            assert!(entity_set.contains(&Entity(1)));
-
-            assert_eq!(component_map.len(), 1);
+            assert_eq!(em.entity_locations.len(), 1);
+            assert!(em.available_entity_ids.is_empty());
             assert_eq!(
                 TestComponent::cast(
-                    component_map
-                        .get(TestComponent::name())
+                    get_component_on_entity(&em, &entity, TestComponent::id())
                         .unwrap()
                         .borrow()
                         .as_ref()
@@ -583,47 +854,67 @@ mod tests {
         fn can_remove_an_existing_entity() {
             let mut em = EntityManager::new();
 
-            em.components_to_entities.insert(
-                TestComponent::name().to_string(),
-                HashSet::from([Entity(1)]),
-            );
-            em.entities_to_components.insert(
-                Entity(1),
-                HashMap::from([(
-                    TestComponent::name().to_string(),
-                    Rc::new(RefCell::new(
-                        Box::new(TestComponent { prop1: 1 }) as Box<dyn Component>
-                    )),
-                )]),
+            let entity = em.add_entity(vec![Box::new(TestComponent { prop1: 1 })]);
+
+            em.remove_entity(&entity);
+
+            assert!(!em.entity_locations.contains_key(&entity));
+            assert!(get_component_on_entity(&em, &entity, TestComponent::id()).is_none());
+            assert_eq!(
+                em.archetypes.len(),
+                1,
+                "The archetype wasn't wiped just because there are no longer any Entities in it."
             );
+            assert!(em.archetypes[0].entities().is_empty());
+        }
+
+        #[test]
+        fn can_remove_an_entity_that_has_no_components() {
+            let mut em = EntityManager::new();
 
-            em.remove_entity(&Entity(1));
+            let entity = em.add_entity(vec![]);
 
-            let entity_set = em
-                .components_to_entities
-                .get(TestComponent::name())
-                .expect("TestComponent entry wasn't wiped just because there are no longer any Entities with that component.");
-            let component_map = em.entities_to_components.get(&Entity(1));
+            em.remove_entity(&entity);
 
-            assert_eq!(entity_set.len(), 0);
-            assert!(component_map.is_none());
-            assert_eq!(em.entities_to_components.len(), 0);
-            assert_eq!(em.components_to_entities.len(), 1);
+            assert!(em.entity_locations.is_empty());
+            assert!(em.archetypes[0].entities().is_empty());
         }
 
         #[test]
-        fn can_remove_an_entity_that_has_no_components() {
+        fn removing_an_entity_keeps_other_entities_in_its_archetype_intact() {
             let mut em = EntityManager::new();
 
-            em.entities_to_components.insert(Entity(1), HashMap::new());
+            let entity1 = em.add_entity(vec![Box::new(TestComponent { prop1: 1 })]);
+            let entity2 = em.add_entity(vec![Box::new(TestComponent { prop1: 2 })]);
+            let entity3 = em.add_entity(vec![Box::new(TestComponent { prop1: 3 })]);
+
+            em.remove_entity(&entity1);
+
+            for (entity, expected_prop) in [(entity2, 2), (entity3, 3)] {
+                assert_eq!(
+                    TestComponent::cast(
+                        get_component_on_entity(&em, &entity, TestComponent::id())
+                            .unwrap()
+                            .borrow()
+                            .as_ref()
+                    )
+                    .unwrap()
+                    .prop1,
+                    expected_prop
+                );
+            }
+        }
 
-            em.remove_entity(&Entity(1));
+        #[test]
+        fn removing_a_stale_entity_does_not_remove_the_entity_that_reused_its_id() {
+            let mut em = EntityManager::new();
 
-            let component_map = em.entities_to_components.get(&Entity(1));
+            let stale_entity = em.add_entity(vec![]);
+            em.remove_entity(&stale_entity);
+            let new_entity = em.add_entity(vec![Box::new(TestComponent { prop1: 1 })]);
 
-            assert!(component_map.is_none());
-            assert_eq!(em.entities_to_components.len(), 0);
-            assert_eq!(em.components_to_entities.len(), 0);
+            assert!(!em.remove_entity(&stale_entity));
+            assert!(em.is_alive(&new_entity));
         }
 
         #[test]
@@ -647,46 +938,31 @@ mod tests {
             let mut em = EntityManager::new();
 
             em.add_component_to_entity(
-                &Entity(0),
+                &Entity(0, 0),
                 Box::new(TestComponent { prop1: 5 }) as Box<dyn Component>,
             );
 
-            assert!(em.components_to_entities.is_empty());
-            assert!(em.entities_to_components.is_empty());
+            assert!(em.archetypes.is_empty());
+            assert!(em.entity_locations.is_empty());
         }
 
         #[test]
         fn component_is_correctly_added_on_an_existing_entity() {
             let mut em = EntityManager::new();
 
-            em.entities_to_components.insert(
-                Entity(0),
-                HashMap::from([(
-                    TestComponent::name().to_string(),
-                    Rc::new(RefCell::new(
-                        Box::new(TestComponent { prop1: 5 }) as Box<dyn Component>
-                    )),
-                )]),
-            );
-            em.components_to_entities.insert(
-                TestComponent::name().to_string(),
-                HashSet::from([Entity(0)]),
-            );
+            let entity = em.add_entity(vec![Box::new(TestComponent { prop1: 5 })]);
 
             em.add_component_to_entity(
-                &Entity(0),
+                &entity,
                 Box::new(OtherTestComponent { prop1: 10 }) as Box<dyn Component>,
             );
 
-            assert_eq!(em.components_to_entities.len(), 2);
-            assert_eq!(em.entities_to_components.len(), 1);
+            assert_eq!(em.archetypes.len(), 2);
+            assert_eq!(em.entity_locations.len(), 1);
             assert_eq!(
                 OtherTestComponent::cast(
-                    em.entities_to_components
-                        .get(&Entity(0))
-                        .expect("Entity 0 exists")
-                        .get(OtherTestComponent::name())
-                        .expect("OtherTestComponent is on Entity 0")
+                    get_component_on_entity(&em, &entity, OtherTestComponent::id())
+                        .expect("OtherTestComponent is on the entity")
                         .borrow()
                         .as_ref()
                 )
@@ -694,40 +970,10 @@ mod tests {
                 .prop1,
                 10
             );
-        }
-
-        #[test]
-        fn nothing_happens_when_adding_a_component_to_an_entity_that_it_already_has() {
-            let mut em = EntityManager::new();
-
-            em.entities_to_components.insert(
-                Entity(0),
-                HashMap::from([(
-                    TestComponent::name().to_string(),
-                    Rc::new(RefCell::new(
-                        Box::new(TestComponent { prop1: 5 }) as Box<dyn Component>
-                    )),
-                )]),
-            );
-            em.components_to_entities.insert(
-                TestComponent::name().to_string(),
-                HashSet::from([Entity(0)]),
-            );
-
-            em.add_component_to_entity(
-                &Entity(0),
-                Box::new(TestComponent { prop1: 10 }) as Box<dyn Component>,
-            );
-
-            assert_eq!(em.components_to_entities.len(), 1);
-            assert_eq!(em.entities_to_components.len(), 1);
             assert_eq!(
                 TestComponent::cast(
-                    em.entities_to_components
-                        .get(&Entity(0))
-                        .expect("Entity 0 exists")
-                        .get(TestComponent::name())
-                        .expect("TestComponent is on Entity 0")
+                    get_component_on_entity(&em, &entity, TestComponent::id())
+                        .expect("TestComponent moved with the entity")
                         .borrow()
                         .as_ref()
                 )
@@ -736,101 +982,218 @@ mod tests {
                 5
             );
         }
-    }
 
-    mod test_remove_component_from_entity {
-        use super::*;
+        #[test]
+        fn nothing_happens_when_adding_to_a_stale_entity() {
+            let mut em = EntityManager::new();
+
+            let stale_entity = em.add_entity(vec![]);
+            em.remove_entity(&stale_entity);
+            let new_entity = em.add_entity(vec![]);
+
+            assert!(!em.add_component_to_entity(&stale_entity, Box::new(EmptyComponent {})));
+            assert!(get_component_on_entity(&em, &new_entity, EmptyComponent::id()).is_none());
+        }
 
         #[test]
-        fn removing_a_component_that_does_not_exist_on_the_entity_has_no_effect() {
+        fn entity_moves_out_of_its_old_archetype() {
+            let mut em = EntityManager::new();
+
+            let entity = em.add_entity(vec![Box::new(TestComponent { prop1: 5 })]);
+            let old_archetype_index = em.entity_locations.get(&entity).unwrap().archetype_index;
+
+            em.add_component_to_entity(&entity, Box::new(EmptyComponent {}));
+
+            assert!(em.archetypes[old_archetype_index].entities().is_empty());
+            assert_ne!(
+                em.entity_locations.get(&entity).unwrap().archetype_index,
+                old_archetype_index
+            );
+        }
+
+        #[test]
+        fn nothing_happens_when_adding_a_component_to_an_entity_that_it_already_has() {
             let mut em = EntityManager::new();
 
-            em.entities_to_components.insert(
-                Entity(0),
-                HashMap::from([(
-                    TestComponent::name().to_string(),
-                    Rc::new(RefCell::new(
-                        Box::new(TestComponent { prop1: 5 }) as Box<dyn Component>
-                    )),
-                )]),
+            let entity = em.add_entity(vec![Box::new(TestComponent { prop1: 5 })]);
+
+            em.add_component_to_entity(
+                &entity,
+                Box::new(TestComponent { prop1: 10 }) as Box<dyn Component>,
             );
-            em.components_to_entities.insert(
-                TestComponent::name().to_string(),
-                HashSet::from([Entity(0)]),
+
+            assert_eq!(em.archetypes.len(), 1);
+            assert_eq!(em.entity_locations.len(), 1);
+            assert_eq!(
+                TestComponent::cast(
+                    get_component_on_entity(&em, &entity, TestComponent::id())
+                        .expect("TestComponent is on the entity")
+                        .borrow()
+                        .as_ref()
+                )
+                .expect("TestComponent could be cast.")
+                .prop1,
+                5
             );
+        }
+    }
+
+    mod test_remove_component_from_entity {
+        use super::*;
+
+        #[test]
+        fn removing_a_component_that_does_not_exist_on_the_entity_has_no_effect() {
+            let mut em = EntityManager::new();
 
-            em.remove_component_from_entity(&Entity(0), OtherTestComponent::name());
+            let entity = em.add_entity(vec![Box::new(TestComponent { prop1: 5 })]);
 
-            assert_eq!(em.entities_to_components.len(), 1);
-            assert_eq!(em.components_to_entities.len(), 1);
-            assert!(!em
-                .entities_to_components
-                .get(&Entity(0))
-                .expect("Entity 0 exists")
-                .contains_key(OtherTestComponent::name()));
+            em.remove_component_from_entity(&entity, OtherTestComponent::id());
+
+            assert_eq!(em.archetypes.len(), 1);
+            assert_eq!(em.entity_locations.len(), 1);
+            assert!(get_component_on_entity(&em, &entity, TestComponent::id()).is_some());
         }
 
         #[test]
         fn removing_a_component_on_a_nonexistent_entity_has_no_effect() {
             let mut em = EntityManager::new();
 
-            em.entities_to_components.insert(
-                Entity(0),
-                HashMap::from([(
-                    TestComponent::name().to_string(),
-                    Rc::new(RefCell::new(
-                        Box::new(TestComponent { prop1: 5 }) as Box<dyn Component>
-                    )),
-                )]),
-            );
-            em.components_to_entities.insert(
-                TestComponent::name().to_string(),
-                HashSet::from([Entity(0)]),
-            );
+            let entity = em.add_entity(vec![Box::new(TestComponent { prop1: 5 })]);
 
-            em.remove_component_from_entity(&Entity(1), TestComponent::name());
+            em.remove_component_from_entity(&Entity(entity.0 + 1, 0), TestComponent::id());
 
-            assert_eq!(em.entities_to_components.len(), 1);
-            assert_eq!(em.components_to_entities.len(), 1);
-            assert!(!em
-                .components_to_entities
-                .get(TestComponent::name())
-                .expect("TestComponent is available")
-                .contains(&Entity(1)));
+            assert_eq!(em.archetypes.len(), 1);
+            assert_eq!(em.entity_locations.len(), 1);
+            assert!(get_component_on_entity(&em, &entity, TestComponent::id()).is_some());
         }
 
         #[test]
         fn removing_from_an_existent_entity_succeeds() {
             let mut em = EntityManager::new();
 
-            em.entities_to_components.insert(
-                Entity(0),
-                HashMap::from([(
-                    TestComponent::name().to_string(),
-                    Rc::new(RefCell::new(
-                        Box::new(TestComponent { prop1: 5 }) as Box<dyn Component>
-                    )),
-                )]),
-            );
-            em.components_to_entities.insert(
-                TestComponent::name().to_string(),
-                HashSet::from([Entity(0)]),
-            );
+            let entity = em.add_entity(vec![Box::new(TestComponent { prop1: 5 })]);
+
+            em.remove_component_from_entity(&entity, TestComponent::id());
+
+            assert_eq!(em.archetypes.len(), 2);
+            assert_eq!(em.entity_locations.len(), 1);
+            assert!(get_component_on_entity(&em, &entity, TestComponent::id()).is_none());
+            assert!(em.archetypes[0].entities().is_empty());
+        }
+    }
+
+    mod test_is_alive {
+        use super::*;
+
+        #[test]
+        fn is_true_for_an_entity_in_the_world() {
+            let mut em = EntityManager::new();
+
+            let entity = em.add_entity(vec![]);
+
+            assert!(em.is_alive(&entity));
+        }
+
+        #[test]
+        fn is_false_for_a_removed_entity() {
+            let mut em = EntityManager::new();
+
+            let entity = em.add_entity(vec![]);
+            em.remove_entity(&entity);
+
+            assert!(!em.is_alive(&entity));
+        }
 
-            em.remove_component_from_entity(&Entity(0), TestComponent::name());
-
-            assert_eq!(em.entities_to_components.len(), 1);
-            assert_eq!(em.components_to_entities.len(), 1);
-            assert!(em
-                .entities_to_components
-                .get(&Entity(0))
-                .expect("Entity 0 exists.")
-                .is_empty());
-            assert!(em
-                .components_to_entities
-                .get(TestComponent::name())
-                .expect("There's an entry for TestComponent")
-                .is_empty());
+        #[test]
+        fn is_false_for_a_previous_generation_of_a_reused_id() {
+            let mut em = EntityManager::new();
+
+            let old_entity = em.add_entity(vec![]);
+            em.remove_entity(&old_entity);
+            let new_entity = em.add_entity(vec![]);
+
+            assert!(!em.is_alive(&old_entity));
+            assert!(em.is_alive(&new_entity));
+        }
+    }
+
+    mod test_get_component {
+        use super::*;
+
+        #[test]
+        fn gets_the_component_on_the_entity() {
+            let mut em = EntityManager::new();
+
+            em.add_entity(vec![Box::new(TestComponent { prop1: 1 })]);
+            let entity = em.add_entity(vec![Box::new(TestComponent { prop1: 2 })]);
+
+            assert_eq!(em.get_component::<TestComponent>(&entity).unwrap().prop1, 2);
+        }
+
+        #[test]
+        fn is_none_when_the_entity_does_not_have_the_component() {
+            let mut em = EntityManager::new();
+
+            let entity = em.add_entity(vec![Box::new(EmptyComponent {})]);
+
+            assert!(em.get_component::<TestComponent>(&entity).is_none());
+        }
+
+        #[test]
+        fn is_none_for_a_stale_entity() {
+            let mut em = EntityManager::new();
+
+            let old_entity = em.add_entity(vec![Box::new(TestComponent { prop1: 1 })]);
+            em.remove_entity(&old_entity);
+            em.add_entity(vec![Box::new(TestComponent { prop1: 2 })]);
+
+            assert!(em.get_component::<TestComponent>(&old_entity).is_none());
+        }
+    }
+
+    mod test_get_component_mut {
+        use super::*;
+
+        #[test]
+        fn changes_are_kept_on_the_entity() {
+            let mut em = EntityManager::new();
+
+            let entity = em.add_entity(vec![Box::new(TestComponent { prop1: 1 })]);
+
+            em.get_component_mut::<TestComponent>(&entity)
+                .unwrap()
+                .prop1 = 5;
+
+            assert_eq!(em.get_component::<TestComponent>(&entity).unwrap().prop1, 5);
+        }
+
+        #[test]
+        fn marks_the_component_as_changed() {
+            let mut em = EntityManager::new();
+
+            let entity = em.add_entity(vec![Box::new(TestComponent { prop1: 1 })]);
+            let last_run_tick = em.start_system_run(0);
+            em.start_system_run(last_run_tick);
+
+            em.get_component_mut::<TestComponent>(&entity);
+
+            assert_eq!(em.query(&Query::new().changed::<TestComponent>()).len(), 1);
+        }
+    }
+
+    mod test_entities {
+        use super::*;
+
+        #[test]
+        fn sorts_a_reused_id_ahead_of_higher_ids() {
+            let mut em = EntityManager::new();
+
+            let first = em.add_entity(vec![]);
+            let second = em.add_entity(vec![]);
+            em.remove_entity(&first);
+            let third = em.add_entity(vec![]);
+
+            assert_eq!(em.entities(), vec![third, second]);
         }
     }
 
@@ -856,6 +1219,63 @@ mod tests {
                 assert_eq!(*query_results.get(0).unwrap().entity(), entity);
             }
 
+            #[test]
+            fn components_with_the_same_name_in_different_modules_are_not_confused() {
+                mod other {
+                    use crate::Component;
+
+                    #[derive(Component)]
+                    pub struct TestComponent {
+                        pub prop1: u8,
+                    }
+                }
+
+                let mut em = EntityManager::new();
+
+                let entity = em.add_entity(vec![
+                    Box::new(TestComponent { prop1: 1 }),
+                    Box::new(other::TestComponent { prop1: 2 }),
+                ]);
+                em.add_entity(vec![Box::new(TestComponent { prop1: 3 })]);
+
+                let query_results = em.query(&Query::new().has::<other::TestComponent>());
+
+                assert_eq!(query_results.len(), 1);
+                assert_eq!(*query_results[0].entity(), entity);
+                assert_eq!(
+                    query_results[0]
+                        .components()
+                        .get::<other::TestComponent>()
+                        .prop1,
+                    2
+                );
+            }
+
+            #[test]
+            fn generic_components_with_different_type_arguments_are_not_confused() {
+                #[derive(Component)]
+                struct GenericComponent<T> {
+                    value: T,
+                }
+
+                let mut em = EntityManager::new();
+
+                em.add_entity(vec![Box::new(GenericComponent { value: 1_u8 })]);
+                let entity = em.add_entity(vec![Box::new(GenericComponent { value: 2_u16 })]);
+
+                let query_results = em.query(&Query::new().has::<GenericComponent<u16>>());
+
+                assert_eq!(query_results.len(), 1);
+                assert_eq!(*query_results[0].entity(), entity);
+                assert_eq!(
+                    query_results[0]
+                        .components()
+                        .get::<GenericComponent<u16>>()
+                        .value,
+                    2
+                );
+            }
+
             #[test]
             fn complex_query_for_more_than_one_component_match_works() {
                 let mut em = EntityManager::new();
@@ -1194,418 +1614,685 @@ mod tests {
                     .is_some());
             }
         }
-    }
 
-    mod test_entity_components_pass_all_predicates {
-        use super::*;
+        mod with_optional_components {
+            use super::*;
 
-        #[test]
-        fn is_true_when_all_predicates_pass() {
-            let mut em = EntityManager::new();
+            #[test]
+            fn optional_components_do_not_filter_matches() {
+                let mut em = EntityManager::new();
 
-            let entity = em.add_entity(vec![
-                Box::new(TestComponent { prop1: 10 }),
-                Box::new(AnotherTestComponent { prop1: 100 }),
-                Box::new(EmptyComponent {}),
-            ]);
+                em.add_entity(vec![
+                    Box::new(TestComponent { prop1: 10 }),
+                    Box::new(OtherTestComponent { prop1: 20 }),
+                ]);
+                em.add_entity(vec![Box::new(TestComponent { prop1: 30 })]);
+                em.add_entity(vec![Box::new(OtherTestComponent { prop1: 40 })]);
 
-            let result = EntityManager::entity_components_pass_all_predicates(
-                &em.entities_to_components,
-                &entity,
-                &vec![
-                    ComponentQueryData::new(
-                        TestComponent::name(),
-                        Some(Box::new(|comp| {
-                            let test_component = TestComponent::cast(comp).unwrap();
+                let query_results = em.query(
+                    &Query::new()
+                        .has::<TestComponent>()
+                        .has_optional::<OtherTestComponent>(),
+                );
 
-                            test_component.prop1 == 10
-                        })),
-                    ),
-                    ComponentQueryData::new(
-                        AnotherTestComponent::name(),
-                        Some(Box::new(|comp| {
-                            let another_test_component = AnotherTestComponent::cast(comp).unwrap();
+                assert_eq!(query_results.len(), 2);
+            }
 
-                            another_test_component.prop1 == 100
-                        })),
-                    ),
-                ],
-            );
+            #[test]
+            fn optional_components_are_included_when_present() {
+                let mut em = EntityManager::new();
 
-            assert!(result);
-        }
+                let entity1 = em.add_entity(vec![
+                    Box::new(TestComponent { prop1: 10 }),
+                    Box::new(OtherTestComponent { prop1: 20 }),
+                ]);
+                let entity2 = em.add_entity(vec![Box::new(TestComponent { prop1: 30 })]);
 
-        #[test]
-        fn is_true_when_there_are_no_predicates() {
-            let mut em = EntityManager::new();
+                let query_results = em.query(
+                    &Query::new()
+                        .has::<TestComponent>()
+                        .has_optional::<OtherTestComponent>(),
+                );
 
-            let entity = em.add_entity(vec![
-                Box::new(TestComponent { prop1: 10 }),
-                Box::new(AnotherTestComponent { prop1: 100 }),
-                Box::new(EmptyComponent {}),
-            ]);
+                let result1 = query_results
+                    .iter()
+                    .find(|result| *result.entity() == entity1)
+                    .unwrap();
+                let result2 = query_results
+                    .iter()
+                    .find(|result| *result.entity() == entity2)
+                    .unwrap();
 
-            let result = EntityManager::entity_components_pass_all_predicates(
-                &em.entities_to_components,
-                &entity,
-                &vec![
-                    ComponentQueryData::new(TestComponent::name(), None),
-                    ComponentQueryData::new(AnotherTestComponent::name(), None),
-                ],
-            );
+                assert_eq!(
+                    result1
+                        .components()
+                        .try_get::<OtherTestComponent>()
+                        .unwrap()
+                        .prop1,
+                    20
+                );
+                assert!(result2
+                    .components()
+                    .try_get::<OtherTestComponent>()
+                    .is_none());
+            }
 
-            assert!(result);
+            #[test]
+            fn a_query_with_only_optional_components_has_no_matches() {
+                let mut em = EntityManager::new();
+
+                em.add_entity(vec![Box::new(TestComponent { prop1: 10 })]);
+
+                let query_results = em.query(&Query::new().has_optional::<TestComponent>());
+
+                assert_eq!(query_results.len(), 0);
+            }
         }
 
-        #[test]
-        fn is_false_when_all_predicates_fail() {
-            let mut em = EntityManager::new();
+        mod with_any_of_components {
+            use super::*;
 
-            let entity = em.add_entity(vec![
-                Box::new(TestComponent { prop1: 10 }),
-                Box::new(AnotherTestComponent { prop1: 100 }),
-                Box::new(EmptyComponent {}),
-            ]);
+            #[test]
+            fn matches_entities_with_any_of_the_components() {
+                let mut em = EntityManager::new();
 
-            let result = EntityManager::entity_components_pass_all_predicates(
-                &em.entities_to_components,
-                &entity,
-                &vec![
-                    ComponentQueryData::new(
-                        TestComponent::name(),
-                        Some(Box::new(|comp| {
-                            let test_component = TestComponent::cast(comp).unwrap();
+                let entity1 = em.add_entity(vec![Box::new(TestComponent { prop1: 10 })]);
+                let entity2 = em.add_entity(vec![Box::new(OtherTestComponent { prop1: 20 })]);
+                let entity3 = em.add_entity(vec![
+                    Box::new(TestComponent { prop1: 30 }),
+                    Box::new(OtherTestComponent { prop1: 40 }),
+                ]);
+                em.add_entity(vec![Box::new(EmptyComponent {})]);
 
-                            test_component.prop1 == 99
-                        })),
-                    ),
-                    ComponentQueryData::new(
-                        AnotherTestComponent::name(),
-                        Some(Box::new(|comp| {
-                            let another_test_component = AnotherTestComponent::cast(comp).unwrap();
+                let query_results =
+                    em.query(&Query::new().has_any::<(TestComponent, OtherTestComponent)>());
 
-                            another_test_component.prop1 == 5
-                        })),
-                    ),
-                ],
-            );
+                assert_eq!(query_results.len(), 3);
+                for entity in [entity1, entity2, entity3] {
+                    assert!(query_results
+                        .iter()
+                        .any(|result| *result.entity() == entity));
+                }
+            }
 
-            assert!(!result);
-        }
+            #[test]
+            fn includes_whichever_of_the_components_are_present() {
+                let mut em = EntityManager::new();
 
-        #[test]
-        fn is_false_when_any_predicate_fails() {
-            let mut em = EntityManager::new();
+                em.add_entity(vec![Box::new(OtherTestComponent { prop1: 20 })]);
 
-            let entity = em.add_entity(vec![
-                Box::new(TestComponent { prop1: 10 }),
-                Box::new(AnotherTestComponent { prop1: 100 }),
-                Box::new(EmptyComponent {}),
-            ]);
+                let query_results =
+                    em.query(&Query::new().has_any::<(TestComponent, OtherTestComponent)>());
+
+                assert!(query_results[0]
+                    .components()
+                    .try_get::<TestComponent>()
+                    .is_none());
+                assert_eq!(
+                    query_results[0]
+                        .components()
+                        .try_get::<OtherTestComponent>()
+                        .unwrap()
+                        .prop1,
+                    20
+                );
+            }
 
-            let result = EntityManager::entity_components_pass_all_predicates(
-                &em.entities_to_components,
-                &entity,
-                &vec![
-                    ComponentQueryData::new(
-                        TestComponent::name(),
-                        Some(Box::new(|comp| {
-                            let test_component = TestComponent::cast(comp).unwrap();
+            #[test]
+            fn every_group_must_be_satisfied() {
+                let mut em = EntityManager::new();
 
-                            test_component.prop1 == 10
-                        })),
-                    ),
-                    ComponentQueryData::new(
-                        AnotherTestComponent::name(),
-                        Some(Box::new(|comp| {
-                            let another_test_component = AnotherTestComponent::cast(comp).unwrap();
+                let entity = em.add_entity(vec![
+                    Box::new(TestComponent { prop1: 10 }),
+                    Box::new(EmptyComponent {}),
+                ]);
+                em.add_entity(vec![Box::new(TestComponent { prop1: 20 })]);
+                em.add_entity(vec![Box::new(EmptyComponent {})]);
 
-                            another_test_component.prop1 == 5
-                        })),
-                    ),
-                ],
-            );
+                let query_results = em.query(
+                    &Query::new()
+                        .has_any::<(TestComponent, OtherTestComponent)>()
+                        .has_any::<(EmptyComponent, AnotherTestComponent)>(),
+                );
 
-            assert!(!result);
-        }
-    }
+                assert_eq!(query_results.len(), 1);
+                assert_eq!(*query_results[0].entity(), entity);
+            }
 
-    mod test_get_entities_with_component {
-        use super::*;
+            #[test]
+            fn combines_with_required_and_forbidden_components() {
+                let mut em = EntityManager::new();
 
-        #[test]
-        fn is_empty_when_there_are_no_entities() {
-            let em = EntityManager::new();
+                let entity = em.add_entity(vec![
+                    Box::new(TestComponent { prop1: 10 }),
+                    Box::new(OtherTestComponent { prop1: 20 }),
+                ]);
+                em.add_entity(vec![Box::new(TestComponent { prop1: 30 })]);
+                em.add_entity(vec![
+                    Box::new(TestComponent { prop1: 40 }),
+                    Box::new(AnotherTestComponent { prop1: 50 }),
+                    Box::new(EmptyComponent {}),
+                ]);
 
-            let result = EntityManager::get_entities_with_component(
-                &em.components_to_entities,
-                EmptyComponent::name(),
-            );
+                let query_results = em.query(
+                    &Query::new()
+                        .has::<TestComponent>()
+                        .has_any::<(OtherTestComponent, AnotherTestComponent)>()
+                        .has_no::<EmptyComponent>(),
+                );
 
-            assert!(result.is_empty());
+                assert_eq!(query_results.len(), 1);
+                assert_eq!(*query_results[0].entity(), entity);
+            }
         }
 
-        #[test]
-        fn is_empty_when_no_entities_have_the_provided_component() {
-            let mut em = EntityManager::new();
+        mod with_entity_filters {
+            use super::*;
 
-            em.add_entity(vec![Box::new(TestComponent { prop1: 10 })]);
+            #[test]
+            fn only_matches_the_provided_entity() {
+                let mut em = EntityManager::new();
 
-            let result = EntityManager::get_entities_with_component(
-                &em.components_to_entities,
-                EmptyComponent::name(),
-            );
+                em.add_entity(vec![Box::new(TestComponent { prop1: 1 })]);
+                let entity = em.add_entity(vec![Box::new(TestComponent { prop1: 2 })]);
+
+                let query_results =
+                    em.query(&Query::new().has::<TestComponent>().for_entity(entity));
+
+                assert_eq!(query_results.len(), 1);
+                assert_eq!(*query_results[0].entity(), entity);
+            }
+
+            #[test]
+            fn matches_must_be_in_every_list_of_entities() {
+                let mut em = EntityManager::new();
+
+                let first = em.add_entity(vec![Box::new(TestComponent { prop1: 1 })]);
+                let second = em.add_entity(vec![Box::new(TestComponent { prop1: 2 })]);
+                let third = em.add_entity(vec![Box::new(TestComponent { prop1: 3 })]);
+
+                let query_results = em.query(
+                    &Query::new()
+                        .has::<TestComponent>()
+                        .for_entities(vec![first, second])
+                        .for_entities(vec![second, third]),
+                );
 
-            assert!(result.is_empty());
+                assert_eq!(query_results.len(), 1);
+                assert_eq!(*query_results[0].entity(), second);
+            }
+
+            #[test]
+            fn entities_still_have_to_satisfy_the_rest_of_the_query() {
+                let mut em = EntityManager::new();
+
+                let entity = em.add_entity(vec![Box::new(TestComponent { prop1: 1 })]);
+
+                let query_results = em.query(
+                    &Query::new()
+                        .has::<AnotherTestComponent>()
+                        .for_entity(entity),
+                );
+
+                assert!(query_results.is_empty());
+            }
+
+            #[test]
+            fn matches_the_entity_without_any_components_in_the_query() {
+                let mut em = EntityManager::new();
+
+                em.add_entity(vec![Box::new(TestComponent { prop1: 1 })]);
+                let entity = em.add_entity(vec![Box::new(AnotherTestComponent { prop1: 2 })]);
+
+                let query_results = em.query(&Query::new().for_entity(entity));
+
+                assert_eq!(query_results.len(), 1);
+                assert_eq!(*query_results[0].entity(), entity);
+            }
+
+            #[test]
+            fn does_not_match_removed_entities() {
+                let mut em = EntityManager::new();
+
+                let entity = em.add_entity(vec![Box::new(TestComponent { prop1: 1 })]);
+                em.remove_entity(&entity);
+
+                let query_results =
+                    em.query(&Query::new().has::<TestComponent>().for_entity(entity));
+
+                assert!(query_results.is_empty());
+            }
+
+            #[test]
+            fn does_not_match_the_new_generation_of_a_reused_id() {
+                let mut em = EntityManager::new();
+
+                let old_entity = em.add_entity(vec![Box::new(TestComponent { prop1: 1 })]);
+                em.remove_entity(&old_entity);
+                let new_entity = em.add_entity(vec![Box::new(TestComponent { prop1: 2 })]);
+
+                let old_results =
+                    em.query(&Query::new().has::<TestComponent>().for_entity(old_entity));
+                let new_results =
+                    em.query(&Query::new().has::<TestComponent>().for_entity(new_entity));
+
+                assert!(old_results.is_empty());
+                assert_eq!(new_results.len(), 1);
+                assert_eq!(new_results.get_only::<TestComponent>().prop1, 2);
+            }
         }
 
-        #[test]
-        fn works_when_one_entity_matches() {
-            let mut em = EntityManager::new();
+        mod with_change_filters {
+            use super::*;
 
-            em.add_entity(vec![Box::new(TestComponent { prop1: 10 })]);
-            let entity1 = em.add_entity(vec![Box::new(EmptyComponent {})]);
+            #[test]
+            fn everything_is_added_and_changed_for_a_system_that_has_never_run() {
+                let mut em = EntityManager::new();
 
-            let result = EntityManager::get_entities_with_component(
-                &em.components_to_entities,
-                EmptyComponent::name(),
-            );
+                em.add_entity(vec![Box::new(TestComponent { prop1: 1 })]);
+
+                em.start_system_run(0);
+
+                assert_eq!(em.query(&Query::new().added::<TestComponent>()).len(), 1);
+                assert_eq!(em.query(&Query::new().changed::<TestComponent>()).len(), 1);
+            }
+
+            #[cfg(feature = "parallel")]
+            #[test]
+            fn use_the_last_run_tick_of_the_system_running_on_the_thread() {
+                let mut em = EntityManager::new();
+
+                em.add_entity(vec![Box::new(TestComponent { prop1: 1 })]);
+                let last_run_tick = em.start_system_run(0);
+
+                em.increment_change_tick();
+                em.add_entity(vec![Box::new(TestComponent { prop1: 2 })]);
+
+                em.start_system_run(0);
+
+                let query_results = run_in_parallel_system(last_run_tick, || {
+                    em.query(&Query::new().added::<TestComponent>())
+                });
+
+                assert_eq!(query_results.len(), 1);
+            }
+
+            #[test]
+            fn added_only_matches_components_added_since_the_last_run() {
+                let mut em = EntityManager::new();
+
+                em.add_entity(vec![Box::new(TestComponent { prop1: 1 })]);
+                let last_run_tick = em.start_system_run(0);
+
+                em.increment_change_tick();
+                let new_entity = em.add_entity(vec![Box::new(TestComponent { prop1: 2 })]);
+                let existing_entity = em.add_entity(vec![Box::new(EmptyComponent {})]);
+                em.add_component_to_entity(&existing_entity, Box::new(TestComponent { prop1: 3 }));
+
+                em.start_system_run(last_run_tick);
+
+                let query_results = em.query(&Query::new().added::<TestComponent>());
+
+                assert_eq!(query_results.len(), 2);
+                assert!(query_results
+                    .iter()
+                    .any(|result| *result.entity() == new_entity));
+                assert!(query_results
+                    .iter()
+                    .any(|result| *result.entity() == existing_entity));
+            }
+
+            #[test]
+            fn changed_matches_components_mutably_borrowed_since_the_last_run() {
+                let mut em = EntityManager::new();
+
+                let entity = em.add_entity(vec![Box::new(TestComponent { prop1: 1 })]);
+                em.add_entity(vec![Box::new(TestComponent { prop1: 2 })]);
+
+                let first_system_last_run_tick = em.start_system_run(0);
+
+                em.start_system_run(0);
+                em.query(&Query::new().has_where::<TestComponent>(|comp| comp.prop1 == 1))[0]
+                    .components()
+                    .get_mut::<TestComponent>()
+                    .prop1 = 10;
+
+                em.start_system_run(first_system_last_run_tick);
+
+                let query_results = em.query(&Query::new().changed::<TestComponent>());
+
+                assert_eq!(query_results.len(), 1);
+                assert_eq!(*query_results[0].entity(), entity);
+                assert_eq!(
+                    query_results[0].components().get::<TestComponent>().prop1,
+                    10
+                );
+            }
+
+            #[test]
+            fn immutably_borrowing_a_component_does_not_change_it() {
+                let mut em = EntityManager::new();
+
+                em.add_entity(vec![Box::new(TestComponent { prop1: 1 })]);
+
+                let last_run_tick = em.start_system_run(0);
+                em.query(&Query::new().has::<TestComponent>())[0]
+                    .components()
+                    .get::<TestComponent>();
+
+                em.start_system_run(last_run_tick);
+
+                assert_eq!(em.query(&Query::new().changed::<TestComponent>()).len(), 0);
+            }
+
+            #[test]
+            fn a_system_does_not_see_its_own_changes() {
+                let mut em = EntityManager::new();
+
+                em.add_entity(vec![Box::new(TestComponent { prop1: 1 })]);
+
+                let last_run_tick = em.start_system_run(0);
+                em.query(&Query::new().changed::<TestComponent>())[0]
+                    .components()
+                    .get_mut::<TestComponent>()
+                    .prop1 = 2;
+
+                em.start_system_run(last_run_tick);
+
+                assert_eq!(em.query(&Query::new().changed::<TestComponent>()).len(), 0);
+            }
 
-            assert_eq!(result.len(), 1);
-            assert_eq!(result[0], entity1);
+            #[test]
+            fn components_keep_their_ticks_when_their_entity_changes_archetypes() {
+                let mut em = EntityManager::new();
+
+                let entity = em.add_entity(vec![Box::new(TestComponent { prop1: 1 })]);
+                let last_run_tick = em.start_system_run(0);
+
+                em.increment_change_tick();
+                em.add_component_to_entity(&entity, Box::new(EmptyComponent {}));
+
+                em.start_system_run(last_run_tick);
+
+                assert_eq!(em.query(&Query::new().added::<TestComponent>()).len(), 0);
+                assert_eq!(em.query(&Query::new().added::<EmptyComponent>()).len(), 1);
+            }
         }
+    }
+
+    mod test_removed_components {
+        use super::*;
 
         #[test]
-        fn works_when_multiple_entities_match() {
+        fn includes_components_removed_from_entities() {
             let mut em = EntityManager::new();
 
-            let entity1 = em.add_entity(vec![Box::new(TestComponent { prop1: 10 })]);
-            let entity2 = em.add_entity(vec![
-                Box::new(TestComponent { prop1: 100 }),
+            let entity = em.add_entity(vec![
+                Box::new(TestComponent { prop1: 5 }),
                 Box::new(EmptyComponent {}),
             ]);
+            let last_run_tick = em.start_system_run(0);
 
-            let result = EntityManager::get_entities_with_component(
-                &em.components_to_entities,
-                TestComponent::name(),
-            );
+            em.increment_change_tick();
+            em.remove_component_from_entity(&entity, TestComponent::id());
+
+            em.start_system_run(last_run_tick);
 
-            assert_eq!(result.len(), 2);
-            assert!(result.contains(&entity1) && result.contains(&entity2));
+            let removed = em.removed_components::<TestComponent>();
+
+            assert_eq!(removed.len(), 1);
+            assert_eq!(removed[0].0, entity);
+            assert_eq!(removed[0].1.prop1, 5);
+            assert!(em.removed_components::<EmptyComponent>().is_empty());
         }
-    }
 
-    mod test_get_entities_with_components {
-        use super::*;
+        #[test]
+        fn includes_components_of_destroyed_entities() {
+            let mut em = EntityManager::new();
+
+            let entity = em.add_entity(vec![Box::new(TestComponent { prop1: 5 })]);
+            let last_run_tick = em.start_system_run(0);
+
+            em.increment_change_tick();
+            em.remove_entity(&entity);
+
+            em.start_system_run(last_run_tick);
+
+            assert_eq!(em.removed_components::<TestComponent>().len(), 1);
+        }
 
         #[test]
-        fn is_empty_when_there_are_no_entities() {
-            let em = EntityManager::new();
+        fn excludes_components_removed_before_the_last_run() {
+            let mut em = EntityManager::new();
 
-            let result = EntityManager::get_entities_with_components(
-                &em.components_to_entities,
-                &vec![EmptyComponent::name()],
-            );
+            let entity = em.add_entity(vec![Box::new(TestComponent { prop1: 5 })]);
+            em.remove_entity(&entity);
 
-            assert!(result.is_empty());
+            let last_run_tick = em.start_system_run(0);
+            em.start_system_run(last_run_tick);
+
+            assert!(em.removed_components::<TestComponent>().is_empty());
         }
 
         #[test]
-        fn is_empty_when_no_entities_have_provided_components() {
+        fn are_dropped_once_a_full_frame_has_passed() {
             let mut em = EntityManager::new();
 
-            em.add_entity(vec![Box::new(TestComponent { prop1: 10 })]);
+            em.start_frame();
+            let entity = em.add_entity(vec![Box::new(TestComponent { prop1: 5 })]);
+            em.remove_entity(&entity);
 
-            let result = EntityManager::get_entities_with_components(
-                &em.components_to_entities,
-                &vec![EmptyComponent::name()],
-            );
+            em.start_frame();
+            em.start_system_run(0);
+            assert_eq!(em.removed_components::<TestComponent>().len(), 1);
 
-            assert!(result.is_empty());
+            em.start_frame();
+            em.start_system_run(0);
+            assert!(em.removed_components::<TestComponent>().is_empty());
         }
+    }
+
+    mod test_row_passes_all_predicates {
+        use super::*;
 
         #[test]
-        fn returns_entity_list_for_component_when_searching_for_one_component() {
+        fn is_true_when_all_predicates_pass() {
             let mut em = EntityManager::new();
 
-            em.add_entity(vec![Box::new(TestComponent { prop1: 10 })]);
-            let entity2 = em.add_entity(vec![
-                Box::new(AnotherTestComponent { prop1: 20 }),
+            let entity = em.add_entity(vec![
+                Box::new(TestComponent { prop1: 10 }),
+                Box::new(AnotherTestComponent { prop1: 100 }),
                 Box::new(EmptyComponent {}),
             ]);
 
-            let result = EntityManager::get_entities_with_components(
-                &em.components_to_entities,
-                &vec![EmptyComponent::name()],
+            let location = em.entity_locations.get(&entity).unwrap();
+
+            let result = EntityManager::row_passes_all_predicates(
+                &em.archetypes[location.archetype_index],
+                location.row,
+                &[
+                    ComponentQueryData::new(
+                        TestComponent::id(),
+                        Some(Box::new(|comp| {
+                            let test_component = TestComponent::cast(comp).unwrap();
+
+                            test_component.prop1 == 10
+                        })),
+                    ),
+                    ComponentQueryData::new(
+                        AnotherTestComponent::id(),
+                        Some(Box::new(|comp| {
+                            let another_test_component = AnotherTestComponent::cast(comp).unwrap();
+
+                            another_test_component.prop1 == 100
+                        })),
+                    ),
+                ],
             );
 
-            assert_eq!(result.len(), 1);
-            assert_eq!(result[0], entity2);
+            assert!(result);
         }
 
         #[test]
-        fn works_when_one_entity_has_all_provided_components() {
+        fn is_true_when_there_are_no_predicates() {
             let mut em = EntityManager::new();
 
-            em.add_entity(vec![Box::new(TestComponent { prop1: 10 })]);
-            let entity2 = em.add_entity(vec![
-                Box::new(AnotherTestComponent { prop1: 20 }),
+            let entity = em.add_entity(vec![
+                Box::new(TestComponent { prop1: 10 }),
+                Box::new(AnotherTestComponent { prop1: 100 }),
                 Box::new(EmptyComponent {}),
-                Box::new(OtherTestComponent { prop1: 200 }),
             ]);
 
-            let result = EntityManager::get_entities_with_components(
-                &em.components_to_entities,
-                &vec![AnotherTestComponent::name(), OtherTestComponent::name()],
+            let location = em.entity_locations.get(&entity).unwrap();
+
+            let result = EntityManager::row_passes_all_predicates(
+                &em.archetypes[location.archetype_index],
+                location.row,
+                &[
+                    ComponentQueryData::new(TestComponent::id(), None),
+                    ComponentQueryData::new(AnotherTestComponent::id(), None),
+                ],
             );
 
-            assert_eq!(result.len(), 1);
-            assert!(result.contains(&entity2));
+            assert!(result);
         }
 
         #[test]
-        fn works_when_multiple_entities_have_all_provided_components() {
+        fn is_false_when_all_predicates_fail() {
             let mut em = EntityManager::new();
 
-            let entity1 = em.add_entity(vec![
+            let entity = em.add_entity(vec![
                 Box::new(TestComponent { prop1: 10 }),
+                Box::new(AnotherTestComponent { prop1: 100 }),
                 Box::new(EmptyComponent {}),
             ]);
-            em.add_entity(vec![Box::new(TestComponent { prop1: 10 })]);
-            em.add_entity(vec![Box::new(AnotherTestComponent { prop1: 10 })]);
-            em.add_entity(vec![Box::new(EmptyComponent {})]);
-            let entity2 = em.add_entity(vec![
-                Box::new(TestComponent { prop1: 20 }),
-                Box::new(EmptyComponent {}),
-                Box::new(OtherTestComponent { prop1: 200 }),
-            ]);
 
-            let result = EntityManager::get_entities_with_components(
-                &em.components_to_entities,
-                &vec![TestComponent::name(), EmptyComponent::name()],
-            );
+            let location = em.entity_locations.get(&entity).unwrap();
 
-            assert_eq!(result.len(), 2);
-            assert!(result.contains(&entity1));
-            assert!(result.contains(&entity2));
-        }
-    }
-
-    mod test_get_components_on_entity {
-        use super::*;
+            let result = EntityManager::row_passes_all_predicates(
+                &em.archetypes[location.archetype_index],
+                location.row,
+                &[
+                    ComponentQueryData::new(
+                        TestComponent::id(),
+                        Some(Box::new(|comp| {
+                            let test_component = TestComponent::cast(comp).unwrap();
 
-        #[test]
-        fn is_empty_for_non_existent_entity() {
-            let em = EntityManager::new();
+                            test_component.prop1 == 99
+                        })),
+                    ),
+                    ComponentQueryData::new(
+                        AnotherTestComponent::id(),
+                        Some(Box::new(|comp| {
+                            let another_test_component = AnotherTestComponent::cast(comp).unwrap();
 
-            let results = EntityManager::get_components_on_entity(
-                &em.entities_to_components,
-                &Entity(0),
-                &vec![TestComponent::name()],
+                            another_test_component.prop1 == 5
+                        })),
+                    ),
+                ],
             );
 
-            assert!(results.is_empty());
+            assert!(!result);
         }
 
         #[test]
-        fn is_empty_for_entity_with_no_components() {
+        fn is_false_when_any_predicate_fails() {
             let mut em = EntityManager::new();
 
-            let entity = em.add_entity(vec![]);
+            let entity = em.add_entity(vec![
+                Box::new(TestComponent { prop1: 10 }),
+                Box::new(AnotherTestComponent { prop1: 100 }),
+                Box::new(EmptyComponent {}),
+            ]);
 
-            let results = EntityManager::get_components_on_entity(
-                &em.entities_to_components,
-                &entity,
-                &vec![TestComponent::name()],
+            let location = em.entity_locations.get(&entity).unwrap();
+
+            let result = EntityManager::row_passes_all_predicates(
+                &em.archetypes[location.archetype_index],
+                location.row,
+                &[
+                    ComponentQueryData::new(
+                        TestComponent::id(),
+                        Some(Box::new(|comp| {
+                            let test_component = TestComponent::cast(comp).unwrap();
+
+                            test_component.prop1 == 10
+                        })),
+                    ),
+                    ComponentQueryData::new(
+                        AnotherTestComponent::id(),
+                        Some(Box::new(|comp| {
+                            let another_test_component = AnotherTestComponent::cast(comp).unwrap();
+
+                            another_test_component.prop1 == 5
+                        })),
+                    ),
+                ],
             );
 
-            assert!(results.is_empty());
+            assert!(!result);
         }
+    }
+
+    mod test_get_components_in_row {
+        use super::*;
 
         #[test]
         fn is_empty_when_no_search_components_are_provided() {
             let mut em = EntityManager::new();
 
-            let entity = em.add_entity(vec![Box::new(EmptyComponent {})]);
+            em.add_entity(vec![Box::new(EmptyComponent {})]);
 
-            let results = EntityManager::get_components_on_entity(
-                &em.entities_to_components,
-                &entity,
-                &vec![],
-            );
+            let results = em.get_components_in_row(&em.archetypes[0], 0, &[]);
 
             assert!(results.is_empty());
         }
 
         #[test]
-        fn works_when_searching_for_one_component_on_entity_with_that_component() {
+        fn is_empty_when_the_archetype_does_not_have_the_search_components() {
             let mut em = EntityManager::new();
 
-            let entity = em.add_entity(vec![Box::new(TestComponent { prop1: 10 })]);
+            em.add_entity(vec![Box::new(EmptyComponent {})]);
 
-            let results = EntityManager::get_components_on_entity(
-                &em.entities_to_components,
-                &entity,
-                &vec![TestComponent::name()],
-            );
+            let results = em.get_components_in_row(&em.archetypes[0], 0, &[TestComponent::id()]);
 
-            assert_eq!(results.len(), 1);
-            assert_eq!(results.get::<TestComponent>().prop1, 10);
+            assert!(results.is_empty());
         }
 
         #[test]
-        fn works_when_searching_for_one_component_on_entity_with_multiple_components() {
+        fn works_when_searching_for_one_component_in_an_archetype_with_multiple_components() {
             let mut em = EntityManager::new();
 
-            let entity = em.add_entity(vec![
+            em.add_entity(vec![
                 Box::new(TestComponent { prop1: 10 }),
                 Box::new(EmptyComponent {}),
             ]);
 
-            let results = EntityManager::get_components_on_entity(
-                &em.entities_to_components,
-                &entity,
-                &vec![TestComponent::name()],
-            );
-
-            assert_eq!(results.len(), 1);
-            assert_eq!(results.get::<TestComponent>().prop1, 10);
-        }
-
-        #[test]
-        fn works_when_searching_for_multiple_components_on_entity_with_one_component() {
-            let mut em = EntityManager::new();
-
-            let entity = em.add_entity(vec![Box::new(TestComponent { prop1: 10 })]);
-
-            let results = EntityManager::get_components_on_entity(
-                &em.entities_to_components,
-                &entity,
-                &vec![TestComponent::name()],
-            );
+            let results = em.get_components_in_row(&em.archetypes[0], 0, &[TestComponent::id()]);
 
             assert_eq!(results.len(), 1);
             assert_eq!(results.get::<TestComponent>().prop1, 10);
         }
 
         #[test]
-        fn works_when_searching_for_multiple_components_on_entity_with_multiple_components() {
+        fn works_when_searching_for_multiple_components_in_an_archetype_with_multiple_components() {
             let mut em = EntityManager::new();
 
-            let entity = em.add_entity(vec![
+            em.add_entity(vec![Box::new(TestComponent { prop1: 1 })]);
+            em.add_entity(vec![
                 Box::new(TestComponent { prop1: 10 }),
                 Box::new(EmptyComponent {}),
                 Box::new(OtherTestComponent { prop1: 100 }),
                 Box::new(AnotherTestComponent { prop1: 200 }),
             ]);
 
-            let results = EntityManager::get_components_on_entity(
-                &em.entities_to_components,
-                &entity,
-                &vec![
-                    TestComponent::name(),
-                    EmptyComponent::name(),
-                    AnotherTestComponent::name(),
+            let results = em.get_components_in_row(
+                &em.archetypes[1],
+                0,
+                &[
+                    TestComponent::id(),
+                    EmptyComponent::id(),
+                    AnotherTestComponent::id(),
                 ],
             );
 
@@ -1621,63 +2308,4 @@ mod tests {
             assert!(empty_component_option.is_some());
         }
     }
-
-    mod test_intersection {
-        use super::*;
-
-        #[test]
-        fn is_empty_when_there_are_no_vectors() {
-            let vecs: Vec<Vec<u32>> = vec![];
-
-            let result = intersection(&vecs);
-
-            assert!(result.is_empty());
-        }
-
-        #[test]
-        fn is_empty_when_there_is_only_one_vector() {
-            let vecs: Vec<Vec<u32>> = vec![vec![1, 4, 5]];
-
-            let result = intersection(&vecs);
-
-            assert!(result.is_empty());
-        }
-
-        #[test]
-        fn is_empty_when_there_are_no_intersections() {
-            let vecs: Vec<Vec<u32>> = vec![vec![1, 4, 5], vec![10, 20, 30]];
-
-            let result = intersection(&vecs);
-
-            assert!(result.is_empty());
-        }
-
-        #[test]
-        fn reports_intersections_for_two_vectors() {
-            let vecs: Vec<Vec<u32>> = vec![vec![1, 2, 3], vec![4, 3, 5]];
-
-            let result = intersection(&vecs);
-
-            assert_eq!(result.len(), 1);
-            assert!(result.contains(&&3));
-        }
-
-        #[test]
-        fn reports_intersections_for_more_than_two_vectors() {
-            let vecs: Vec<Vec<u32>> = vec![
-                vec![1, 2, 3],
-                vec![4, 3, 5],
-                vec![5, 10, 20],
-                vec![40, 10, 20, 30],
-            ];
-
-            let result = intersection(&vecs);
-
-            assert_eq!(result.len(), 4);
-            assert!(result.contains(&&3));
-            assert!(result.contains(&&5));
-            assert!(result.contains(&&10));
-            assert!(result.contains(&&20));
-        }
-    }
 }
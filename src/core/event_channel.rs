@@ -0,0 +1,327 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, MutexGuard,
+    },
+};
+
+use crate::{ParallelSafe, Shared, World};
+
+/// The number of frames an event lives for when its channel hasn't been given a lifetime with
+/// `Game::add_event_channel`. Two frames guarantees every `System` that runs each frame sees every event exactly once,
+/// regardless of whether it runs before or after the `System` that sent it.
+pub const DEFAULT_EVENT_LIFETIME_FRAMES: u64 = 2;
+
+struct SentEvent<T> {
+    id: u64,
+    frame_sent: u64,
+    event: Shared<T>,
+}
+
+/// All events of a single type that are still alive.
+struct EventChannel<T> {
+    events: Vec<SentEvent<T>>,
+    last_event_id: u64,
+    lifetime_frames: u64,
+}
+impl<T: ParallelSafe + 'static> EventChannel<T> {
+    fn new(lifetime_frames: u64) -> Self {
+        Self {
+            events: vec![],
+            last_event_id: 0,
+            lifetime_frames,
+        }
+    }
+}
+
+/// Lets the event store manage channels without knowing the type of event each carries.
+trait AnyEventChannel: ParallelSafe {
+    fn drop_expired_events(&mut self, current_frame: u64);
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+impl<T: ParallelSafe + 'static> AnyEventChannel for EventChannel<T> {
+    fn drop_expired_events(&mut self, current_frame: u64) {
+        let lifetime_frames = self.lifetime_frames;
+
+        self.events
+            .retain(|sent_event| sent_event.frame_sent + lifetime_frames > current_frame);
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Holds a channel for every type of event that's been sent, keyed by the event's type. The channels are behind a lock
+/// so systems running at the same time can all send events.
+pub(crate) struct EventChannels {
+    channels: Mutex<HashMap<TypeId, Box<dyn AnyEventChannel>>>,
+    current_frame: u64,
+}
+impl EventChannels {
+    pub fn new() -> Self {
+        Self {
+            channels: Mutex::new(HashMap::new()),
+            current_frame: 0,
+        }
+    }
+
+    /// Sets how many frames events of type `T` live for, creating the channel if it doesn't exist yet.
+    pub fn add_channel<T: ParallelSafe + 'static>(&mut self, lifetime_frames: u64) {
+        let mut channels = self.channels();
+
+        if let Some(channel) = channels.get_mut(&TypeId::of::<T>()) {
+            Self::downcast_mut::<T>(channel.as_mut()).lifetime_frames = lifetime_frames;
+        } else {
+            channels.insert(
+                TypeId::of::<T>(),
+                Box::new(EventChannel::<T>::new(lifetime_frames)),
+            );
+        }
+    }
+
+    pub fn send<T: ParallelSafe + 'static>(&self, event: T) {
+        let mut channels = self.channels();
+
+        let channel = Self::downcast_mut::<T>(
+            channels
+                .entry(TypeId::of::<T>())
+                .or_insert_with(|| Box::new(EventChannel::<T>::new(DEFAULT_EVENT_LIFETIME_FRAMES)))
+                .as_mut(),
+        );
+
+        channel.last_event_id += 1;
+
+        channel.events.push(SentEvent {
+            id: channel.last_event_id,
+            frame_sent: self.current_frame,
+            event: Shared::new(event),
+        });
+    }
+
+    /// Gets every living event of type `T` that was sent after the event with the provided ID, along with the ID of the
+    /// last event of type `T` that was sent.
+    pub fn read_after<T: ParallelSafe + 'static>(&self, event_id: u64) -> (Vec<Shared<T>>, u64) {
+        let channels = self.channels();
+
+        if let Some(channel) = channels.get(&TypeId::of::<T>()) {
+            let channel = channel.as_any().downcast_ref::<EventChannel<T>>().unwrap();
+
+            let events = channel
+                .events
+                .iter()
+                .filter(|sent_event| sent_event.id > event_id)
+                .map(|sent_event| Shared::clone(&sent_event.event))
+                .collect();
+
+            return (events, channel.last_event_id);
+        }
+
+        (vec![], event_id)
+    }
+
+    /// Moves on to the next frame, dropping any events that have outlived their channel's lifetime.
+    pub fn start_frame(&mut self) {
+        self.current_frame += 1;
+
+        for channel in self.channels().values_mut() {
+            channel.drop_expired_events(self.current_frame);
+        }
+    }
+
+    fn channels(&self) -> MutexGuard<'_, HashMap<TypeId, Box<dyn AnyEventChannel>>> {
+        self.channels
+            .lock()
+            .expect("No system panicked while sending or reading events.")
+    }
+
+    fn downcast_mut<T: ParallelSafe + 'static>(
+        channel: &mut dyn AnyEventChannel,
+    ) -> &mut EventChannel<T> {
+        channel
+            .as_any_mut()
+            .downcast_mut::<EventChannel<T>>()
+            .unwrap()
+    }
+}
+
+/// Reads events of type `T` sent with `World::send_event`. An `EventReader` remembers which events it's already read,
+/// so each call to `read` only gives back events that were sent since the last call.
+///
+/// Each `System` that wants to read events should have its own reader, which it can move into its operator:
+/// ```
+/// use thomas::{EventReader, System};
+///
+/// struct Damage {
+///     amount: u32,
+/// }
+///
+/// let damage_reader = EventReader::<Damage>::new();
+///
+/// System::new(vec![], move |_, _, world| {
+///     for damage in damage_reader.read(world) {
+///         // Apply damage.amount...
+///     }
+/// });
+/// ```
+/// Events only live for a limited number of frames (see `Game::add_event_channel`), so a reader that isn't read often
+/// enough may miss some events.
+pub struct EventReader<T> {
+    last_read_event_id: AtomicU64,
+    event_type: PhantomData<T>,
+}
+impl<T: ParallelSafe + 'static> EventReader<T> {
+    pub fn new() -> Self {
+        Self {
+            last_read_event_id: AtomicU64::new(0),
+            event_type: PhantomData,
+        }
+    }
+
+    /// Gets every event of type `T` that's been sent since the last time this reader was read, oldest first.
+    pub fn read(&self, world: &World) -> Vec<Shared<T>> {
+        let (events, last_event_id) = world
+            .event_channels()
+            .read_after::<T>(self.last_read_event_id.load(Ordering::Relaxed));
+
+        self.last_read_event_id
+            .store(last_event_id, Ordering::Relaxed);
+
+        events
+    }
+}
+impl<T: ParallelSafe + 'static> Default for EventReader<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestEvent {
+        value: u8,
+    }
+
+    struct OtherTestEvent {}
+
+    mod test_send {
+        use super::*;
+
+        #[test]
+        fn keeps_events_of_different_types_separate() {
+            let channels = EventChannels::new();
+
+            channels.send(TestEvent { value: 1 });
+            channels.send(OtherTestEvent {});
+            channels.send(OtherTestEvent {});
+
+            assert_eq!(channels.read_after::<TestEvent>(0).0.len(), 1);
+            assert_eq!(channels.read_after::<OtherTestEvent>(0).0.len(), 2);
+        }
+    }
+
+    mod test_read_after {
+        use super::*;
+
+        #[test]
+        fn only_gives_events_sent_after_the_provided_event() {
+            let channels = EventChannels::new();
+
+            channels.send(TestEvent { value: 1 });
+            channels.send(TestEvent { value: 2 });
+            channels.send(TestEvent { value: 3 });
+
+            let (events, last_event_id) = channels.read_after::<TestEvent>(1);
+
+            assert_eq!(
+                events.iter().map(|event| event.value).collect::<Vec<u8>>(),
+                vec![2, 3]
+            );
+            assert_eq!(last_event_id, 3);
+        }
+
+        #[test]
+        fn is_empty_when_nothing_has_been_sent() {
+            let channels = EventChannels::new();
+
+            assert!(channels.read_after::<TestEvent>(0).0.is_empty());
+        }
+    }
+
+    mod test_start_frame {
+        use super::*;
+
+        #[test]
+        fn drops_events_once_they_have_lived_for_their_lifetime() {
+            let mut channels = EventChannels::new();
+
+            channels.send(TestEvent { value: 1 });
+
+            channels.start_frame();
+            assert_eq!(channels.read_after::<TestEvent>(0).0.len(), 1);
+
+            channels.start_frame();
+            assert!(channels.read_after::<TestEvent>(0).0.is_empty());
+        }
+
+        #[test]
+        fn respects_the_lifetime_of_the_channel() {
+            let mut channels = EventChannels::new();
+
+            channels.add_channel::<TestEvent>(4);
+            channels.send(TestEvent { value: 1 });
+
+            for _ in 0..3 {
+                channels.start_frame();
+            }
+            assert_eq!(channels.read_after::<TestEvent>(0).0.len(), 1);
+
+            channels.start_frame();
+            assert!(channels.read_after::<TestEvent>(0).0.is_empty());
+        }
+    }
+
+    mod test_event_reader {
+        use super::*;
+
+        #[test]
+        fn does_not_give_back_events_it_has_already_read() {
+            let world = World::new();
+            let reader = EventReader::<TestEvent>::new();
+
+            world.send_event(TestEvent { value: 1 });
+
+            assert_eq!(reader.read(&world).len(), 1);
+            assert!(reader.read(&world).is_empty());
+
+            world.send_event(TestEvent { value: 2 });
+
+            let events = reader.read(&world);
+
+            assert_eq!(events.len(), 1);
+            assert_eq!(events[0].value, 2);
+        }
+
+        #[test]
+        fn readers_are_independent_of_each_other() {
+            let world = World::new();
+            let reader1 = EventReader::<TestEvent>::new();
+            let reader2 = EventReader::<TestEvent>::new();
+
+            world.send_event(TestEvent { value: 1 });
+
+            assert_eq!(reader1.read(&world).len(), 1);
+            assert_eq!(reader2.read(&world).len(), 1);
+        }
+    }
+}
@@ -1,15 +1,26 @@
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    time::{Duration, Instant},
+};
 
 use device_query::Keycode;
 
 use crate::{
-    Component, Entity, EntityManager, Input, Query, ServicesSystemsGenerator, System,
-    SystemsGenerator, TerminalCollisionsSystemsGenerator, TerminalRendererOptions,
+    enter_state_event_name, exit_state_event_name, BoxedGameState, Clock, Component, EngineStats,
+    Entity, FixedTime, FramePacer, GameState, HeadlessFrame, HeadlessRendererSystemsGenerator,
+    HierarchySystemsGenerator, Input, InputRecorder, InputRecording, InputReplay, LoadedScenes,
+    ManualClock, ParallelSafe, Query, QueryResultList, RealClock, RecordedFrame, ReplayInputSource,
+    Resource, Scene, ServicesSystemsGenerator, Shared, SharedCell, State, StateScoped, SteppedGame,
+    System, SystemsGenerator, TerminalCollisionsSystemsGenerator, TerminalRendererOptions,
     TerminalRendererState, TerminalRendererSystemsGenerator, TerminalUiRendererSystemsGenerator,
-    Timer,
+    Time, World,
 };
 
-pub type GameCommandsArg = Rc<RefCell<GameCommandQueue>>;
+pub type GameCommandsArg = Shared<SharedCell<GameCommandQueue>>;
+
+/// Carries out a `GameCommand::ChangeState` for a particular type of state.
+type StateChangeFn = fn(&mut Game, BoxedGameState, GameCommandsArg);
 
 /// The init event. Runs once before the main game loop starts.
 pub const EVENT_INIT: &str = "init";
@@ -17,6 +28,9 @@ pub const EVENT_INIT: &str = "init";
 pub const EVENT_AFTER_INIT: &str = "after-init";
 /// The before-update event. Runs once per frame before the update event.
 pub const EVENT_BEFORE_UPDATE: &str = "before-update";
+/// The fixed-update event. Runs zero or more times per frame, between the before-update and update events, so that
+/// it runs at a steady rate on average. See `FixedTime`.
+pub const EVENT_FIXED_UPDATE: &str = "fixed-update";
 /// The update event. Runs once per frame.
 pub const EVENT_UPDATE: &str = "update";
 /// The after-update event. Runs once per frame after the update event.
@@ -27,6 +41,10 @@ pub const EVENT_CLEANUP: &str = "cleanup";
 #[derive(PartialEq, Eq)]
 pub enum Renderer {
     Terminal(TerminalRendererOptions),
+    /// Runs the game without a terminal. Everything runs as it would with the terminal renderer, but nothing is
+    /// drawn to the screen and no input is read from the keyboard. Instead, each frame is drawn into the
+    /// `HeadlessFrame` resource. Useful for tests, simulations, and servers, where there may not be a terminal at all.
+    Headless(TerminalRendererOptions),
 }
 
 pub struct GameOptions {
@@ -49,7 +67,7 @@ pub struct GameOptions {
 ///     press_escape_to_quit: false,
 ///     max_frame_rate: 60,
 /// })
-/// .add_init_system(System::new(vec![], |_, commands| {
+/// .add_init_system(System::new(vec![], |_, commands, _| {
 ///     commands.borrow_mut().issue(GameCommand::AddEntity(vec![
 ///         Box::new(Text {
 ///             anchor: UiAnchor::TopLeft,
@@ -68,23 +86,76 @@ pub struct GameOptions {
 /// // }));
 /// ```
 pub struct Game {
-    entity_manager: EntityManager,
-    events_to_systems: HashMap<&'static str, Vec<System>>,
+    world: World,
+    events_to_systems: HashMap<String, Vec<System>>,
+    state_changers: HashMap<TypeId, StateChangeFn>,
+    initial_state_events: Vec<String>,
+    scenes: HashMap<String, Scene>,
     is_playing: bool,
+    clock: Shared<dyn Clock>,
+    input_recorder: Option<InputRecorder>,
+    input_replay: Option<InputReplay>,
     options: GameOptions,
-    frame_timer: Timer,
 }
 impl Game {
     pub fn new(options: GameOptions) -> Self {
         Self {
-            entity_manager: EntityManager::new(),
+            world: World::new(),
             events_to_systems: HashMap::new(),
+            state_changers: HashMap::new(),
+            initial_state_events: vec![],
+            scenes: HashMap::new(),
             is_playing: false,
+            clock: Shared::new(RealClock::new()),
+            input_recorder: None,
+            input_replay: None,
             options,
-            frame_timer: Timer::new(),
         }
     }
 
+    /// Sets the clock the game reads time from. Games run on a `RealClock` by default. Give the game a `ManualClock`
+    /// to have time only pass as fast as the frame pacer says it should, which makes the game play out the same way
+    /// every time it runs.
+    ///
+    /// # Panics
+    /// If the game is replaying input. Replays run on their own clock, which keeps time with the recording.
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        if self.input_replay.is_some() {
+            panic!("A game that's replaying input can't be given another clock.");
+        }
+
+        self.clock = Shared::new(clock);
+
+        self
+    }
+
+    /// Records the keys pressed on every frame, along with how long each frame took, as the game is played. Load the
+    /// recording with `InputRecording::load` and give it to `replay_input` to play the game back. If writing to the
+    /// recording fails, the game carries on without recording.
+    pub fn record_input(mut self, recorder: InputRecorder) -> Self {
+        self.input_recorder = Some(recorder);
+
+        self
+    }
+
+    /// Plays the game with the input from the recording rather than from the keyboard. Every frame takes as long as it
+    /// did when it was recorded, and the game runs on a `ManualClock` that keeps time with the recording, so the game
+    /// plays out exactly as it did when it was recorded. The game ends when the recording runs out. Any clock given to
+    /// `with_clock` beforehand is replaced, and giving the game a clock afterwards panics.
+    pub fn replay_input(mut self, recording: &InputRecording) -> Self {
+        let clock = ManualClock::new();
+
+        self = self.with_clock(clock.clone());
+
+        self.world
+            .insert_resource(Box::new(Input::with_source(ReplayInputSource::new(
+                recording,
+            ))));
+        self.input_replay = Some(InputReplay::new(recording, clock));
+
+        self
+    }
+
     /// Adds a system to the init event. The init event runs exactly **one** time during the life of the game. It runs
     /// before the main game loop starts. The init event is a good place to put any systems that will be used to
     /// initialize your game world.
@@ -98,6 +169,13 @@ impl Game {
         self.add_system(EVENT_UPDATE, system)
     }
 
+    /// Adds a system to the fixed-update event. The fixed-update event runs at a steady rate set by the `FixedTime`
+    /// resource, regardless of frame rate, which makes it the place for movement, physics, and anything else that
+    /// should play out the same on every machine.
+    pub fn add_fixed_update_system(self, system: System) -> Self {
+        self.add_system(EVENT_FIXED_UPDATE, system)
+    }
+
     /// Adds a system to the cleanup event. The cleanup event runs exactly **one** time during the life of the game. It
     /// runs after the main game loop has ended. It's a good place to do anything you want to do when the game
     /// _successfully and properly_ exits. This could include cleaning up any system side effects, or perhaps saving
@@ -109,14 +187,128 @@ impl Game {
     /// Adds a system to the specified event. While it's likely you'll mostly use the init, update, and cleanup events,
     /// this method can be useful if you have a reason to use the other events Thomas provides. All event names are
     /// constants and start with `EVENT_`.
-    pub fn add_system(mut self, event_name: &'static str, system: System) -> Self {
-        if !self.events_to_systems.contains_key(event_name) {
-            self.events_to_systems.insert(event_name, vec![]);
-        }
+    pub fn add_system(self, event_name: &'static str, system: System) -> Self {
+        self.add_system_to_event(event_name.to_string(), system)
+    }
 
-        if let Some(system_list) = self.events_to_systems.get_mut(event_name) {
-            system_list.push(system);
-        }
+    /// Adds a type of state to the game, starting in `initial_state`. The game's current state is available as the
+    /// `State<S>` resource, and can be changed with `GameCommand::ChangeState`. The initial state is entered right after
+    /// the after-init event, so its on-enter systems run before the first frame.
+    ///
+    /// A game can have any number of state types, each of which changes independently of the others.
+    pub fn add_state<S: GameState>(mut self, initial_state: S) -> Self {
+        self.initial_state_events
+            .push(enter_state_event_name(&initial_state));
+        self.state_changers
+            .insert(TypeId::of::<S>(), Self::change_state::<S>);
+
+        self.insert_resource(State {
+            current: initial_state,
+        })
+    }
+
+    /// Adds a system that runs once whenever the game enters the state.
+    pub fn add_on_enter_system<S: GameState>(self, state: S, system: System) -> Self {
+        self.add_system_to_event(enter_state_event_name(&state), system)
+    }
+
+    /// Adds a system that runs once whenever the game exits the state. Exit systems run before any entities scoped to
+    /// the state with `StateScoped` are destroyed.
+    pub fn add_on_exit_system<S: GameState>(self, state: S, system: System) -> Self {
+        self.add_system_to_event(exit_state_event_name(&state), system)
+    }
+
+    /// Adds a system to the specified event that only runs while the game is in the state. This saves every system
+    /// from having to check the state itself.
+    pub fn add_state_system<S: GameState>(
+        self,
+        event_name: &'static str,
+        state: S,
+        system: System,
+    ) -> Self {
+        self.add_system(
+            event_name,
+            system.with_run_condition(move |world| {
+                world
+                    .try_resource::<State<S>>()
+                    .is_some_and(|current_state| current_state.is(&state))
+            }),
+        )
+    }
+
+    /// Makes the scene available to load with `GameCommand::LoadScene`. A scene with the same name as one that was
+    /// already added replaces it.
+    ///
+    /// # Panics
+    /// When the game starts, if the scene was read from a file and any of its entities has a component that isn't
+    /// registered or doesn't match its fields, or uses a prefab that doesn't exist.
+    pub fn add_scene(mut self, scene: Scene) -> Self {
+        self.scenes.insert(scene.name().to_string(), scene);
+
+        self
+    }
+
+    /// Adds a resource to the world, replacing any existing resource of the same type. Resources added here are
+    /// available to every `System`, including those in the init event.
+    pub fn insert_resource(mut self, resource: impl Resource + 'static) -> Self {
+        self.world.insert_resource(Box::new(resource));
+
+        self
+    }
+
+    /// Registers a serializable component so it can be included in `WorldSnapshot`s and restored from them. The
+    /// component is identified in snapshots by its type name without any module paths, like `Health` or
+    /// `Health<u32>`. Two components with the same type name in different modules need their own names, so register
+    /// at least one of them with `register_component_with_name`.
+    ///
+    /// # Panics
+    /// If a different component is already registered with the same name.
+    #[cfg(feature = "serde")]
+    pub fn register_component<T>(self) -> Self
+    where
+        T: Component + serde::de::DeserializeOwned + 'static,
+    {
+        self.register_component_with_name::<T>(&crate::short_type_name(T::name()))
+    }
+
+    /// Like `register_component`, but identifies the component in snapshots by the provided name. Changing a
+    /// component's name will stop snapshots saved under its old name from restoring it.
+    ///
+    /// # Panics
+    /// If a different component is already registered with the same name.
+    #[cfg(feature = "serde")]
+    pub fn register_component_with_name<T>(mut self, name: &str) -> Self
+    where
+        T: Component + serde::de::DeserializeOwned + 'static,
+    {
+        self.world.component_registry_mut().register::<T>(name);
+
+        self
+    }
+
+    /// Makes the prefabs available to spawn with `GameCommand::SpawnPrefab`. Prefabs with the same name as ones that
+    /// were already added replace them.
+    ///
+    /// Prefabs are checked when the game starts, so make sure any of your own components they use are registered with
+    /// `register_component` by then.
+    ///
+    /// # Panics
+    /// When the game starts, if any prefab has a component that isn't registered, or whose fields don't match the
+    /// component.
+    #[cfg(feature = "serde")]
+    pub fn add_prefabs(mut self, prefabs: crate::Prefabs) -> Self {
+        self.world.add_prefabs(prefabs);
+
+        self
+    }
+
+    /// Sets how many frames events of type `T` live for after they're sent. By default, events live for
+    /// `DEFAULT_EVENT_LIFETIME_FRAMES`, which is enough for every `System` that runs every frame to see each event once.
+    /// A longer lifetime is useful when the `System`s reading the events don't run every frame.
+    pub fn add_event_channel<T: ParallelSafe + 'static>(mut self, lifetime_frames: u64) -> Self {
+        self.world
+            .event_channels_mut()
+            .add_channel::<T>(lifetime_frames);
 
         self
     }
@@ -135,42 +327,151 @@ impl Game {
 
     /// Starts the game. This is the last thing you should be calling on your game instance, as it starts the main game
     /// loop. The thread will spin in this method until the `GameCommand::Quit` command is issued.
-    pub fn start(mut self, renderer: Renderer) {
-        let commands = Rc::new(RefCell::new(GameCommandQueue::new()));
+    pub fn start(self, renderer: Renderer) {
+        let commands = Shared::new(SharedCell::new(GameCommandQueue::new()));
 
-        self = self.setup_renderer(renderer);
-        self = self.setup_builtin_systems();
+        let mut game = self.setup(renderer);
 
-        self.sort_systems_by_priority();
+        game.run_init(Shared::clone(&commands));
 
-        self.is_playing = true;
+        while game.is_playing {
+            let frame_duration = match game.start_frame() {
+                Some(frame_duration) => frame_duration,
+                None => break,
+            };
+
+            game.run_frame(frame_duration, Shared::clone(&commands));
+
+            game.end_frame(frame_duration);
+        }
+
+        game.run_cleanup(commands);
+    }
+
+    /// Starts the game without running the main game loop. The init events run right away, and then the game waits for
+    /// you to step it forward a frame at a time. This is meant for tests: you can step the game as many frames as you
+    /// need, then look at the world to check your systems did what you expected.
+    ///
+    /// Stepped games always run on a `ManualClock`, which only moves forward as the game is stepped, so any clock given
+    /// to `with_clock` is replaced. Stepped games are usually paired with `Renderer::Headless` so they don't need a
+    /// terminal.
+    ///
+    /// A stepped game that's replaying input gets the recorded keys one frame per step, but each frame takes as long as
+    /// it's stepped for rather than as long as it did when it was recorded.
+    pub fn start_stepped(mut self, renderer: Renderer) -> SteppedGame {
+        let commands = Shared::new(SharedCell::new(GameCommandQueue::new()));
+        let clock = match &self.input_replay {
+            Some(input_replay) => input_replay.clock().clone(),
+            None => ManualClock::new(),
+        };
+
+        self.clock = Shared::new(clock.clone());
+
+        let mut game = self.setup(renderer);
+
+        game.run_init(Shared::clone(&commands));
+
+        SteppedGame::new(game, commands, clock)
+    }
 
-        self.trigger_event(EVENT_INIT, Rc::clone(&commands));
-        self.trigger_event(EVENT_AFTER_INIT, Rc::clone(&commands));
+    pub(crate) fn world(&self) -> &World {
+        &self.world
+    }
 
-        while self.is_playing {
-            self.frame_timer.restart();
+    pub(crate) fn is_playing(&self) -> bool {
+        self.is_playing
+    }
 
-            self.trigger_event(EVENT_BEFORE_UPDATE, Rc::clone(&commands));
+    /// Runs a single frame of the main game loop, treating `frame_duration` as the time that passed since the last
+    /// frame started.
+    pub(crate) fn run_frame(&mut self, frame_duration: Duration, commands: GameCommandsArg) {
+        self.world.start_frame();
+        self.world.resource_mut::<Time>().advance(frame_duration);
 
-            self.trigger_event(EVENT_UPDATE, Rc::clone(&commands));
+        self.trigger_event(EVENT_BEFORE_UPDATE, Shared::clone(&commands));
 
-            self.trigger_event(EVENT_AFTER_UPDATE, Rc::clone(&commands));
+        let scaled_frame_duration = self.world.resource::<Time>().delta();
+        let fixed_steps = self
+            .world
+            .resource_mut::<FixedTime>()
+            .advance(scaled_frame_duration);
 
-            self.wait_for_frame();
+        for _ in 0..fixed_steps {
+            self.trigger_event(EVENT_FIXED_UPDATE, Shared::clone(&commands));
         }
 
-        self.trigger_event(EVENT_CLEANUP, Rc::clone(&commands));
+        self.trigger_event(EVENT_UPDATE, Shared::clone(&commands));
+
+        self.trigger_event(EVENT_AFTER_UPDATE, commands);
     }
 
-    fn wait_for_frame(&self) {
-        let minimum_frame_time = if self.options.max_frame_rate > 0 {
-            1000 / self.options.max_frame_rate
-        } else {
-            0
-        };
+    /// Works out how long the last frame took. Gives `None` when the game is replaying input and the recording has
+    /// run out.
+    fn start_frame(&mut self) -> Option<Duration> {
+        match &mut self.input_replay {
+            Some(input_replay) => input_replay.start_frame(),
+            None => Some(self.world.resource_mut::<FramePacer>().start_frame()),
+        }
+    }
+
+    fn end_frame(&mut self, frame_duration: Duration) {
+        self.record_frame(frame_duration);
+
+        match &self.input_replay {
+            Some(input_replay) => input_replay.wait_for_frame_end(),
+            None => self.world.resource_mut::<FramePacer>().wait_for_frame_end(),
+        }
+    }
+
+    /// Writes the frame that just ran to the input recording, if the game is being recorded.
+    pub(crate) fn record_frame(&mut self, frame_duration: Duration) {
+        if let Some(input_recorder) = &mut self.input_recorder {
+            let frame = RecordedFrame {
+                frame_duration,
+                pressed_keys: self.world.resource::<Input>().pressed_keys(),
+            };
+
+            if input_recorder.record_frame(&frame).is_err() {
+                self.input_recorder = None;
+            }
+        }
+    }
+
+    pub(crate) fn run_cleanup(&mut self, commands: GameCommandsArg) {
+        self.trigger_event(EVENT_CLEANUP, commands);
+    }
+
+    fn setup(mut self, renderer: Renderer) -> Self {
+        #[cfg(feature = "serde")]
+        if let Err(error) = self.world.validate_prefabs() {
+            panic!("{error}");
+        }
+
+        #[cfg(feature = "serde")]
+        for scene in self.scenes.values() {
+            if let Err(error) = scene.validate(&self.world) {
+                panic!("{error}");
+            }
+        }
+
+        self = self.setup_renderer(renderer);
+        self = self.setup_builtin_resources();
+        self = self.setup_builtin_systems();
+
+        self.sort_systems_by_priority();
+
+        self.is_playing = true;
+
+        self
+    }
+
+    fn run_init(&mut self, commands: GameCommandsArg) {
+        self.trigger_event(EVENT_INIT, Shared::clone(&commands));
+        self.trigger_event(EVENT_AFTER_INIT, Shared::clone(&commands));
 
-        while self.frame_timer.elapsed_millis() < minimum_frame_time as u128 {}
+        for event_name in std::mem::take(&mut self.initial_state_events) {
+            self.trigger_event(&event_name, Shared::clone(&commands));
+        }
     }
 
     fn sort_systems_by_priority(&mut self) {
@@ -179,68 +480,157 @@ impl Game {
         }
     }
 
-    fn trigger_event(&mut self, event_name: &'static str, commands: GameCommandsArg) {
+    fn add_system_to_event(mut self, event_name: String, system: System) -> Self {
+        self.events_to_systems
+            .entry(event_name)
+            .or_default()
+            .push(system);
+
+        self
+    }
+
+    fn trigger_event(&mut self, event_name: &str, commands: GameCommandsArg) {
         if let Some(system_list) = self.events_to_systems.get(event_name) {
-            for system in system_list {
-                let queries_results = system
-                    .queries()
-                    .iter()
-                    .map(|query| self.entity_manager.query(query))
-                    .collect();
-
-                system.operator()(queries_results, Rc::clone(&commands));
+            let mut batch: Vec<(usize, &System)> = vec![];
+
+            for (index, system) in system_list.iter().enumerate() {
+                let fits_in_batch = batch.iter().all(|(_, batched_system)| {
+                    **batched_system.priority() == **system.priority()
+                        && batched_system.access().is_compatible_with(system.access())
+                });
+
+                if !fits_in_batch {
+                    run_system_batch(&mut self.world, event_name, &batch, &commands);
+
+                    batch.clear();
+                }
+
+                batch.push((index, system));
             }
 
+            run_system_batch(&mut self.world, event_name, &batch, &commands);
+
+            let command_processing_start = Instant::now();
+
             self.process_command_queue(commands);
+
+            if let Some(mut engine_stats) = self.world.try_resource_mut::<EngineStats>() {
+                engine_stats
+                    .record_command_processing(event_name, command_processing_start.elapsed());
+            }
         }
     }
 
+    /// Moves the game out of its current state of type `S` and into `new_state`. Nothing happens if the game is already
+    /// in `new_state`.
+    fn change_state<S: GameState>(&mut self, new_state: BoxedGameState, commands: GameCommandsArg) {
+        let new_state = *new_state
+            .downcast::<S>()
+            .expect("State change is for the state type it's registered to.");
+        let old_state = self.world.resource::<State<S>>().current.clone();
+
+        if old_state == new_state {
+            return;
+        }
+
+        self.trigger_event(&exit_state_event_name(&old_state), Shared::clone(&commands));
+
+        let scoped_state = old_state.clone();
+        let scoped_entities: Vec<Entity> = self
+            .world
+            .entity_manager()
+            .query(
+                &Query::new()
+                    .has_where::<StateScoped<S>>(move |scoped| scoped.state == scoped_state),
+            )
+            .iter()
+            .map(|result| *result.entity())
+            .collect();
+
+        for entity in scoped_entities {
+            self.world.destroy_entity_and_descendants(&entity);
+        }
+
+        self.world.resource_mut::<State<S>>().current = new_state.clone();
+
+        self.trigger_event(&enter_state_event_name(&new_state), commands);
+    }
+
     fn setup_renderer(mut self, renderer: Renderer) -> Self {
         match renderer {
             Renderer::Terminal(options) => {
-                self.entity_manager
-                    .add_entity(vec![Box::new(TerminalRendererState::new(options))]);
+                self.world
+                    .insert_resource(Box::new(TerminalRendererState::new(options)));
 
                 return self.add_systems_from_generator(TerminalRendererSystemsGenerator::new());
             }
+            Renderer::Headless(options) => {
+                self.world.insert_resource(Box::new(HeadlessFrame::new()));
+
+                if !self.world.has_resource::<Input>() {
+                    self.world
+                        .insert_resource(Box::new(Input::new_disconnected()));
+                }
+
+                self.add_systems_from_generator(HeadlessRendererSystemsGenerator::new(options))
+            }
         }
     }
 
-    fn setup_builtin_systems(mut self) -> Self {
-        if self.options.press_escape_to_quit {
-            self = self.add_update_system(System::new(
-                vec![Query::new().has::<Input>()],
-                |results, commands| {
-                    if let [input_results, ..] = &results[..] {
-                        let input = input_results.get_only::<Input>();
-
-                        if input.is_key_down(&Keycode::Escape) {
-                            commands.borrow_mut().issue(GameCommand::Quit);
-                        }
-                    }
-                },
-            ));
+    fn setup_builtin_resources(mut self) -> Self {
+        if !self.world.has_resource::<FixedTime>() {
+            self = self.insert_resource(FixedTime::default());
         }
 
-        self.add_update_system(System::new(
-            vec![Query::new().has::<Input>()],
-            |results, commands| {
-                if let [input_results, ..] = &results[..] {
-                    let input = input_results.get_only::<Input>();
+        let max_frame_rate = self.options.max_frame_rate;
+
+        if !self.world.has_resource::<Input>() {
+            self = self.insert_resource(Input::new());
+        }
+
+        self.world.insert_resource(Box::new(LoadedScenes::new()));
+
+        let time = Time::with_clock(Shared::clone(&self.clock));
+        let frame_pacer = FramePacer::new(max_frame_rate, Shared::clone(&self.clock));
 
-                    if input.is_chord_pressed_exclusively(&[&Keycode::LControl, &Keycode::C]) {
+        self.insert_resource(time).insert_resource(frame_pacer)
+    }
+
+    fn setup_builtin_systems(mut self) -> Self {
+        if self.options.press_escape_to_quit {
+            self = self.add_update_system(
+                System::new(vec![], |_, commands, world| {
+                    if world.resource::<Input>().is_key_down(&Keycode::Escape) {
                         commands.borrow_mut().issue(GameCommand::Quit);
                     }
+                })
+                .with_name("thomas_escape_to_quit")
+                .reads_resource::<Input>(),
+            );
+        }
+
+        self.add_update_system(
+            System::new(vec![], |_, commands, world| {
+                if world
+                    .resource::<Input>()
+                    .is_chord_pressed_exclusively(&[&Keycode::LControl, &Keycode::C])
+                {
+                    commands.borrow_mut().issue(GameCommand::Quit);
                 }
-            },
-        ))
+            })
+            .with_name("thomas_ctrl_c_to_quit")
+            .reads_resource::<Input>(),
+        )
         .add_systems_from_generator(ServicesSystemsGenerator::new())
+        .add_systems_from_generator(HierarchySystemsGenerator::new())
         .add_systems_from_generator(TerminalCollisionsSystemsGenerator::new())
         .add_systems_from_generator(TerminalUiRendererSystemsGenerator::new())
     }
 
-    fn process_command_queue(&mut self, commands: GameCommandsArg) {
-        let old_commands = commands.replace(GameCommandQueue::new());
+    pub(crate) fn process_command_queue(&mut self, commands: GameCommandsArg) {
+        let old_commands = std::mem::replace(&mut *commands.borrow_mut(), GameCommandQueue::new());
+
+        self.world.entity_manager_mut().increment_change_tick();
 
         for command in old_commands {
             match command {
@@ -248,35 +638,280 @@ impl Game {
                     self.is_playing = false;
                 }
                 GameCommand::AddEntity(components) => {
-                    self.entity_manager.add_entity(components);
+                    self.world.entity_manager_mut().add_entity(components);
+                }
+                GameCommand::AddEntityWithChildren(components, children_components) => {
+                    let parent = self.world.entity_manager_mut().add_entity(components);
+
+                    for child_components in children_components {
+                        self.world.add_child_entity(&parent, child_components);
+                    }
+                }
+                GameCommand::AddChildEntity(parent, components) => {
+                    self.world.add_child_entity(&parent, components);
                 }
                 GameCommand::AddComponentsToEntity(entity, components) => {
                     for component in components {
-                        self.entity_manager
+                        self.world
+                            .entity_manager_mut()
                             .add_component_to_entity(&entity, component);
                     }
                 }
                 GameCommand::DestroyEntity(entity) => {
-                    self.entity_manager.remove_entity(&entity);
+                    self.world.destroy_entity(&entity);
+                }
+                GameCommand::DestroyEntityAndDescendants(entity) => {
+                    self.world.destroy_entity_and_descendants(&entity);
+                }
+                GameCommand::SetParent(child, parent) => {
+                    self.world.set_parent(&child, &parent);
+                }
+                GameCommand::RemoveParent(child) => {
+                    self.world.remove_parent(&child);
+                }
+                GameCommand::RemoveComponentFromEntity(entity, component_id) => {
+                    self.world.remove_component(&entity, component_id);
+                }
+                GameCommand::InsertResource(resource) => {
+                    self.world.insert_resource(resource);
+                }
+                GameCommand::RemoveResource(resource_id) => {
+                    self.world.remove_resource(&resource_id);
                 }
-                GameCommand::RemoveComponentFromEntity(entity, component_name) => self
-                    .entity_manager
-                    .remove_component_from_entity(&entity, component_name),
                 GameCommand::TriggerEvent(event_name) => {
-                    self.trigger_event(event_name, Rc::clone(&commands))
+                    self.trigger_event(event_name, Shared::clone(&commands))
+                }
+                GameCommand::ChangeState(new_state) => {
+                    if let Some(change_state) =
+                        self.state_changers.get(&(*new_state).type_id()).copied()
+                    {
+                        change_state(self, new_state, Shared::clone(&commands));
+                    }
+                }
+                GameCommand::LoadScene(name) => {
+                    if let Some(scene) = self.scenes.get(&name) {
+                        if !self.world.resource::<LoadedScenes>().is_loaded(&name) {
+                            scene.spawn_into(&mut self.world);
+                        }
+                    }
+                }
+                GameCommand::UnloadScene(name) => {
+                    self.world.unload_scene(&name);
+                }
+                #[cfg(feature = "serde")]
+                GameCommand::RestoreSnapshot(snapshot) => {
+                    if let Err(error) = self.world.restore_snapshot(&snapshot) {
+                        self.world.send_event(error);
+                    }
+                }
+                #[cfg(feature = "serde")]
+                GameCommand::SpawnPrefab(name, overrides) => {
+                    self.world.spawn_prefab(&name, overrides);
                 }
             }
         }
     }
 }
 
+/// Runs a batch of systems from the same event. Systems in a batch have the same priority and their access doesn't
+/// conflict, so with the `parallel` feature they run at the same time. Otherwise, they run one after another.
+#[cfg(not(feature = "parallel"))]
+fn run_system_batch(
+    world: &mut World,
+    event_name: &str,
+    batch: &[(usize, &System)],
+    commands: &GameCommandsArg,
+) {
+    for (index, system) in batch {
+        if !system.should_run(world) {
+            continue;
+        }
+
+        let this_run_tick = world
+            .entity_manager_mut()
+            .start_system_run(system.last_run_tick());
+
+        let queries_start = Instant::now();
+
+        let queries_results = run_system_queries(world, system);
+
+        let queries_duration = queries_start.elapsed();
+        let operator_start = Instant::now();
+
+        system.operator()(queries_results, Shared::clone(commands), world);
+
+        record_system_profile(
+            world,
+            event_name,
+            *index,
+            system,
+            queries_duration,
+            operator_start.elapsed(),
+        );
+
+        system.set_last_run_tick(this_run_tick);
+    }
+}
+
+/// Runs a batch of systems from the same event. Systems in a batch have the same priority and their access doesn't
+/// conflict, so with the `parallel` feature they run at the same time. Otherwise, they run one after another.
+///
+/// Queries are run up front on the calling thread, and then the operators run on the thread pool, each with its own
+/// command queue. The queues are added to the event's queue afterwards in the order the systems were in, so commands
+/// are processed in the same order as they would be if the systems had run one after another.
+#[cfg(feature = "parallel")]
+fn run_system_batch(
+    world: &mut World,
+    event_name: &str,
+    batch: &[(usize, &System)],
+    commands: &GameCommandsArg,
+) {
+    use crate::run_in_parallel_system;
+    use rayon::prelude::*;
+
+    let mut started_systems = vec![];
+    let mut operator_runs = vec![];
+
+    for (index, system) in batch {
+        if !system.should_run(world) {
+            continue;
+        }
+
+        let this_run_tick = world
+            .entity_manager_mut()
+            .start_system_run(system.last_run_tick());
+
+        let queries_start = Instant::now();
+
+        let queries_results = run_system_queries(world, system);
+
+        started_systems.push((*index, *system, this_run_tick, queries_start.elapsed()));
+        operator_runs.push((system.operator(), system.last_run_tick(), queries_results));
+    }
+
+    let world = &*world;
+    let operator_outcomes: Vec<(GameCommandQueue, Duration)> = operator_runs
+        .into_par_iter()
+        .map(|(operator, last_run_tick, queries_results)| {
+            let system_commands = Shared::new(SharedCell::new(GameCommandQueue::new()));
+            let operator_start = Instant::now();
+
+            run_in_parallel_system(last_run_tick, || {
+                operator(queries_results, Shared::clone(&system_commands), world)
+            });
+
+            let operator_duration = operator_start.elapsed();
+            let system_commands =
+                std::mem::replace(&mut *system_commands.borrow_mut(), GameCommandQueue::new());
+
+            (system_commands, operator_duration)
+        })
+        .collect();
+
+    for ((index, system, this_run_tick, queries_duration), (system_commands, operator_duration)) in
+        started_systems.into_iter().zip(operator_outcomes)
+    {
+        commands.borrow_mut().queue.extend(system_commands);
+
+        record_system_profile(
+            world,
+            event_name,
+            index,
+            system,
+            queries_duration,
+            operator_duration,
+        );
+
+        system.set_last_run_tick(this_run_tick);
+    }
+}
+
+fn run_system_queries(world: &World, system: &System) -> Vec<QueryResultList> {
+    system
+        .queries()
+        .iter()
+        .map(|query| world.entity_manager().query(query))
+        .collect()
+}
+
+/// Records how long the `System` took in the `EngineStats`, if there are any. Systems without a name are recorded
+/// by their event and where they are in it.
+fn record_system_profile(
+    world: &World,
+    event_name: &str,
+    index: usize,
+    system: &System,
+    queries_duration: Duration,
+    operator_duration: Duration,
+) {
+    if let Some(mut engine_stats) = world.try_resource_mut::<EngineStats>() {
+        let system_name = match system.name() {
+            Some(name) => name.to_string(),
+            None => format!("{event_name} #{index}"),
+        };
+
+        engine_stats.record_system(event_name, system_name, queries_duration, operator_duration);
+    }
+}
+
+/// A request to change the game. Commands are issued to the `GameCommandQueue` and carried out when the queue is
+/// processed.
+///
+/// Commands that target an `Entity` are ignored if that `Entity` is no longer alive by the time the command is
+/// processed. This includes an `Entity` whose ID has since been reused, so a stale `Entity` can never cause changes
+/// to an unrelated `Entity`.
 pub enum GameCommand {
     Quit,
     AddEntity(Vec<Box<dyn Component>>),
+    /// Adds an entity along with children of it. The first list is the components of the parent, and each list after
+    /// it is the components of one of its children.
+    AddEntityWithChildren(Vec<Box<dyn Component>>, Vec<Vec<Box<dyn Component>>>),
+    /// Adds an entity as a child of the provided parent `Entity`.
+    AddChildEntity(Entity, Vec<Box<dyn Component>>),
     AddComponentsToEntity(Entity, Vec<Box<dyn Component>>),
-    RemoveComponentFromEntity(Entity, &'static str),
+    /// Removes the component from the entity. Use the component's `id()` to specify which component to remove.
+    /// Removing `Parent` or `Children` works like `GameCommand::RemoveParent`, keeping parents and children linked up.
+    /// A child's `GlobalTerminalTransform` can't be removed, since Thomas keeps it up to date.
+    RemoveComponentFromEntity(Entity, TypeId),
+    /// Destroys the entity. Any children it had are left in the world without a parent, in the same place they were.
     DestroyEntity(Entity),
+    /// Destroys the entity along with its children, their children, and so on.
+    DestroyEntityAndDescendants(Entity),
+    /// Makes the first `Entity` a child of the second, taking it away from any parent it already had. A child's
+    /// `TerminalTransform` is its offset from its parent. Ignored if the second `Entity` is the first or one of its
+    /// descendants.
+    SetParent(Entity, Entity),
+    /// Takes the `Entity` away from its parent. Its `TerminalTransform` is updated so it stays where it was in the
+    /// world.
+    RemoveParent(Entity),
+    /// Adds the resource to the world, replacing any existing resource of the same type.
+    InsertResource(Box<dyn Resource>),
+    /// Removes the resource from the world. Use the resource's `id()` to specify which resource to remove.
+    RemoveResource(TypeId),
     TriggerEvent(&'static str),
+    /// Changes the game's state to the provided one, like `GameCommand::ChangeState(Box::new(AppState::Playing))`.
+    /// The current state's on-exit systems run, entities scoped to it are destroyed, and then the new state's
+    /// on-enter systems run. Ignored if the game is already in the state, or the state's type was never added with
+    /// `Game::add_state`.
+    ChangeState(BoxedGameState),
+    /// Loads the scene with the provided name, adding its entities to the world. Ignored if the scene is already
+    /// loaded, or was never added with `Game::add_scene`.
+    LoadScene(String),
+    /// Unloads the scene with the provided name, destroying every entity in it along with their descendants.
+    UnloadScene(String),
+    /// Adds the entities in the snapshot to the world, along with their serializable components. Nothing already in
+    /// the world is removed, so destroy anything the snapshot should replace first. Every component in the snapshot
+    /// has to be registered with `Game::register_component`. If any component can't be read back, or an entity's
+    /// parent isn't in the snapshot, nothing is added and the `SnapshotError` is sent as an event instead. Read it with
+    /// an `EventReader<SnapshotError>`.
+    #[cfg(feature = "serde")]
+    RestoreSnapshot(crate::WorldSnapshot),
+    /// Adds an entity made from the prefab with the provided name, like
+    /// `GameCommand::SpawnPrefab(String::from("goblin"), vec![Box::new(TerminalTransform { coords })])`. Each of the
+    /// components in the list replaces the prefab's component of the same type, or is added to the entity if the prefab
+    /// doesn't have one. Ignored if there's no prefab with the name.
+    #[cfg(feature = "serde")]
+    SpawnPrefab(String, Vec<Box<dyn Component>>),
 }
 
 pub struct GameCommandQueue {
@@ -335,29 +970,29 @@ mod tests {
             .add_init_system(System::new_with_priority(
                 Priority::new(5),
                 vec![],
-                |_, _| {},
+                |_, _, _| {},
             ))
             .add_init_system(System::new_with_priority(
                 Priority::new(1),
                 vec![],
-                |_, _| {},
+                |_, _, _| {},
             ))
             .add_init_system(System::new_with_priority(
                 Priority::new(3),
                 vec![],
-                |_, _| {},
+                |_, _, _| {},
             ))
             .add_system(
                 EVENT_CUSTOM,
-                System::new_with_priority(Priority::new(50), vec![], |_, _| {}),
+                System::new_with_priority(Priority::new(50), vec![], |_, _, _| {}),
             )
             .add_system(
                 EVENT_CUSTOM,
-                System::new_with_priority(Priority::new(10), vec![], |_, _| {}),
+                System::new_with_priority(Priority::new(10), vec![], |_, _, _| {}),
             )
             .add_system(
                 EVENT_CUSTOM,
-                System::new_with_priority(Priority::new(30), vec![], |_, _| {}),
+                System::new_with_priority(Priority::new(30), vec![], |_, _, _| {}),
             );
 
             game.sort_systems_by_priority();
@@ -384,9 +1019,9 @@ mod tests {
                 press_escape_to_quit: false,
                 max_frame_rate: 5,
             })
-            .add_init_system(System::new(vec![], |_, _| {}))
-            .add_update_system(System::new(vec![], |_, _| {}))
-            .add_cleanup_system(System::new(vec![], |_, _| {}));
+            .add_init_system(System::new(vec![], |_, _, _| {}))
+            .add_update_system(System::new(vec![], |_, _, _| {}))
+            .add_cleanup_system(System::new(vec![], |_, _, _| {}));
 
             assert_eq!(game.events_to_systems.get(EVENT_INIT).unwrap().len(), 1);
             assert_eq!(game.events_to_systems.get(EVENT_UPDATE).unwrap().len(), 1);
@@ -399,7 +1034,7 @@ mod tests {
                 press_escape_to_quit: false,
                 max_frame_rate: 5,
             })
-            .add_system("my key", System::new(vec![], |_, _| {}));
+            .add_system("my key", System::new(vec![], |_, _, _| {}));
 
             assert_eq!(game.events_to_systems.get("my key").unwrap().len(), 1);
         }
@@ -416,9 +1051,9 @@ mod tests {
             impl SystemsGenerator for TestGenerator {
                 fn generate(&self) -> Vec<(&'static str, System)> {
                     vec![
-                        (EVENT_INIT, System::new(vec![], |_, _| {})),
-                        (EVENT_CLEANUP, System::new(vec![], |_, _| {})),
-                        (EVENT_CUSTOM, System::new(vec![], |_, _| {})),
+                        (EVENT_INIT, System::new(vec![], |_, _, _| {})),
+                        (EVENT_CLEANUP, System::new(vec![], |_, _, _| {})),
+                        (EVENT_CUSTOM, System::new(vec![], |_, _, _| {})),
                     ]
                 }
             }
@@ -441,6 +1076,7 @@ mod tests {
         use std::sync::atomic::{AtomicU8, Ordering};
 
         use super::*;
+        use crate::Query;
 
         const EVENT_1: &str = "1";
 
@@ -455,18 +1091,21 @@ mod tests {
             })
             .add_system(
                 EVENT_1,
-                System::new(vec![], |_, _| {
+                System::new(vec![], |_, _, _| {
                     COUNTER_1.fetch_add(2, Ordering::Relaxed);
                 }),
             )
             .add_system(
                 EVENT_1,
-                System::new(vec![], |_, _| {
+                System::new(vec![], |_, _, _| {
                     COUNTER_2.fetch_add(5, Ordering::Relaxed);
                 }),
             );
 
-            game.trigger_event(EVENT_1, Rc::new(RefCell::new(GameCommandQueue::new())));
+            game.trigger_event(
+                EVENT_1,
+                Shared::new(SharedCell::new(GameCommandQueue::new())),
+            );
 
             assert_eq!(COUNTER_1.fetch_add(0, Ordering::Relaxed), 2);
             assert_eq!(COUNTER_2.fetch_add(0, Ordering::Relaxed), 5);
@@ -480,53 +1119,809 @@ mod tests {
             })
             .add_system(
                 EVENT_1,
-                System::new(vec![], |_, commands| {
+                System::new(vec![], |_, commands, _| {
                     commands.borrow_mut().issue(GameCommand::Quit);
 
                     assert_eq!(commands.borrow().queue.len(), 1);
                 }),
             );
 
-            game.trigger_event(EVENT_1, Rc::new(RefCell::new(GameCommandQueue::new())));
+            game.trigger_event(
+                EVENT_1,
+                Shared::new(SharedCell::new(GameCommandQueue::new())),
+            );
         }
-    }
-
-    mod test_process_command_queue {
-        use super::*;
 
         #[test]
-        fn quit() {
+        fn systems_only_see_changes_made_since_they_last_ran() {
+            static CHANGED_COUNT: AtomicU8 = AtomicU8::new(0);
+
+            #[derive(Component)]
+            struct Counter {
+                count: u8,
+            }
+
             let mut game = Game::new(GameOptions {
                 press_escape_to_quit: false,
                 max_frame_rate: 5,
-            });
-            game.is_playing = true;
+            })
+            .add_system(
+                EVENT_1,
+                System::new(vec![Query::new().changed::<Counter>()], |results, _, _| {
+                    if let [counter_results, ..] = &results[..] {
+                        CHANGED_COUNT.store(counter_results.len() as u8, Ordering::Relaxed);
+                    }
+                }),
+            )
+            .add_system(
+                EVENT_1,
+                System::new(vec![Query::new().has::<Counter>()], |results, _, _| {
+                    if let [counter_results, ..] = &results[..] {
+                        let is_first_run = counter_results.get_only::<Counter>().count == 0;
 
-            assert_eq!(game.is_playing, true);
+                        if is_first_run {
+                            counter_results.get_only_mut::<Counter>().count += 1;
+                        }
+                    }
+                }),
+            );
 
-            let commands = Rc::new(RefCell::new(GameCommandQueue::new()));
-            commands.borrow_mut().issue(GameCommand::Quit);
+            game.world
+                .entity_manager_mut()
+                .add_entity(vec![Box::new(Counter { count: 0 })]);
 
-            game.process_command_queue(Rc::clone(&commands));
+            let commands = Shared::new(SharedCell::new(GameCommandQueue::new()));
 
-            assert_eq!(game.is_playing, false);
+            game.trigger_event(EVENT_1, Shared::clone(&commands));
+            assert_eq!(CHANGED_COUNT.load(Ordering::Relaxed), 1);
+
+            game.trigger_event(EVENT_1, Shared::clone(&commands));
+            assert_eq!(CHANGED_COUNT.load(Ordering::Relaxed), 1);
+
+            game.trigger_event(EVENT_1, Shared::clone(&commands));
+            assert_eq!(CHANGED_COUNT.load(Ordering::Relaxed), 0);
         }
 
         #[test]
-        fn queue_is_empty_after_processing() {
+        fn profiles_systems_when_there_are_engine_stats() {
             let mut game = Game::new(GameOptions {
                 press_escape_to_quit: false,
                 max_frame_rate: 5,
-            });
+            })
+            .add_system(
+                EVENT_1,
+                System::new(vec![], |_, _, _| {
+                    std::thread::sleep(Duration::from_millis(2));
+                })
+                .with_name("sleepy"),
+            )
+            .add_system(EVENT_1, System::new(vec![], |_, _, _| {}));
 
-            let commands = Rc::new(RefCell::new(GameCommandQueue::new()));
-            commands.borrow_mut().issue(GameCommand::Quit);
+            game.world
+                .insert_resource(Box::new(EngineStats::new(crate::Timer::new())));
 
-            assert_eq!(commands.borrow().queue.len(), 1);
+            let commands = Shared::new(SharedCell::new(GameCommandQueue::new()));
 
-            game.process_command_queue(Rc::clone(&commands));
+            game.trigger_event(EVENT_1, Shared::clone(&commands));
+            game.trigger_event(EVENT_1, Shared::clone(&commands));
 
-            assert_eq!(commands.borrow().queue.len(), 0);
+            let engine_stats = game.world.resource::<EngineStats>();
+            let sleepy = engine_stats.system(EVENT_1, "sleepy").unwrap();
+
+            assert_eq!(engine_stats.systems().count(), 2);
+            assert!(sleepy.operator().average() >= Duration::from_millis(2));
+            assert!(sleepy.operator().max() >= Duration::from_millis(2));
+            assert!(engine_stats.system(EVENT_1, "1 #1").is_some());
+            assert!(engine_stats.command_processing(EVENT_1).is_some());
+        }
+
+        #[test]
+        fn systems_that_conflict_run_one_after_another() {
+            static SEEN_COUNT: AtomicU8 = AtomicU8::new(0);
+
+            #[derive(Component)]
+            struct Counter {
+                count: u8,
+            }
+
+            let mut game = Game::new(GameOptions {
+                press_escape_to_quit: false,
+                max_frame_rate: 5,
+            })
+            .add_system(
+                EVENT_1,
+                System::new(vec![Query::new().has::<Counter>()], |results, _, _| {
+                    if let [counter_results, ..] = &results[..] {
+                        counter_results.get_only_mut::<Counter>().count += 1;
+                    }
+                }),
+            )
+            .add_system(
+                EVENT_1,
+                System::new(
+                    vec![Query::new().has::<Counter>().read_only()],
+                    |results, _, _| {
+                        if let [counter_results, ..] = &results[..] {
+                            SEEN_COUNT.store(
+                                counter_results.get_only::<Counter>().count,
+                                Ordering::Relaxed,
+                            );
+                        }
+                    },
+                ),
+            );
+
+            game.world
+                .entity_manager_mut()
+                .add_entity(vec![Box::new(Counter { count: 0 })]);
+
+            game.trigger_event(
+                EVENT_1,
+                Shared::new(SharedCell::new(GameCommandQueue::new())),
+            );
+
+            assert_eq!(SEEN_COUNT.load(Ordering::Relaxed), 1);
+        }
+
+        #[test]
+        fn commands_are_processed_in_the_order_systems_were_added() {
+            #[derive(Resource)]
+            struct LastWriter {
+                value: u8,
+            }
+
+            let mut game = Game::new(GameOptions {
+                press_escape_to_quit: false,
+                max_frame_rate: 5,
+            });
+
+            for value in 0..8 {
+                game = game.add_system(
+                    EVENT_1,
+                    System::new(vec![], move |_, commands, _| {
+                        commands
+                            .borrow_mut()
+                            .issue(GameCommand::InsertResource(Box::new(LastWriter { value })));
+                    }),
+                );
+            }
+
+            game.trigger_event(
+                EVENT_1,
+                Shared::new(SharedCell::new(GameCommandQueue::new())),
+            );
+
+            assert_eq!(game.world.resource::<LastWriter>().value, 7);
+        }
+
+        #[test]
+        fn typed_systems_get_the_matches_of_their_queries() {
+            use crate::{TypedQuery, Without};
+
+            #[derive(Component)]
+            struct Counter {
+                count: u8,
+            }
+
+            #[derive(Component)]
+            struct Frozen {}
+
+            let mut game = Game::new(GameOptions {
+                press_escape_to_quit: false,
+                max_frame_rate: 5,
+            })
+            .add_system(
+                EVENT_1,
+                System::typed(
+                    |counters: TypedQuery<&mut Counter, Without<Frozen>>, _, _| {
+                        for mut counter in &counters {
+                            counter.count += 1;
+                        }
+                    },
+                ),
+            );
+
+            let counting = game
+                .world
+                .entity_manager_mut()
+                .add_entity(vec![Box::new(Counter { count: 0 })]);
+            let frozen = game
+                .world
+                .entity_manager_mut()
+                .add_entity(vec![Box::new(Counter { count: 0 }), Box::new(Frozen {})]);
+
+            game.trigger_event(
+                EVENT_1,
+                Shared::new(SharedCell::new(GameCommandQueue::new())),
+            );
+
+            let count_of = |entity: &Entity| {
+                game.world
+                    .entity_manager()
+                    .get_component::<Counter>(entity)
+                    .unwrap()
+                    .count
+            };
+
+            assert_eq!(count_of(&counting), 1);
+            assert_eq!(count_of(&frozen), 0);
+        }
+
+        #[cfg(feature = "parallel")]
+        #[test]
+        fn systems_that_do_not_conflict_run_at_the_same_time() {
+            use std::{sync::atomic::AtomicBool, time::Instant};
+
+            static ARRIVED: AtomicU8 = AtomicU8::new(0);
+            static MET: AtomicBool = AtomicBool::new(true);
+
+            if rayon::current_num_threads() < 2 {
+                return;
+            }
+
+            // Each system waits for the other to arrive. Run one after another, the first gives up waiting.
+            let wait_for_other_system = |_: Vec<QueryResultList>, _: GameCommandsArg, _: &World| {
+                ARRIVED.fetch_add(1, Ordering::SeqCst);
+
+                let wait_start = Instant::now();
+
+                while ARRIVED.load(Ordering::SeqCst) < 2 {
+                    if wait_start.elapsed() > Duration::from_secs(5) {
+                        MET.store(false, Ordering::SeqCst);
+
+                        return;
+                    }
+                }
+            };
+
+            let mut game = Game::new(GameOptions {
+                press_escape_to_quit: false,
+                max_frame_rate: 5,
+            })
+            .add_system(EVENT_1, System::new(vec![], wait_for_other_system))
+            .add_system(EVENT_1, System::new(vec![], wait_for_other_system));
+
+            game.trigger_event(
+                EVENT_1,
+                Shared::new(SharedCell::new(GameCommandQueue::new())),
+            );
+
+            assert!(MET.load(Ordering::SeqCst));
+        }
+    }
+
+    mod test_setup_builtin_systems {
+        use super::*;
+
+        #[test]
+        fn quit_systems_never_run_alongside_systems_that_write_input() {
+            let game = Game::new(GameOptions {
+                press_escape_to_quit: true,
+                max_frame_rate: 5,
+            })
+            .setup_builtin_systems();
+
+            let input_writer = System::new(vec![], |_, _, _| {}).writes_resource::<Input>();
+            let quit_systems: Vec<&System> = game.events_to_systems[EVENT_UPDATE]
+                .iter()
+                .filter(|system| {
+                    matches!(
+                        system.name(),
+                        Some("thomas_escape_to_quit" | "thomas_ctrl_c_to_quit")
+                    )
+                })
+                .collect();
+
+            assert_eq!(quit_systems.len(), 2);
+
+            for system in quit_systems {
+                assert!(!system.access().is_compatible_with(input_writer.access()));
+            }
+        }
+    }
+
+    mod test_process_command_queue {
+        use super::*;
+
+        #[test]
+        fn quit() {
+            let mut game = Game::new(GameOptions {
+                press_escape_to_quit: false,
+                max_frame_rate: 5,
+            });
+            game.is_playing = true;
+
+            assert_eq!(game.is_playing, true);
+
+            let commands = Shared::new(SharedCell::new(GameCommandQueue::new()));
+            commands.borrow_mut().issue(GameCommand::Quit);
+
+            game.process_command_queue(Shared::clone(&commands));
+
+            assert_eq!(game.is_playing, false);
+        }
+
+        #[test]
+        fn queue_is_empty_after_processing() {
+            let mut game = Game::new(GameOptions {
+                press_escape_to_quit: false,
+                max_frame_rate: 5,
+            });
+
+            let commands = Shared::new(SharedCell::new(GameCommandQueue::new()));
+            commands.borrow_mut().issue(GameCommand::Quit);
+
+            assert_eq!(commands.borrow().queue.len(), 1);
+
+            game.process_command_queue(Shared::clone(&commands));
+
+            assert_eq!(commands.borrow().queue.len(), 0);
+        }
+
+        #[test]
+        fn insert_and_remove_resource() {
+            #[derive(Resource)]
+            struct Score {
+                value: u64,
+            }
+
+            let mut game = Game::new(GameOptions {
+                press_escape_to_quit: false,
+                max_frame_rate: 5,
+            });
+
+            let commands = Shared::new(SharedCell::new(GameCommandQueue::new()));
+            commands
+                .borrow_mut()
+                .issue(GameCommand::InsertResource(Box::new(Score { value: 3 })));
+
+            game.process_command_queue(Shared::clone(&commands));
+
+            assert_eq!(game.world.resource::<Score>().value, 3);
+
+            commands
+                .borrow_mut()
+                .issue(GameCommand::RemoveResource(Score::id()));
+
+            game.process_command_queue(Shared::clone(&commands));
+
+            assert!(!game.world.has_resource::<Score>());
+        }
+    }
+
+    mod test_insert_resource {
+        use super::*;
+
+        #[test]
+        fn resource_is_available_to_systems_in_the_init_event() {
+            #[derive(Resource)]
+            struct Score {
+                value: u64,
+            }
+
+            let mut game = Game::new(GameOptions {
+                press_escape_to_quit: false,
+                max_frame_rate: 5,
+            })
+            .insert_resource(Score { value: 1 })
+            .add_init_system(System::new(vec![], |_, _, world| {
+                world.resource_mut::<Score>().value += 1;
+            }));
+
+            game.trigger_event(
+                EVENT_INIT,
+                Shared::new(SharedCell::new(GameCommandQueue::new())),
+            );
+
+            assert_eq!(game.world.resource::<Score>().value, 2);
+        }
+    }
+
+    mod test_change_state {
+        use std::sync::atomic::{AtomicU8, Ordering};
+
+        use super::*;
+
+        #[derive(Clone, PartialEq, Debug)]
+        enum TestState {
+            Title,
+            Playing,
+        }
+
+        fn change_state(game: &mut Game, new_state: TestState) {
+            let commands = Shared::new(SharedCell::new(GameCommandQueue::new()));
+            commands
+                .borrow_mut()
+                .issue(GameCommand::ChangeState(Box::new(new_state)));
+
+            game.process_command_queue(commands);
+        }
+
+        #[test]
+        fn runs_the_exit_and_enter_systems() {
+            static EXIT_COUNT: AtomicU8 = AtomicU8::new(0);
+            static ENTER_COUNT: AtomicU8 = AtomicU8::new(0);
+
+            let mut game = Game::new(GameOptions {
+                press_escape_to_quit: false,
+                max_frame_rate: 5,
+            })
+            .add_state(TestState::Title)
+            .add_on_exit_system(
+                TestState::Title,
+                System::new(vec![], |_, _, world| {
+                    assert!(world.resource::<State<TestState>>().is(&TestState::Title));
+
+                    EXIT_COUNT.fetch_add(1, Ordering::Relaxed);
+                }),
+            )
+            .add_on_enter_system(
+                TestState::Playing,
+                System::new(vec![], |_, _, world| {
+                    assert!(world.resource::<State<TestState>>().is(&TestState::Playing));
+
+                    ENTER_COUNT.fetch_add(1, Ordering::Relaxed);
+                }),
+            );
+
+            change_state(&mut game, TestState::Playing);
+            change_state(&mut game, TestState::Playing);
+
+            assert_eq!(EXIT_COUNT.load(Ordering::Relaxed), 1);
+            assert_eq!(ENTER_COUNT.load(Ordering::Relaxed), 1);
+            assert_eq!(
+                *game.world.resource::<State<TestState>>().current(),
+                TestState::Playing
+            );
+        }
+
+        #[test]
+        fn destroys_entities_scoped_to_the_exited_state() {
+            let mut game = Game::new(GameOptions {
+                press_escape_to_quit: false,
+                max_frame_rate: 5,
+            })
+            .add_state(TestState::Title);
+
+            let title_entity =
+                game.world
+                    .entity_manager_mut()
+                    .add_entity(vec![Box::new(StateScoped {
+                        state: TestState::Title,
+                    })]);
+            let playing_entity =
+                game.world
+                    .entity_manager_mut()
+                    .add_entity(vec![Box::new(StateScoped {
+                        state: TestState::Playing,
+                    })]);
+
+            change_state(&mut game, TestState::Playing);
+
+            assert!(!game.world.is_alive(&title_entity));
+            assert!(game.world.is_alive(&playing_entity));
+        }
+
+        #[test]
+        fn is_ignored_for_a_state_type_that_was_never_added() {
+            let mut game = Game::new(GameOptions {
+                press_escape_to_quit: false,
+                max_frame_rate: 5,
+            });
+
+            change_state(&mut game, TestState::Playing);
+
+            assert!(!game.world.has_resource::<State<TestState>>());
+        }
+    }
+
+    mod test_add_state_system {
+        use std::sync::atomic::{AtomicU8, Ordering};
+
+        use super::*;
+
+        #[derive(Clone, PartialEq, Debug)]
+        enum TestState {
+            Playing,
+            Paused,
+        }
+
+        #[test]
+        fn only_runs_in_the_state() {
+            static RUN_COUNT: AtomicU8 = AtomicU8::new(0);
+
+            let mut game = Game::new(GameOptions {
+                press_escape_to_quit: false,
+                max_frame_rate: 5,
+            })
+            .add_state(TestState::Playing)
+            .add_state_system(
+                EVENT_UPDATE,
+                TestState::Playing,
+                System::new(vec![], |_, _, _| {
+                    RUN_COUNT.fetch_add(1, Ordering::Relaxed);
+                }),
+            );
+
+            game.trigger_event(
+                EVENT_UPDATE,
+                Shared::new(SharedCell::new(GameCommandQueue::new())),
+            );
+
+            game.world.resource_mut::<State<TestState>>().current = TestState::Paused;
+
+            game.trigger_event(
+                EVENT_UPDATE,
+                Shared::new(SharedCell::new(GameCommandQueue::new())),
+            );
+
+            assert_eq!(RUN_COUNT.load(Ordering::Relaxed), 1);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    mod test_register_component {
+        use super::*;
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Component, Deserialize)]
+        struct Health<T> {
+            value: T,
+        }
+
+        mod first {
+            use super::*;
+
+            #[derive(Component, Serialize, Deserialize)]
+            #[component(serialize)]
+            pub struct Position {}
+        }
+
+        mod second {
+            use super::*;
+
+            #[derive(Component, Serialize, Deserialize)]
+            #[component(serialize)]
+            pub struct Position {}
+        }
+
+        fn game() -> Game {
+            Game::new(GameOptions {
+                press_escape_to_quit: false,
+                max_frame_rate: 0,
+            })
+        }
+
+        #[test]
+        fn names_the_component_without_its_module_path() {
+            let game = game().register_component::<first::Position>();
+
+            assert_eq!(
+                game.world
+                    .component_registry()
+                    .name_of(&first::Position::id()),
+                Some("Position")
+            );
+        }
+
+        #[test]
+        fn gives_each_type_argument_of_a_generic_component_its_own_name() {
+            let game = game()
+                .register_component::<Health<u32>>()
+                .register_component::<Health<i32>>();
+
+            assert_eq!(
+                game.world
+                    .component_registry()
+                    .name_of(&Health::<u32>::id()),
+                Some("Health<u32>")
+            );
+            assert_eq!(
+                game.world
+                    .component_registry()
+                    .name_of(&Health::<i32>::id()),
+                Some("Health<i32>")
+            );
+        }
+
+        #[test]
+        #[should_panic(
+            expected = "A different component is already registered with the name Position."
+        )]
+        fn panics_for_components_with_the_same_name_in_different_modules() {
+            game()
+                .register_component::<first::Position>()
+                .register_component::<second::Position>();
+        }
+
+        #[test]
+        fn allows_components_with_the_same_name_when_one_is_given_its_own_name() {
+            let game = game()
+                .register_component::<first::Position>()
+                .register_component_with_name::<second::Position>("OtherPosition");
+
+            assert_eq!(
+                game.world
+                    .component_registry()
+                    .name_of(&second::Position::id()),
+                Some("OtherPosition")
+            );
+        }
+    }
+
+    mod test_with_clock {
+        use super::*;
+
+        #[test]
+        #[should_panic(expected = "A game that's replaying input can't be given another clock.")]
+        fn panics_when_the_game_is_replaying_input() {
+            let recording = InputRecording::parse("thomas-input-recording 1\n1000000\n").unwrap();
+
+            Game::new(GameOptions {
+                press_escape_to_quit: false,
+                max_frame_rate: 0,
+            })
+            .replay_input(&recording)
+            .with_clock(ManualClock::new());
+        }
+    }
+
+    mod test_start {
+        use std::{env, fs, process, sync::Mutex};
+
+        use super::*;
+        use crate::{
+            Dimensions2d, IntCoords2d, Layer, TerminalRenderer, TerminalTransform,
+            EVENT_BEFORE_UPDATE,
+        };
+
+        #[test]
+        fn headless_games_draw_into_the_headless_frame() {
+            static DRAWN_LINES: Mutex<Vec<String>> = Mutex::new(vec![]);
+
+            Game::new(GameOptions {
+                press_escape_to_quit: true,
+                max_frame_rate: 0,
+            })
+            .add_init_system(System::new(vec![], |_, commands, _| {
+                commands.borrow_mut().issue(GameCommand::AddEntity(vec![
+                    Box::new(TerminalRenderer {
+                        display: '@',
+                        layer: Layer::base(),
+                        foreground_color: None,
+                        background_color: None,
+                    }),
+                    Box::new(TerminalTransform {
+                        coords: IntCoords2d::new(1, 1),
+                    }),
+                ]));
+            }))
+            .add_system(
+                EVENT_BEFORE_UPDATE,
+                System::new(vec![], |_, commands, world| {
+                    let frame = world.resource::<HeadlessFrame>();
+
+                    if frame.frames_drawn() > 0 {
+                        *DRAWN_LINES.lock().unwrap() = frame.lines();
+
+                        commands.borrow_mut().issue(GameCommand::Quit);
+                    }
+                }),
+            )
+            .start(Renderer::Headless(TerminalRendererOptions {
+                screen_resolution: Dimensions2d::new(2, 3),
+                include_default_camera: true,
+                default_foreground_color: None,
+                default_background_color: None,
+            }));
+
+            assert_eq!(*DRAWN_LINES.lock().unwrap(), vec!["   ", " @ "]);
+        }
+
+        #[test]
+        fn recorded_games_write_each_frame_to_the_recording() {
+            let path = env::temp_dir().join(format!(
+                "thomas_game_record_input_test_{}.rec",
+                process::id()
+            ));
+
+            Game::new(GameOptions {
+                press_escape_to_quit: false,
+                max_frame_rate: 0,
+            })
+            .record_input(InputRecorder::create(&path).unwrap())
+            .add_update_system(System::new(vec![], |_, commands, world| {
+                if world.resource::<Time>().frame_count() == 3 {
+                    commands.borrow_mut().issue(GameCommand::Quit);
+                }
+            }))
+            .start(Renderer::Headless(TerminalRendererOptions {
+                screen_resolution: Dimensions2d::new(1, 1),
+                include_default_camera: true,
+                default_foreground_color: None,
+                default_background_color: None,
+            }));
+
+            let recording = InputRecording::load(&path).unwrap();
+
+            fs::remove_file(&path).unwrap();
+
+            assert_eq!(recording.frames().len(), 3);
+            assert!(recording
+                .frames()
+                .iter()
+                .all(|frame| frame.pressed_keys.is_empty()));
+        }
+
+        #[test]
+        fn replayed_games_play_out_the_recording() {
+            static KEY_DOWNS: Mutex<u32> = Mutex::new(0);
+            static ELAPSED: Mutex<Duration> = Mutex::new(Duration::ZERO);
+
+            let recording =
+                InputRecording::parse("thomas-input-recording 1\n1000000\n1000000 A\n1000000 A\n")
+                    .unwrap();
+
+            Game::new(GameOptions {
+                press_escape_to_quit: false,
+                max_frame_rate: 0,
+            })
+            .replay_input(&recording)
+            .add_update_system(System::new(vec![], |_, _, world| {
+                if world.resource::<Input>().is_key_down(&Keycode::A) {
+                    *KEY_DOWNS.lock().unwrap() += 1;
+                }
+            }))
+            .add_cleanup_system(System::new(vec![], |_, _, world| {
+                *ELAPSED.lock().unwrap() = world.resource::<Time>().elapsed();
+            }))
+            .start(Renderer::Headless(TerminalRendererOptions {
+                screen_resolution: Dimensions2d::new(1, 1),
+                include_default_camera: true,
+                default_foreground_color: None,
+                default_background_color: None,
+            }));
+
+            assert_eq!(*KEY_DOWNS.lock().unwrap(), 1);
+            assert_eq!(*ELAPSED.lock().unwrap(), Duration::from_millis(3));
+        }
+
+        #[test]
+        fn replayed_games_can_be_stepped() {
+            static KEY_DOWNS: Mutex<u32> = Mutex::new(0);
+
+            let recording =
+                InputRecording::parse("thomas-input-recording 1\n1000000\n1000000 A\n1000000 A\n")
+                    .unwrap();
+
+            let mut game = Game::new(GameOptions {
+                press_escape_to_quit: false,
+                max_frame_rate: 0,
+            })
+            .replay_input(&recording)
+            .add_update_system(System::new(vec![], |_, _, world| {
+                if world.resource::<Input>().is_key_down(&Keycode::A) {
+                    *KEY_DOWNS.lock().unwrap() += 1;
+                }
+            }))
+            .start_stepped(Renderer::Headless(TerminalRendererOptions {
+                screen_resolution: Dimensions2d::new(1, 1),
+                include_default_camera: true,
+                default_foreground_color: None,
+                default_background_color: None,
+            }));
+
+            game.step(Duration::from_millis(1));
+
+            assert_eq!(*KEY_DOWNS.lock().unwrap(), 0);
+
+            game.step_frames(2, Duration::from_millis(1));
+
+            assert_eq!(*KEY_DOWNS.lock().unwrap(), 1);
+            assert_eq!(
+                game.world().resource::<Time>().elapsed(),
+                Duration::from_millis(3)
+            );
         }
     }
 }
@@ -0,0 +1,269 @@
+use std::{
+    collections::VecDeque,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::Path,
+    time::Duration,
+};
+
+use device_query::Keycode;
+
+use crate::{Clock, InputSource, ManualClock, RealClock};
+
+const INPUT_RECORDING_HEADER: &str = "thomas-input-recording 1";
+
+/// The keys that were pressed during one frame of a recorded game, and how long the frame took.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordedFrame {
+    pub frame_duration: Duration,
+    pub pressed_keys: Vec<Keycode>,
+}
+
+/// A recording of the input a game received, frame by frame, made by an `InputRecorder`. Load a recording with `load`
+/// and give it to `Game::replay_input` to play the game again exactly as it was played when it was recorded.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InputRecording {
+    frames: Vec<RecordedFrame>,
+}
+impl InputRecording {
+    /// Reads a recording from the file at `path`. Fails if the file can't be read, or isn't a recording.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn frames(&self) -> &[RecordedFrame] {
+        &self.frames
+    }
+
+    pub(crate) fn parse(contents: &str) -> io::Result<Self> {
+        let mut lines = contents.lines();
+
+        if lines.next() != Some(INPUT_RECORDING_HEADER) {
+            return Err(invalid_data(
+                "Input recording is missing its header.".to_string(),
+            ));
+        }
+
+        let frames = lines
+            .enumerate()
+            .map(|(index, line)| {
+                let line_number = index + 2;
+                let mut parts = line.split_whitespace();
+
+                let frame_duration = parts
+                    .next()
+                    .and_then(|nanos| nanos.parse::<u64>().ok())
+                    .map(Duration::from_nanos)
+                    .ok_or_else(|| {
+                        invalid_data(format!(
+                            "Line {line_number} of input recording has no frame duration."
+                        ))
+                    })?;
+
+                let pressed_keys = parts
+                    .map(|key| {
+                        key.parse::<Keycode>().map_err(|_| {
+                            invalid_data(format!(
+                                "Line {line_number} of input recording has unknown key {key}."
+                            ))
+                        })
+                    })
+                    .collect::<io::Result<Vec<Keycode>>>()?;
+
+                Ok(RecordedFrame {
+                    frame_duration,
+                    pressed_keys,
+                })
+            })
+            .collect::<io::Result<Vec<RecordedFrame>>>()?;
+
+        Ok(Self { frames })
+    }
+}
+
+/// Writes the input a game receives to a file as it's played. Give a recorder to `Game::record_input` to have the game
+/// record itself.
+///
+/// Each frame is written as soon as it's done, so the recording holds everything up to the last frame even if the game
+/// doesn't end cleanly.
+pub struct InputRecorder {
+    writer: BufWriter<File>,
+}
+impl InputRecorder {
+    /// Creates the file at `path` to write the recording to, replacing it if it already exists.
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+
+        writeln!(writer, "{INPUT_RECORDING_HEADER}")?;
+        writer.flush()?;
+
+        Ok(Self { writer })
+    }
+
+    pub(crate) fn record_frame(&mut self, frame: &RecordedFrame) -> io::Result<()> {
+        write!(self.writer, "{}", frame.frame_duration.as_nanos())?;
+
+        for key in &frame.pressed_keys {
+            write!(self.writer, " {key}")?;
+        }
+
+        writeln!(self.writer)?;
+
+        self.writer.flush()
+    }
+}
+
+/// Plays an `InputRecording` back into a game. The recorded keys are fed to `Input` through a `ReplayInputSource`,
+/// and the recorded frame durations drive the game's clock.
+pub(crate) struct InputReplay {
+    frame_durations: VecDeque<Duration>,
+    clock: ManualClock,
+    playback_clock: RealClock,
+}
+impl InputReplay {
+    pub fn new(recording: &InputRecording, clock: ManualClock) -> Self {
+        Self {
+            frame_durations: recording
+                .frames
+                .iter()
+                .map(|frame| frame.frame_duration)
+                .collect(),
+            clock,
+            playback_clock: RealClock::new(),
+        }
+    }
+
+    pub fn clock(&self) -> &ManualClock {
+        &self.clock
+    }
+
+    /// Moves the game's clock forward by the next recorded frame's duration and gives that duration back, or gives
+    /// `None` if the recording is over.
+    pub fn start_frame(&mut self) -> Option<Duration> {
+        let frame_duration = self.frame_durations.pop_front()?;
+
+        self.clock.advance(frame_duration);
+
+        Some(frame_duration)
+    }
+
+    /// Waits in real time until the replay has caught up with the recording, so it plays back at the speed it was
+    /// recorded at.
+    pub fn wait_for_frame_end(&self) {
+        self.playback_clock.wait_until(self.clock.now());
+    }
+}
+
+/// An `InputSource` that gives back the keys from each frame of an `InputRecording`, in order. Once the recording runs
+/// out, no keys are pressed.
+pub(crate) struct ReplayInputSource {
+    frames: VecDeque<Vec<Keycode>>,
+}
+impl ReplayInputSource {
+    pub fn new(recording: &InputRecording) -> Self {
+        Self {
+            frames: recording
+                .frames
+                .iter()
+                .map(|frame| frame.pressed_keys.clone())
+                .collect(),
+        }
+    }
+}
+impl InputSource for ReplayInputSource {
+    fn pressed_keys(&mut self) -> Vec<Keycode> {
+        self.frames.pop_front().unwrap_or_default()
+    }
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{env, process};
+
+    mod test_parse {
+        use super::*;
+
+        #[test]
+        fn reads_each_frame() {
+            let recording =
+                InputRecording::parse("thomas-input-recording 1\n16666667 A LShift\n1500\n")
+                    .unwrap();
+
+            assert_eq!(
+                recording.frames(),
+                &[
+                    RecordedFrame {
+                        frame_duration: Duration::from_nanos(16_666_667),
+                        pressed_keys: vec![Keycode::A, Keycode::LShift],
+                    },
+                    RecordedFrame {
+                        frame_duration: Duration::from_nanos(1500),
+                        pressed_keys: vec![],
+                    },
+                ]
+            );
+        }
+
+        #[test]
+        fn fails_without_a_header() {
+            assert!(InputRecording::parse("16666667 A\n").is_err());
+        }
+
+        #[test]
+        fn fails_on_unknown_keys() {
+            assert!(InputRecording::parse("thomas-input-recording 1\n100 NotAKey\n").is_err());
+        }
+    }
+
+    mod test_input_recorder {
+        use super::*;
+
+        #[test]
+        fn recorded_frames_can_be_loaded() {
+            let path =
+                env::temp_dir().join(format!("thomas_input_recorder_test_{}.rec", process::id()));
+            let frames = vec![
+                RecordedFrame {
+                    frame_duration: Duration::from_nanos(123_456),
+                    pressed_keys: vec![Keycode::Space],
+                },
+                RecordedFrame {
+                    frame_duration: Duration::from_millis(16),
+                    pressed_keys: vec![Keycode::Up, Keycode::LControl],
+                },
+            ];
+
+            let mut recorder = InputRecorder::create(&path).unwrap();
+
+            for frame in &frames {
+                recorder.record_frame(frame).unwrap();
+            }
+
+            let recording = InputRecording::load(&path).unwrap();
+
+            fs::remove_file(&path).unwrap();
+
+            assert_eq!(recording.frames(), &frames[..]);
+        }
+    }
+
+    mod test_replay_input_source {
+        use super::*;
+
+        #[test]
+        fn gives_no_keys_once_the_recording_runs_out() {
+            let recording =
+                InputRecording::parse("thomas-input-recording 1\n100 Escape\n").unwrap();
+            let mut source = ReplayInputSource::new(&recording);
+
+            assert_eq!(source.pressed_keys(), vec![Keycode::Escape]);
+            assert!(source.pressed_keys().is_empty());
+        }
+    }
+}
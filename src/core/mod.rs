@@ -1,20 +1,74 @@
+mod shared;
+pub use shared::*;
+
 mod entity;
 pub use entity::*;
 
 mod component;
 pub use component::*;
 
+mod reflect;
+pub use reflect::*;
+
+mod resource;
+pub use resource::*;
+
 mod system;
 pub use system::*;
 
+mod system_access;
+pub(crate) use system_access::*;
+
 mod data;
 pub use data::*;
 
+mod change_detection;
+pub(crate) use change_detection::*;
+
+mod archetype;
+pub(crate) use archetype::*;
+
 mod entity_manager;
 pub use entity_manager::*;
 
+mod event_channel;
+pub use event_channel::*;
+
+mod state;
+pub use state::*;
+
+mod scene;
+pub use scene::*;
+
+mod world;
+pub use world::*;
+
 mod query;
 pub use query::*;
 
+mod typed_query;
+pub use typed_query::*;
+
 mod game;
-pub use game::*;
\ No newline at end of file
+pub use game::*;
+
+mod stepped_game;
+pub use stepped_game::*;
+
+mod input_recording;
+pub use input_recording::*;
+
+#[cfg(feature = "serde")]
+mod snapshot;
+#[cfg(feature = "serde")]
+pub use snapshot::*;
+
+#[cfg(feature = "serde")]
+mod prefab;
+#[cfg(feature = "serde")]
+pub use prefab::*;
+
+#[cfg(feature = "serde")]
+mod component_registry;
+#[cfg(feature = "serde")]
+pub(crate) use component_registry::*;
\ No newline at end of file
@@ -0,0 +1,296 @@
+use std::{collections::BTreeMap, path::Path};
+
+use serde::Deserialize;
+
+use crate::{
+    from_ron_data, load_data_file, Component, Entity, SnapshotError, SnapshotResult, SnapshotValue,
+    World,
+};
+
+/// A template for an entity: a set of components, keyed by the name each component was registered with, along with
+/// the values of their fields.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(transparent)]
+pub struct Prefab {
+    components: BTreeMap<String, SnapshotValue>,
+}
+impl Prefab {
+    pub fn components(&self) -> &BTreeMap<String, SnapshotValue> {
+        &self.components
+    }
+}
+
+/// A collection of named `Prefab`s, usually read from a data file so entities can be designed without writing any
+/// code. Give them to the game with `Game::add_prefabs`, then spawn them with `GameCommand::SpawnPrefab`.
+///
+/// Each prefab maps the names of its components to their fields. Thomas's own components are registered under their
+/// type names, and your own components need to be registered with `Game::register_component`. In RON, a file of
+/// prefabs looks like this:
+/// ```
+/// use thomas::Prefabs;
+///
+/// let prefabs = Prefabs::from_ron(r#"{
+///     "goblin": {
+///         "TerminalRenderer": (
+///             display: 'g',
+///             layer: 1,
+///             foreground_color: Some((0, 255, 0)),
+///             background_color: None,
+///         ),
+///         "TerminalCollider": (layer: 1, is_active: true),
+///     },
+/// }"#).unwrap();
+///
+/// assert!(prefabs.get("goblin").is_some());
+/// ```
+/// And the same prefab in TOML:
+/// ```toml
+/// [goblin.TerminalRenderer]
+/// display = "g"
+/// layer = 1
+/// foreground_color = [0, 255, 0]
+///
+/// [goblin.TerminalCollider]
+/// layer = 1
+/// is_active = true
+/// ```
+/// Fields that are `Option`s can be left out to make them `None`. Enum variants are written as strings, like
+/// `"Center"`.
+#[derive(Clone, Debug, PartialEq, Default, Deserialize)]
+#[serde(transparent)]
+pub struct Prefabs {
+    prefabs: BTreeMap<String, Prefab>,
+}
+impl Prefabs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_ron(ron: &str) -> SnapshotResult<Self> {
+        from_ron_data(ron)
+    }
+
+    pub fn from_toml(toml: &str) -> SnapshotResult<Self> {
+        Ok(toml::from_str(toml)?)
+    }
+
+    /// Reads prefabs from the file at `path`. The file is read as RON or TOML depending on whether its extension is
+    /// `.ron` or `.toml`.
+    pub fn load(path: impl AsRef<Path>) -> SnapshotResult<Self> {
+        load_data_file(path.as_ref())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Prefab> {
+        self.prefabs.get(name)
+    }
+
+    /// Adds all the provided prefabs to these ones. Any prefab with the same name as one that's already here replaces
+    /// it.
+    pub fn extend(&mut self, prefabs: Prefabs) {
+        self.prefabs.extend(prefabs.prefabs);
+    }
+}
+
+impl World {
+    /// Whether a prefab with the name was given to the game.
+    pub fn has_prefab(&self, name: &str) -> bool {
+        self.prefabs().get(name).is_some()
+    }
+
+    /// Adds an entity made from the named prefab. Each override replaces the prefab's component of the same type, or
+    /// is added alongside the prefab's components if the prefab doesn't have one. Nothing's added if there's no prefab
+    /// with the name.
+    pub(crate) fn spawn_prefab(
+        &mut self,
+        name: &str,
+        overrides: Vec<Box<dyn Component>>,
+    ) -> Option<Entity> {
+        let mut components = self.make_prefab_components(name)?.ok()?;
+
+        components.retain(|component| {
+            !overrides.iter().any(|override_component| {
+                override_component.component_id() == component.component_id()
+            })
+        });
+        components.extend(overrides);
+
+        Some(self.entity_manager_mut().add_entity(components))
+    }
+
+    /// Checks that every component of every prefab is registered and can be made from the prefab's data.
+    pub(crate) fn validate_prefabs(&self) -> SnapshotResult<()> {
+        for name in self.prefabs().prefabs.keys() {
+            if let Some(Err(error)) = self.make_prefab_components(name) {
+                return Err(SnapshotError::new(format!(
+                    "Prefab {name} is invalid: {error}"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn make_prefab_components(
+        &self,
+        name: &str,
+    ) -> Option<SnapshotResult<Vec<Box<dyn Component>>>> {
+        let prefab = self.prefabs().get(name)?;
+
+        Some(
+            prefab
+                .components
+                .iter()
+                .map(|(component_name, value)| {
+                    self.component_registry()
+                        .deserialize(component_name, value.clone())
+                })
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        IntCoords2d, Layer, Query, Rgb, TerminalCollider, TerminalRenderer, TerminalTransform,
+    };
+
+    const GOBLIN_RON: &str = r#"{
+        "goblin": {
+            "TerminalRenderer": (
+                display: 'g',
+                layer: 1,
+                foreground_color: Some((0, 255, 0)),
+                background_color: None,
+            ),
+            "TerminalCollider": (layer: 1, is_active: true),
+            "TerminalTransform": (coords: (x: 0, y: 0)),
+        },
+    }"#;
+
+    const GOBLIN_TOML: &str = r#"
+        [goblin.TerminalRenderer]
+        display = "g"
+        layer = 1
+        foreground_color = [0, 255, 0]
+
+        [goblin.TerminalCollider]
+        layer = 1
+        is_active = true
+
+        [goblin.TerminalTransform]
+        coords = { x = 0, y = 0 }
+    "#;
+
+    fn world_with(prefabs: Prefabs) -> World {
+        let mut world = World::new();
+
+        world.add_prefabs(prefabs);
+
+        world
+    }
+
+    fn assert_is_goblin(world: &World, entity: &Entity) {
+        let renderer = world
+            .entity_manager()
+            .get_component::<TerminalRenderer>(entity)
+            .unwrap();
+
+        assert_eq!(renderer.display, 'g');
+        assert_eq!(renderer.layer, Layer(1));
+        assert_eq!(renderer.foreground_color, Some(Rgb(0, 255, 0)));
+        assert_eq!(renderer.background_color, None);
+
+        let collider = world
+            .entity_manager()
+            .get_component::<TerminalCollider>(entity)
+            .unwrap();
+
+        assert_eq!(collider.layer, Layer(1));
+        assert!(collider.is_active);
+    }
+
+    mod test_spawn_prefab {
+        use super::*;
+
+        #[test]
+        fn makes_the_same_components_from_ron_and_toml() {
+            for prefabs in [
+                Prefabs::from_ron(GOBLIN_RON).unwrap(),
+                Prefabs::from_toml(GOBLIN_TOML).unwrap(),
+            ] {
+                let mut world = world_with(prefabs);
+
+                let goblin = world.spawn_prefab("goblin", vec![]).unwrap();
+
+                assert_is_goblin(&world, &goblin);
+            }
+        }
+
+        #[test]
+        fn overrides_replace_the_prefabs_components() {
+            let mut world = world_with(Prefabs::from_ron(GOBLIN_RON).unwrap());
+
+            let goblin = world
+                .spawn_prefab(
+                    "goblin",
+                    vec![Box::new(TerminalTransform {
+                        coords: IntCoords2d::new(4, 2),
+                    })],
+                )
+                .unwrap();
+
+            assert_eq!(
+                world
+                    .entity_manager()
+                    .query(&Query::new().has::<TerminalTransform>())
+                    .len(),
+                1
+            );
+            assert_eq!(
+                world
+                    .entity_manager()
+                    .get_component::<TerminalTransform>(&goblin)
+                    .unwrap()
+                    .coords,
+                IntCoords2d::new(4, 2)
+            );
+        }
+
+        #[test]
+        fn does_nothing_for_unknown_prefabs() {
+            let mut world = world_with(Prefabs::from_ron(GOBLIN_RON).unwrap());
+
+            assert!(world.spawn_prefab("orc", vec![]).is_none());
+        }
+    }
+
+    mod test_validate_prefabs {
+        use super::*;
+
+        #[test]
+        fn fails_for_unregistered_components() {
+            let world =
+                world_with(Prefabs::from_ron(r#"{ "orc": { "Axe": (damage: 3) } }"#).unwrap());
+
+            assert!(world.validate_prefabs().is_err());
+        }
+
+        #[test]
+        fn fails_for_components_with_bad_fields() {
+            let world = world_with(
+                Prefabs::from_ron(r#"{ "orc": { "TerminalCollider": (layer: "high") } }"#).unwrap(),
+            );
+
+            assert!(world.validate_prefabs().is_err());
+        }
+
+        #[test]
+        fn passes_for_valid_prefabs() {
+            let world = world_with(Prefabs::from_toml(GOBLIN_TOML).unwrap());
+
+            assert!(world.validate_prefabs().is_ok());
+        }
+    }
+}
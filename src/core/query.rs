@@ -1,9 +1,10 @@
 use std::{
-    cell::{Ref, RefMut},
+    any::TypeId,
+    collections::HashSet,
     ops::{Deref, DerefMut},
 };
 
-use crate::{Component, Entity, StoredComponentList};
+use crate::{Component, Entity, Ref, RefMut, StoredComponentList};
 
 pub type WherePredicate = dyn Fn(&dyn Component) -> bool + 'static;
 
@@ -24,22 +25,47 @@ pub type WherePredicate = dyn Fn(&dyn Component) -> bool + 'static;
 /// ```
 /// Will match _only_ for entities that have a `TerminalTransform`, AND do NOT have a `CustomComponent`, AND have an `Identity`
 /// component where its `id` property equals `"PLAYER"`, .
+///
+/// When you need a logical OR, use `has_any`. When you'd like a component if it's there but don't want to require it,
+/// use `has_optional`:
+/// ```
+/// use thomas::{Query, TerminalTransform, TerminalRenderer, Text, WorldText};
+///
+/// Query::new()
+///     .has::<TerminalTransform>()
+///     .has_optional::<TerminalRenderer>()
+///     .has_any::<(Text, WorldText)>();
+/// ```
+/// Will match for entities that have a `TerminalTransform`, AND have a `Text` OR a `WorldText` (or both). Matches that
+/// also have a `TerminalRenderer` will have it in their components.
 pub struct Query {
     allowed_components: Vec<ComponentQueryData>,
     forbidden_components: Vec<ComponentQueryData>,
+    optional_components: Vec<TypeId>,
+    any_of_component_groups: Vec<Vec<TypeId>>,
+    added_components: Vec<TypeId>,
+    changed_components: Vec<TypeId>,
+    allowed_entities: Option<HashSet<Entity>>,
+    is_read_only: bool,
 }
 impl Query {
     pub fn new() -> Self {
         Self {
             allowed_components: vec![],
             forbidden_components: vec![],
+            optional_components: vec![],
+            any_of_component_groups: vec![],
+            added_components: vec![],
+            changed_components: vec![],
+            allowed_entities: None,
+            is_read_only: false,
         }
     }
 
     /// Specifies that a matching entity must have the provided component to be a match for the query.
     pub fn has<T: Component + 'static>(mut self) -> Self {
         self.allowed_components
-            .push(ComponentQueryData::new(T::name(), None));
+            .push(ComponentQueryData::new(T::id(), None));
 
         self
     }
@@ -49,7 +75,7 @@ impl Query {
     /// `has_no` calls on the query, that will entirely remove that entity from the ultimate list of matches.
     pub fn has_no<T: Component + 'static>(mut self) -> Self {
         self.forbidden_components
-            .push(ComponentQueryData::new(T::name(), None));
+            .push(ComponentQueryData::new(T::id(), None));
 
         self
     }
@@ -61,7 +87,7 @@ impl Query {
         T: Component + 'static,
     {
         self.allowed_components.push(ComponentQueryData::new(
-            T::name(),
+            T::id(),
             Some(Box::new(move |comp| {
                 predicate(T::cast(comp).expect(&format!(
                     "Component provided to where clause of query can be cast to concrete Component {}",
@@ -73,25 +99,149 @@ impl Query {
         self
     }
 
+    /// Specifies that the provided component should be included in a match's components when the matching entity has
+    /// it. Unlike `has`, the entity doesn't need to have the component to be a match for the query, so use `try_get`
+    /// or `try_get_mut` to get at it in the results.
+    pub fn has_optional<T: Component + 'static>(mut self) -> Self {
+        self.optional_components.push(T::id());
+
+        self
+    }
+
+    /// Specifies that a matching entity must have _at least one_ of the provided components to be a match for the query.
+    /// Components are given as a tuple, like `has_any::<(Text, WorldText)>()`. Whichever of the components the matching
+    /// entity has will be included in the match's components, so use `try_get` or `try_get_mut` to get at them in the
+    /// results.
+    ///
+    /// Each call to `has_any` is its own group, and an entity must satisfy every group to be a match.
+    pub fn has_any<T: ComponentSet>(mut self) -> Self {
+        self.any_of_component_groups.push(T::component_ids());
+
+        self
+    }
+
+    /// Specifies that a matching entity must have the provided component _and_ the component must have been added to
+    /// the entity since the `System` running the query last ran. This includes components on newly added entities.
+    ///
+    /// The first time a `System` runs, every component it can see is considered added.
+    pub fn added<T: Component + 'static>(mut self) -> Self {
+        self.added_components.push(T::id());
+
+        self.has::<T>()
+    }
+
+    /// Specifies that a matching entity must have the provided component _and_ the component must have changed since
+    /// the `System` running the query last ran. A component is considered changed when it's added, and whenever it's
+    /// mutably borrowed from a query's results with `get_mut` or `try_get_mut`.
+    ///
+    /// The first time a `System` runs, every component it can see is considered changed.
+    pub fn changed<T: Component + 'static>(mut self) -> Self {
+        self.changed_components.push(T::id());
+
+        self.has::<T>()
+    }
+
+    /// Specifies that a matching entity must be the provided `Entity`. Handy for getting at the components of an
+    /// `Entity` you've held onto, though `World::component` is often simpler when you only need one component.
+    pub fn for_entity(self, entity: Entity) -> Self {
+        self.for_entities(vec![entity])
+    }
+
+    /// Specifies that a matching entity must be one of the provided entities. Like with everything else in a query,
+    /// calling `for_entities` more than once narrows down the matches further: a matching entity must be in every
+    /// list. The entities are looked up directly rather than searched for, so this stays quick however big the world
+    /// gets. With no other terms, the query matches every provided entity that's alive.
+    pub fn for_entities(mut self, entities: Vec<Entity>) -> Self {
+        let entities: HashSet<Entity> = entities.into_iter().collect();
+
+        self.allowed_entities = Some(match self.allowed_entities {
+            Some(allowed_entities) => allowed_entities.intersection(&entities).copied().collect(),
+            None => entities,
+        });
+
+        self
+    }
+
+    /// Promises that the `System` running the query only ever reads the components it gets from the query's matches.
+    /// With the `parallel` feature, systems that only read the same components can run at the same time, whereas a
+    /// `System` that may write to a component has to run on its own. Borrowing a component from a read-only query's
+    /// matches with `get_mut` or `try_get_mut` may panic if another `System` is reading it at the same time.
+    pub fn read_only(mut self) -> Self {
+        self.is_read_only = true;
+
+        self
+    }
+
     pub(super) fn allowed_components(&self) -> &Vec<ComponentQueryData> {
         &self.allowed_components
     }
 
-    pub(super) fn allowed_component_names(&self) -> Vec<&'static str> {
+    pub(super) fn allowed_component_ids(&self) -> Vec<TypeId> {
         self.allowed_components
             .iter()
-            .map(|component_query_data| component_query_data.component_name)
+            .map(|component_query_data| component_query_data.component_id)
             .collect()
     }
 
-    pub(super) fn forbidden_component_names(&self) -> Vec<&'static str> {
+    pub(super) fn forbidden_component_ids(&self) -> Vec<TypeId> {
         self.forbidden_components
             .iter()
-            .map(|component_query_data| component_query_data.component_name)
+            .map(|component_query_data| component_query_data.component_id)
             .collect()
     }
+
+    pub(super) fn optional_component_ids(&self) -> &Vec<TypeId> {
+        &self.optional_components
+    }
+
+    pub(super) fn any_of_component_groups(&self) -> &Vec<Vec<TypeId>> {
+        &self.any_of_component_groups
+    }
+
+    pub(super) fn added_component_ids(&self) -> &Vec<TypeId> {
+        &self.added_components
+    }
+
+    pub(super) fn changed_component_ids(&self) -> &Vec<TypeId> {
+        &self.changed_components
+    }
+
+    /// Whether the entity is allowed to match the query. Every entity is allowed unless the query is restricted with
+    /// `for_entity` or `for_entities`.
+    pub(super) fn allowed_entities(&self) -> Option<&HashSet<Entity>> {
+        self.allowed_entities.as_ref()
+    }
+
+    pub(super) fn is_read_only(&self) -> bool {
+        self.is_read_only
+    }
 }
 
+/// A set of component types, given as a tuple like `(Text, WorldText)`. Used by `Query::has_any`.
+pub trait ComponentSet {
+    /// The `id()`s of the components in the set.
+    fn component_ids() -> Vec<TypeId>;
+}
+
+macro_rules! impl_component_set_for_tuple {
+    ($($component:ident),*) => {
+        impl<$($component: Component + 'static),*> ComponentSet for ($($component,)*) {
+            fn component_ids() -> Vec<TypeId> {
+                vec![$($component::id()),*]
+            }
+        }
+    };
+}
+
+impl_component_set_for_tuple!(A);
+impl_component_set_for_tuple!(A, B);
+impl_component_set_for_tuple!(A, B, C);
+impl_component_set_for_tuple!(A, B, C, D);
+impl_component_set_for_tuple!(A, B, C, D, E);
+impl_component_set_for_tuple!(A, B, C, D, E, F);
+impl_component_set_for_tuple!(A, B, C, D, E, F, G);
+impl_component_set_for_tuple!(A, B, C, D, E, F, G, H);
+
 /// Represents a single match from a query.
 pub struct QueryResult {
     pub(crate) entity: Entity,
@@ -196,19 +346,19 @@ impl DerefMut for QueryResultList {
 }
 
 pub(crate) struct ComponentQueryData {
-    component_name: &'static str,
+    component_id: TypeId,
     where_predicate: Option<Box<WherePredicate>>,
 }
 impl ComponentQueryData {
-    pub fn new(component_name: &'static str, where_predicate: Option<Box<WherePredicate>>) -> Self {
+    pub fn new(component_id: TypeId, where_predicate: Option<Box<WherePredicate>>) -> Self {
         Self {
-            component_name,
+            component_id,
             where_predicate,
         }
     }
 
-    pub fn component_name(&self) -> &'static str {
-        &self.component_name
+    pub fn component_id(&self) -> &TypeId {
+        &self.component_id
     }
 
     pub fn where_predicate(&self) -> &Option<Box<WherePredicate>> {
@@ -219,8 +369,7 @@ impl ComponentQueryData {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::Component;
-    use std::{cell::RefCell, rc::Rc};
+    use crate::{Component, Shared, SharedCell};
 
     #[derive(Component)]
     struct TestComponent {
@@ -240,8 +389,8 @@ mod tests {
         #[test]
         fn gives_back_component_when_it_is_present_in_the_results() {
             let qr = QueryResult {
-                entity: Entity(0),
-                components: StoredComponentList::new(vec![Rc::new(RefCell::new(Box::new(
+                entity: Entity(0, 0),
+                components: StoredComponentList::new(vec![Shared::new(SharedCell::new(Box::new(
                     TestComponent {
                         prop: "val".to_string(),
                     },
@@ -257,8 +406,8 @@ mod tests {
         #[test]
         fn is_none_when_component_is_not_present_in_the_results() {
             let qr = QueryResult {
-                entity: Entity(0),
-                components: StoredComponentList::new(vec![Rc::new(RefCell::new(Box::new(
+                entity: Entity(0, 0),
+                components: StoredComponentList::new(vec![Shared::new(SharedCell::new(Box::new(
                     AnotherEmptyComponent {},
                 )
                     as Box<dyn Component>))]),
@@ -276,8 +425,8 @@ mod tests {
         #[test]
         fn can_mutate_returned_component() {
             let qr = QueryResult {
-                entity: Entity(0),
-                components: StoredComponentList::new(vec![Rc::new(RefCell::new(Box::new(
+                entity: Entity(0, 0),
+                components: StoredComponentList::new(vec![Shared::new(SharedCell::new(Box::new(
                     TestComponent {
                         prop: "val".to_string(),
                     },
@@ -299,8 +448,8 @@ mod tests {
         #[test]
         fn is_none_when_component_is_not_present_in_the_results() {
             let qr = QueryResult {
-                entity: Entity(0),
-                components: StoredComponentList::new(vec![Rc::new(RefCell::new(Box::new(
+                entity: Entity(0, 0),
+                components: StoredComponentList::new(vec![Shared::new(SharedCell::new(Box::new(
                     AnotherEmptyComponent {},
                 )
                     as Box<dyn Component>))]),
@@ -318,8 +467,8 @@ mod tests {
         #[test]
         fn gives_back_component_when_it_is_present_in_the_results() {
             let qr = QueryResult {
-                entity: Entity(0),
-                components: StoredComponentList::new(vec![Rc::new(RefCell::new(Box::new(
+                entity: Entity(0, 0),
+                components: StoredComponentList::new(vec![Shared::new(SharedCell::new(Box::new(
                     TestComponent {
                         prop: "val".to_string(),
                     },
@@ -334,12 +483,12 @@ mod tests {
 
         #[test]
         #[should_panic(
-            expected = "Component EmptyComponent was not present, or you're trying to borrow it while it's already mutably borrowed."
+            expected = "Component thomas::core::query::tests::EmptyComponent was not present, or you're trying to borrow it while it's already mutably borrowed."
         )]
         fn panics_when_component_is_not_present_in_the_results() {
             let qr = QueryResult {
-                entity: Entity(0),
-                components: StoredComponentList::new(vec![Rc::new(RefCell::new(Box::new(
+                entity: Entity(0, 0),
+                components: StoredComponentList::new(vec![Shared::new(SharedCell::new(Box::new(
                     AnotherEmptyComponent {},
                 )
                     as Box<dyn Component>))]),
@@ -355,8 +504,8 @@ mod tests {
         #[test]
         fn can_mutate_returned_component() {
             let qr = QueryResult {
-                entity: Entity(0),
-                components: StoredComponentList::new(vec![Rc::new(RefCell::new(Box::new(
+                entity: Entity(0, 0),
+                components: StoredComponentList::new(vec![Shared::new(SharedCell::new(Box::new(
                     TestComponent {
                         prop: "val".to_string(),
                     },
@@ -377,12 +526,12 @@ mod tests {
 
         #[test]
         #[should_panic(
-            expected = "Component EmptyComponent was not present, or you're trying to borrow it while it's already mutably borrowed."
+            expected = "Component thomas::core::query::tests::EmptyComponent was not present, or you're trying to borrow it while it's already mutably borrowed."
         )]
         fn panics_when_component_is_not_present_in_the_results() {
             let qr = QueryResult {
-                entity: Entity(0),
-                components: StoredComponentList::new(vec![Rc::new(RefCell::new(Box::new(
+                entity: Entity(0, 0),
+                components: StoredComponentList::new(vec![Shared::new(SharedCell::new(Box::new(
                     AnotherEmptyComponent {},
                 )
                     as Box<dyn Component>))]),
@@ -0,0 +1,388 @@
+use std::fmt;
+
+use crate::{Coords2d, Coords3d, Dimensions2d, IntCoords2d, IntCoords3d, Layer, Rgb};
+
+/// The name and type of one of a component's fields. Tuple structs' fields are named by their position, like `0`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FieldInfo {
+    name: &'static str,
+    type_name: &'static str,
+}
+impl FieldInfo {
+    #[doc(hidden)]
+    pub const fn new(name: &'static str, type_name: &'static str) -> Self {
+        Self { name, type_name }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The field's type as it's written in the component, like `Option<Rgb>`.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+}
+
+/// The value of a component's field, read or written through reflection with `Component::field` and
+/// `Component::set_field`.
+///
+/// Signed integers are all read as `Int` and unsigned integers as `UInt`, but either can be written to any integer
+/// field as long as the value fits. Integers can also be written to floating point fields.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FieldValue {
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    Char(char),
+    String(String),
+    IntCoords2d(IntCoords2d),
+    IntCoords3d(IntCoords3d),
+    Coords2d(Coords2d),
+    Coords3d(Coords3d),
+    Dimensions2d(Dimensions2d),
+    Layer(Layer),
+    Rgb(Rgb),
+    Option(Option<Box<FieldValue>>),
+}
+
+impl fmt::Display for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bool(value) => write!(f, "{value}"),
+            Self::Int(value) => write!(f, "{value}"),
+            Self::UInt(value) => write!(f, "{value}"),
+            Self::Float(value) => write!(f, "{value}"),
+            Self::Char(value) => write!(f, "{value:?}"),
+            Self::String(value) => write!(f, "{value:?}"),
+            Self::IntCoords2d(value) => write!(f, "({}, {})", value.x(), value.y()),
+            Self::IntCoords3d(value) => write!(f, "({}, {}, {})", value.x(), value.y(), value.z()),
+            Self::Coords2d(value) => write!(f, "({}, {})", value.x(), value.y()),
+            Self::Coords3d(value) => write!(f, "({}, {}, {})", value.x(), value.y(), value.z()),
+            Self::Dimensions2d(value) => write!(f, "{}x{}", value.width(), value.height()),
+            Self::Layer(value) => write!(f, "Layer({})", value.value()),
+            Self::Rgb(value) => write!(f, "Rgb({}, {}, {})", value.r(), value.g(), value.b()),
+            Self::Option(Some(value)) => write!(f, "Some({value})"),
+            Self::Option(None) => write!(f, "None"),
+        }
+    }
+}
+
+/// A type of field that can be read and written through reflection. `#[derive(Component)]` makes every field of one
+/// of these types available through `Component::field` and `Component::set_field`. Fields of any other type are still
+/// listed by `Component::fields`, but their values can't be read or written.
+pub trait ReflectField: Sized {
+    fn to_field_value(&self) -> FieldValue;
+    /// Makes a value of this type from the `FieldValue`, or gives `None` if the value isn't the right type.
+    fn from_field_value(value: FieldValue) -> Option<Self>;
+}
+
+macro_rules! impl_reflect_field_for_int {
+    ($variant:ident, $($int:ty),*) => {
+        $(
+            impl ReflectField for $int {
+                fn to_field_value(&self) -> FieldValue {
+                    FieldValue::$variant((*self).try_into().unwrap())
+                }
+
+                fn from_field_value(value: FieldValue) -> Option<Self> {
+                    match value {
+                        FieldValue::Int(value) => value.try_into().ok(),
+                        FieldValue::UInt(value) => value.try_into().ok(),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_reflect_field_for_int!(Int, i8, i16, i32, i64, isize);
+impl_reflect_field_for_int!(UInt, u8, u16, u32, u64, usize);
+
+macro_rules! impl_reflect_field_for_float {
+    ($($float:ty),*) => {
+        $(
+            impl ReflectField for $float {
+                fn to_field_value(&self) -> FieldValue {
+                    FieldValue::Float(*self as f64)
+                }
+
+                fn from_field_value(value: FieldValue) -> Option<Self> {
+                    match value {
+                        FieldValue::Float(value) => Some(value as $float),
+                        FieldValue::Int(value) => Some(value as $float),
+                        FieldValue::UInt(value) => Some(value as $float),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_reflect_field_for_float!(f32, f64);
+
+macro_rules! impl_reflect_field {
+    ($($variant:ident: $type:ty),*) => {
+        $(
+            impl ReflectField for $type {
+                fn to_field_value(&self) -> FieldValue {
+                    FieldValue::$variant(self.clone())
+                }
+
+                fn from_field_value(value: FieldValue) -> Option<Self> {
+                    match value {
+                        FieldValue::$variant(value) => Some(value),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_reflect_field!(
+    Bool: bool,
+    Char: char,
+    String: String,
+    IntCoords2d: IntCoords2d,
+    IntCoords3d: IntCoords3d,
+    Coords2d: Coords2d,
+    Coords3d: Coords3d,
+    Dimensions2d: Dimensions2d,
+    Layer: Layer,
+    Rgb: Rgb
+);
+
+/// `Option`s can be written with either a `FieldValue::Option` or a value of the type they hold.
+impl<T: ReflectField> ReflectField for Option<T> {
+    fn to_field_value(&self) -> FieldValue {
+        FieldValue::Option(self.as_ref().map(|value| Box::new(value.to_field_value())))
+    }
+
+    fn from_field_value(value: FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::Option(None) => Some(None),
+            FieldValue::Option(Some(value)) => T::from_field_value(*value).map(Some),
+            value => T::from_field_value(value).map(Some),
+        }
+    }
+}
+
+// `#[derive(Component)]` reads and writes fields through these. A field whose type implements `ReflectField` is picked
+// up by `ReflectedFieldProbe`, which takes precedence because it doesn't need the extra reference that
+// `OpaqueFieldProbe` does. Any other field falls through to `OpaqueFieldProbe`, which can't read or write it.
+
+#[doc(hidden)]
+pub struct FieldProbe<'a, T>(pub &'a T);
+
+#[doc(hidden)]
+pub trait ReflectedFieldProbe<T> {
+    fn field_value(&self) -> Option<FieldValue>;
+    fn field_from_value(&self, value: FieldValue) -> Option<T>;
+}
+impl<T: ReflectField> ReflectedFieldProbe<T> for FieldProbe<'_, T> {
+    fn field_value(&self) -> Option<FieldValue> {
+        Some(self.0.to_field_value())
+    }
+
+    fn field_from_value(&self, value: FieldValue) -> Option<T> {
+        T::from_field_value(value)
+    }
+}
+
+#[doc(hidden)]
+pub trait OpaqueFieldProbe<T> {
+    fn field_value(&self) -> Option<FieldValue>;
+    fn field_from_value(&self, value: FieldValue) -> Option<T>;
+}
+impl<T> OpaqueFieldProbe<T> for &FieldProbe<'_, T> {
+    fn field_value(&self) -> Option<FieldValue> {
+        None
+    }
+
+    fn field_from_value(&self, _value: FieldValue) -> Option<T> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Component;
+
+    #[derive(Component)]
+    struct Stats {
+        health: u32,
+        speed: f32,
+        name: String,
+        color: Option<Rgb>,
+        inventory: Vec<String>,
+    }
+
+    #[derive(Component)]
+    struct Position(IntCoords2d, Layer);
+
+    #[derive(Component)]
+    struct Wrapper<T> {
+        value: T,
+    }
+
+    #[derive(Component)]
+    enum Faction {
+        Friendly,
+    }
+
+    fn make_stats() -> Stats {
+        Stats {
+            health: 10,
+            speed: 1.5,
+            name: String::from("Thomas"),
+            color: None,
+            inventory: vec![],
+        }
+    }
+
+    mod test_fields {
+        use super::*;
+
+        #[test]
+        fn lists_every_field_in_order() {
+            let fields: Vec<(&str, &str)> = make_stats()
+                .fields()
+                .iter()
+                .map(|field| (field.name(), field.type_name()))
+                .collect();
+
+            assert_eq!(
+                fields,
+                vec![
+                    ("health", "u32"),
+                    ("speed", "f32"),
+                    ("name", "String"),
+                    ("color", "Option<Rgb>"),
+                    ("inventory", "Vec<String>"),
+                ]
+            );
+        }
+
+        #[test]
+        fn names_tuple_struct_fields_by_position() {
+            let position = Position(IntCoords2d::zero(), Layer::base());
+
+            assert_eq!(
+                position
+                    .fields()
+                    .iter()
+                    .map(|field| field.name())
+                    .collect::<Vec<&str>>(),
+                vec!["0", "1"]
+            );
+        }
+
+        #[test]
+        fn is_empty_for_enums() {
+            assert!(Faction::Friendly.fields().is_empty());
+        }
+    }
+
+    mod test_field {
+        use super::*;
+
+        #[test]
+        fn reads_supported_fields() {
+            let stats = make_stats();
+
+            assert_eq!(stats.field("health"), Some(FieldValue::UInt(10)));
+            assert_eq!(stats.field("speed"), Some(FieldValue::Float(1.5)));
+            assert_eq!(
+                stats.field("name"),
+                Some(FieldValue::String(String::from("Thomas")))
+            );
+            assert_eq!(stats.field("color"), Some(FieldValue::Option(None)));
+            assert_eq!(
+                Position(IntCoords2d::new(1, 2), Layer::base()).field("0"),
+                Some(FieldValue::IntCoords2d(IntCoords2d::new(1, 2)))
+            );
+        }
+
+        #[test]
+        fn is_none_for_unsupported_and_unknown_fields() {
+            let stats = make_stats();
+
+            assert_eq!(stats.field("inventory"), None);
+            assert_eq!(stats.field("mana"), None);
+        }
+
+        #[test]
+        fn is_none_for_fields_of_a_generic_type() {
+            assert_eq!(Wrapper { value: 5 }.field("value"), None);
+        }
+    }
+
+    mod test_display {
+        use super::*;
+
+        #[test]
+        fn writes_values_the_way_they_would_be_written_in_code() {
+            assert_eq!(FieldValue::Char('g').to_string(), "'g'");
+            assert_eq!(
+                FieldValue::IntCoords2d(IntCoords2d::new(-1, 2)).to_string(),
+                "(-1, 2)"
+            );
+            assert_eq!(
+                FieldValue::Option(Some(Box::new(FieldValue::Rgb(Rgb(1, 2, 3))))).to_string(),
+                "Some(Rgb(1, 2, 3))"
+            );
+        }
+    }
+
+    mod test_set_field {
+        use super::*;
+
+        #[test]
+        fn writes_supported_fields() {
+            let mut stats = make_stats();
+
+            assert!(stats.set_field("health", FieldValue::Int(20)));
+            assert!(stats.set_field("speed", FieldValue::Int(3)));
+            assert!(stats.set_field("color", FieldValue::Rgb(Rgb(1, 2, 3))));
+
+            assert_eq!(stats.health, 20);
+            assert_eq!(stats.speed, 3.0);
+            assert_eq!(stats.color, Some(Rgb(1, 2, 3)));
+
+            assert!(stats.set_field("color", FieldValue::Option(None)));
+
+            assert_eq!(stats.color, None);
+        }
+
+        #[test]
+        fn works_through_dyn_component() {
+            let mut component: Box<dyn Component> = Box::new(make_stats());
+
+            assert!(component.set_field("name", FieldValue::String(String::from("Bob"))));
+
+            assert_eq!(Stats::cast(component.as_ref()).unwrap().name, "Bob");
+        }
+
+        #[test]
+        fn fails_for_values_of_the_wrong_type() {
+            let mut stats = make_stats();
+
+            assert!(!stats.set_field("health", FieldValue::String(String::from("lots"))));
+            assert!(!stats.set_field("health", FieldValue::Int(-1)));
+            assert_eq!(stats.health, 10);
+        }
+
+        #[test]
+        fn fails_for_unsupported_and_unknown_fields() {
+            let mut stats = make_stats();
+
+            assert!(!stats.set_field("inventory", FieldValue::String(String::from("sword"))));
+            assert!(!stats.set_field("mana", FieldValue::UInt(5)));
+        }
+    }
+}
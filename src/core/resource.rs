@@ -0,0 +1,176 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+};
+
+use crate::{ParallelSafe, Ref, RefMut, SharedCell};
+
+/// A `Resource` is global data that isn't tied to any particular entity. There's at most one of each type of
+/// `Resource` in the game at a time, and `System`s can get at resources directly through the `World` without having
+/// to query for them. Like `Component`s, you should derive `Resource` rather than implementing it yourself:
+/// ```
+/// use thomas::Resource;
+///
+/// #[derive(Resource)]
+/// pub struct Score {
+///     pub value: u64,
+/// }
+/// ```
+/// Thomas keeps some of its own services as resources, like `Input` and `Time`. They're in the world before any of
+/// your `System`s run, so they're always available.
+pub trait Resource: ParallelSafe {
+    /// A human-readable name for the resource, meant for diagnostics. Names aren't guaranteed to be unique; use `id`
+    /// to tell resources apart.
+    fn name() -> &'static str
+    where
+        Self: Sized;
+    /// The unique identity of the resource type.
+    fn id() -> TypeId
+    where
+        Self: Sized;
+
+    fn resource_name(&self) -> &'static str;
+    fn resource_id(&self) -> TypeId;
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+/// Holds the game's resources, keyed by their type.
+pub(crate) struct ResourceStore {
+    resources: HashMap<TypeId, SharedCell<Box<dyn Resource>>>,
+}
+impl ResourceStore {
+    pub fn new() -> Self {
+        Self {
+            resources: HashMap::new(),
+        }
+    }
+
+    /// Adds the resource to the store, replacing any existing resource of the same type.
+    pub fn insert(&mut self, resource: Box<dyn Resource>) {
+        self.resources
+            .insert(resource.resource_id(), SharedCell::new(resource));
+    }
+
+    /// Removes the resource from the store. Returns whether there was a resource to remove.
+    pub fn remove(&mut self, resource_id: &TypeId) -> bool {
+        self.resources.remove(resource_id).is_some()
+    }
+
+    pub fn contains(&self, resource_id: &TypeId) -> bool {
+        self.resources.contains_key(resource_id)
+    }
+
+    /// Attempts to borrow the resource. If it isn't in the store, or it's already mutably borrowed, returns `None`.
+    pub fn try_get<T>(&self) -> Option<Ref<'_, T>>
+    where
+        T: Resource + 'static,
+    {
+        let resource = self.resources.get(&T::id())?.try_borrow().ok()?;
+
+        Some(Ref::map(resource, |resource| {
+            resource.as_any().downcast_ref::<T>().unwrap()
+        }))
+    }
+
+    /// Like `try_get`, but mutably borrows the resource.
+    pub fn try_get_mut<T>(&self) -> Option<RefMut<'_, T>>
+    where
+        T: Resource + 'static,
+    {
+        let resource = self.resources.get(&T::id())?.try_borrow_mut().ok()?;
+
+        Some(RefMut::map(resource, |resource| {
+            resource.as_any_mut().downcast_mut::<T>().unwrap()
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Resource;
+
+    #[derive(Resource)]
+    struct Score {
+        value: u64,
+    }
+
+    #[derive(Resource)]
+    struct Settings {}
+
+    mod test_insert {
+        use super::*;
+
+        #[test]
+        fn replaces_an_existing_resource_of_the_same_type() {
+            let mut store = ResourceStore::new();
+
+            store.insert(Box::new(Score { value: 1 }));
+            store.insert(Box::new(Score { value: 2 }));
+
+            assert_eq!(store.resources.len(), 1);
+            assert_eq!(store.try_get::<Score>().unwrap().value, 2);
+        }
+    }
+
+    mod test_remove {
+        use super::*;
+
+        #[test]
+        fn removes_only_the_specified_resource() {
+            let mut store = ResourceStore::new();
+
+            store.insert(Box::new(Score { value: 1 }));
+            store.insert(Box::new(Settings {}));
+
+            assert!(store.remove(&Score::id()));
+            assert!(!store.contains(&Score::id()));
+            assert!(store.contains(&Settings::id()));
+        }
+
+        #[test]
+        fn is_false_when_the_resource_is_not_present() {
+            let mut store = ResourceStore::new();
+
+            assert!(!store.remove(&Score::id()));
+        }
+    }
+
+    mod test_try_get {
+        use super::*;
+
+        #[test]
+        fn is_none_when_the_resource_is_not_present() {
+            let store = ResourceStore::new();
+
+            assert!(store.try_get::<Score>().is_none());
+        }
+
+        #[test]
+        fn is_none_when_the_resource_is_mutably_borrowed() {
+            let mut store = ResourceStore::new();
+
+            store.insert(Box::new(Score { value: 1 }));
+
+            let _score = store.try_get_mut::<Score>();
+
+            assert!(store.try_get::<Score>().is_none());
+        }
+    }
+
+    mod test_try_get_mut {
+        use super::*;
+
+        #[test]
+        fn changes_are_kept_in_the_store() {
+            let mut store = ResourceStore::new();
+
+            store.insert(Box::new(Score { value: 1 }));
+
+            store.try_get_mut::<Score>().unwrap().value += 5;
+
+            assert_eq!(store.try_get::<Score>().unwrap().value, 6);
+        }
+    }
+}
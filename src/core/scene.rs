@@ -0,0 +1,532 @@
+use std::collections::HashSet;
+
+use crate::{Component, Entity, Query, Resource, World};
+
+/// Marks an entity as belonging to a scene. When the scene is unloaded with `GameCommand::UnloadScene`, the entity is
+/// destroyed, along with all of its descendants.
+///
+/// Entities loaded from a `Scene` are given this for you. Add it yourself to entities you spawn while the game is
+/// running to have them unloaded with a scene, like projectiles fired during a level.
+#[derive(Component)]
+pub struct InScene {
+    pub scene: String,
+}
+
+/// The scenes that are currently loaded. Thomas adds this resource for you.
+#[derive(Resource)]
+pub struct LoadedScenes {
+    scenes: HashSet<String>,
+}
+impl LoadedScenes {
+    pub(crate) fn new() -> Self {
+        Self {
+            scenes: HashSet::new(),
+        }
+    }
+
+    pub fn is_loaded(&self, scene: &str) -> bool {
+        self.scenes.contains(scene)
+    }
+
+    pub fn scenes(&self) -> impl Iterator<Item = &str> {
+        self.scenes.iter().map(|scene| scene.as_str())
+    }
+}
+
+type SpawnComponentsFn = Box<dyn Fn() -> Vec<Box<dyn Component>>>;
+
+enum SceneEntity {
+    Components(SpawnComponentsFn),
+    #[cfg(feature = "serde")]
+    Prefab(String, SpawnComponentsFn),
+    #[cfg(feature = "serde")]
+    Data(data::SceneEntityData),
+}
+
+/// A named group of entities that can be loaded into the world and unloaded from it all at once, like a level, a
+/// menu, or a HUD. Add scenes to the game with `Game::add_scene`, then load them with `GameCommand::LoadScene` and
+/// unload them with `GameCommand::UnloadScene`. Any number of scenes can be loaded at the same time.
+///
+/// Every entity a scene loads is given an `InScene` component, and unloading the scene destroys every entity that has
+/// it. Nothing else in the world is touched, so entities Thomas adds for you, like the default camera, are safe.
+///
+/// Scenes can be built in code:
+/// ```
+/// use thomas::{IntCoords2d, Scene, TerminalRenderer, TerminalTransform, Layer};
+///
+/// Scene::new("level-1").with_entity(|| {
+///     vec![
+///         Box::new(TerminalTransform {
+///             coords: IntCoords2d::new(2, 3),
+///         }),
+///         Box::new(TerminalRenderer {
+///             display: '#',
+///             layer: Layer::base(),
+///             foreground_color: None,
+///             background_color: None,
+///         }),
+///     ]
+/// });
+/// ```
+/// With the `serde` feature, they can also be read from RON or TOML files. See `Scene::from_ron`.
+pub struct Scene {
+    name: String,
+    entities: Vec<SceneEntity>,
+}
+impl Scene {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            entities: vec![],
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Adds an entity to the scene. The function makes the entity's components, and is called every time the scene is
+    /// loaded.
+    pub fn with_entity(
+        mut self,
+        make_components: impl Fn() -> Vec<Box<dyn Component>> + 'static,
+    ) -> Self {
+        self.entities
+            .push(SceneEntity::Components(Box::new(make_components)));
+
+        self
+    }
+
+    /// Adds an entity made from the named prefab to the scene. The function makes components that replace the
+    /// prefab's, like they do with `GameCommand::SpawnPrefab`, and is called every time the scene is loaded.
+    #[cfg(feature = "serde")]
+    pub fn with_prefab(
+        mut self,
+        prefab: &str,
+        make_overrides: impl Fn() -> Vec<Box<dyn Component>> + 'static,
+    ) -> Self {
+        self.entities.push(SceneEntity::Prefab(
+            prefab.to_string(),
+            Box::new(make_overrides),
+        ));
+
+        self
+    }
+
+    /// Adds the scene's entities to the world, giving each of them an `InScene` for this scene.
+    pub(crate) fn spawn_into(&self, world: &mut World) -> Vec<Entity> {
+        let mut entities = vec![];
+
+        for scene_entity in &self.entities {
+            let entity = match scene_entity {
+                SceneEntity::Components(make_components) => {
+                    let mut components = make_components();
+
+                    components.push(self.in_scene());
+
+                    Some(world.entity_manager_mut().add_entity(components))
+                }
+                #[cfg(feature = "serde")]
+                SceneEntity::Prefab(prefab, make_overrides) => {
+                    let mut overrides = make_overrides();
+
+                    overrides.push(self.in_scene());
+
+                    world.spawn_prefab(prefab, overrides)
+                }
+                #[cfg(feature = "serde")]
+                SceneEntity::Data(entity_data) => match entity_data.make_components(world) {
+                    Ok(mut components) => {
+                        components.push(self.in_scene());
+
+                        entity_data.spawn(world, components)
+                    }
+                    Err(_) => None,
+                },
+            };
+
+            entities.extend(entity);
+        }
+
+        world
+            .resource_mut::<LoadedScenes>()
+            .scenes
+            .insert(self.name.clone());
+
+        entities
+    }
+
+    fn in_scene(&self) -> Box<dyn Component> {
+        Box::new(InScene {
+            scene: self.name.clone(),
+        })
+    }
+}
+
+impl World {
+    /// Destroys every entity in the scene, along with their descendants.
+    pub(crate) fn unload_scene(&mut self, scene: &str) {
+        let scene = scene.to_string();
+        let scene_entities: Vec<Entity> = self
+            .entity_manager()
+            .query(&Query::new().has_where::<InScene>({
+                let scene = scene.clone();
+                move |in_scene| in_scene.scene == scene
+            }))
+            .iter()
+            .map(|result| *result.entity())
+            .collect();
+
+        for entity in scene_entities {
+            self.destroy_entity_and_descendants(&entity);
+        }
+
+        self.resource_mut::<LoadedScenes>().scenes.remove(&scene);
+    }
+}
+
+#[cfg(feature = "serde")]
+mod data {
+    use std::{collections::BTreeMap, path::Path};
+
+    use serde::Deserialize;
+
+    use super::{Scene, SceneEntity};
+    use crate::{
+        from_ron_data, load_data_file, Component, Entity, SnapshotError, SnapshotResult,
+        SnapshotValue, World,
+    };
+
+    #[derive(Deserialize)]
+    struct SceneData {
+        entities: Vec<SceneEntityData>,
+    }
+
+    /// One entity in a scene read from a file: an optional prefab, and components keyed by the name each component was
+    /// registered with. When there's a prefab, the components replace the prefab's.
+    #[derive(Deserialize)]
+    pub(super) struct SceneEntityData {
+        #[serde(default)]
+        prefab: Option<String>,
+        #[serde(default)]
+        components: BTreeMap<String, SnapshotValue>,
+    }
+    impl SceneEntityData {
+        pub fn make_components(&self, world: &World) -> SnapshotResult<Vec<Box<dyn Component>>> {
+            self.components
+                .iter()
+                .map(|(name, value)| world.component_registry().deserialize(name, value.clone()))
+                .collect()
+        }
+
+        pub fn spawn(
+            &self,
+            world: &mut World,
+            components: Vec<Box<dyn Component>>,
+        ) -> Option<Entity> {
+            match &self.prefab {
+                Some(prefab) => world.spawn_prefab(prefab, components),
+                None => Some(world.entity_manager_mut().add_entity(components)),
+            }
+        }
+    }
+
+    impl Scene {
+        /// Reads a scene from RON. The scene is a list of entities, each of which can be made from a prefab, have its
+        /// own components, or both. When an entity has a prefab, its components replace the prefab's:
+        /// ```
+        /// use thomas::Scene;
+        ///
+        /// let scene = Scene::from_ron("level-1", r#"(
+        ///     entities: [
+        ///         (
+        ///             prefab: "goblin",
+        ///             components: {
+        ///                 "TerminalTransform": (coords: (x: 4, y: 2)),
+        ///             },
+        ///         ),
+        ///         (
+        ///             components: {
+        ///                 "TerminalTransform": (coords: (x: 0, y: 0)),
+        ///                 "TerminalRenderer": (display: '|', layer: 0),
+        ///             },
+        ///         ),
+        ///     ],
+        /// )"#).unwrap();
+        ///
+        /// assert_eq!(scene.name(), "level-1");
+        /// ```
+        /// Components are named the same way they are in `Prefabs`.
+        pub fn from_ron(name: &str, ron: &str) -> SnapshotResult<Self> {
+            Ok(Self::from_data(name, from_ron_data(ron)?))
+        }
+
+        /// Reads a scene from TOML, with an `[[entities]]` table for each entity. See `from_ron` for what an entity
+        /// can have.
+        pub fn from_toml(name: &str, toml: &str) -> SnapshotResult<Self> {
+            Ok(Self::from_data(name, toml::from_str(toml)?))
+        }
+
+        /// Reads a scene from the file at `path`. The file is read as RON or TOML depending on whether its extension is
+        /// `.ron` or `.toml`.
+        pub fn load(name: &str, path: impl AsRef<Path>) -> SnapshotResult<Self> {
+            Ok(Self::from_data(name, load_data_file(path.as_ref())?))
+        }
+
+        /// Checks that every entity read from a file has registered components that can be made from its data, and
+        /// that any prefab it's made from exists.
+        pub(crate) fn validate(&self, world: &World) -> SnapshotResult<()> {
+            for scene_entity in &self.entities {
+                if let SceneEntity::Data(entity_data) = scene_entity {
+                    if let Err(error) = entity_data.make_components(world) {
+                        return Err(SnapshotError::new(format!(
+                            "Scene {} is invalid: {error}",
+                            self.name
+                        )));
+                    }
+
+                    if let Some(prefab) = &entity_data.prefab {
+                        if !world.has_prefab(prefab) {
+                            return Err(SnapshotError::new(format!(
+                                "Scene {} uses prefab {prefab}, which doesn't exist.",
+                                self.name
+                            )));
+                        }
+                    }
+                }
+            }
+
+            Ok(())
+        }
+
+        fn from_data(name: &str, data: SceneData) -> Self {
+            Self {
+                name: name.to_string(),
+                entities: data.entities.into_iter().map(SceneEntity::Data).collect(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Dimensions2d, Game, GameCommand, GameOptions, Renderer, TerminalCamera,
+        TerminalRendererOptions,
+    };
+
+    #[derive(Component)]
+    struct Wall {}
+
+    fn make_world() -> World {
+        let mut world = World::new();
+
+        world.insert_resource(Box::new(LoadedScenes::new()));
+
+        world
+    }
+
+    fn make_scene(name: &str, walls: usize) -> Scene {
+        let mut scene = Scene::new(name);
+
+        for _ in 0..walls {
+            scene = scene.with_entity(|| vec![Box::new(Wall {})]);
+        }
+
+        scene
+    }
+
+    fn walls_in(world: &World, scene: &str) -> usize {
+        let scene = scene.to_string();
+
+        world
+            .entity_manager()
+            .query(
+                &Query::new()
+                    .has::<Wall>()
+                    .has_where::<InScene>(move |in_scene| in_scene.scene == scene),
+            )
+            .len()
+    }
+
+    mod test_spawn_into {
+        use super::*;
+
+        #[test]
+        fn tags_every_entity_with_the_scene() {
+            let mut world = make_world();
+
+            let entities = make_scene("level", 3).spawn_into(&mut world);
+
+            assert_eq!(entities.len(), 3);
+            assert_eq!(walls_in(&world, "level"), 3);
+            assert!(world.resource::<LoadedScenes>().is_loaded("level"));
+        }
+    }
+
+    mod test_unload_scene {
+        use super::*;
+
+        #[test]
+        fn only_destroys_entities_in_the_scene() {
+            let mut world = make_world();
+
+            make_scene("level", 2).spawn_into(&mut world);
+            make_scene("hud", 1).spawn_into(&mut world);
+            world
+                .entity_manager_mut()
+                .add_entity(vec![Box::new(Wall {})]);
+
+            world.unload_scene("level");
+
+            assert_eq!(walls_in(&world, "level"), 0);
+            assert_eq!(walls_in(&world, "hud"), 1);
+            assert_eq!(
+                world
+                    .entity_manager()
+                    .query(&Query::new().has::<Wall>())
+                    .len(),
+                2
+            );
+            assert!(!world.resource::<LoadedScenes>().is_loaded("level"));
+            assert!(world.resource::<LoadedScenes>().is_loaded("hud"));
+        }
+
+        #[test]
+        fn destroys_the_descendants_of_entities_in_the_scene() {
+            let mut world = make_world();
+
+            let entities = make_scene("level", 1).spawn_into(&mut world);
+            world.add_child_entity(&entities[0], vec![Box::new(Wall {})]);
+
+            world.unload_scene("level");
+
+            assert!(world
+                .entity_manager()
+                .query(&Query::new().has::<Wall>())
+                .is_empty());
+        }
+
+        #[test]
+        fn leaves_the_default_camera_alone() {
+            let mut game = Game::new(GameOptions {
+                press_escape_to_quit: false,
+                max_frame_rate: 60,
+            })
+            .add_scene(make_scene("level", 2))
+            .start_stepped(Renderer::Headless(TerminalRendererOptions {
+                screen_resolution: Dimensions2d::new(1, 1),
+                include_default_camera: true,
+                default_foreground_color: None,
+                default_background_color: None,
+            }));
+
+            game.issue(GameCommand::LoadScene(String::from("level")));
+
+            assert_eq!(game.query(&Query::new().has::<Wall>()).len(), 2);
+
+            game.issue(GameCommand::UnloadScene(String::from("level")));
+
+            assert!(game.query(&Query::new().has::<Wall>()).is_empty());
+            assert_eq!(game.query(&Query::new().has::<TerminalCamera>()).len(), 1);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    mod test_from_data {
+        use super::*;
+        use crate::{IntCoords2d, Layer, Prefabs, TerminalRenderer, TerminalTransform};
+
+        const LEVEL_RON: &str = r#"(
+            entities: [
+                (
+                    prefab: "goblin",
+                    components: {
+                        "TerminalTransform": (coords: (x: 4, y: 2)),
+                    },
+                ),
+                (
+                    components: {
+                        "TerminalTransform": (coords: (x: 1, y: 1)),
+                        "TerminalRenderer": (display: '|', layer: 0),
+                    },
+                ),
+            ],
+        )"#;
+
+        const LEVEL_TOML: &str = r#"
+            [[entities]]
+            prefab = "goblin"
+            components.TerminalTransform = { coords = { x = 4, y = 2 } }
+
+            [[entities]]
+            components.TerminalTransform = { coords = { x = 1, y = 1 } }
+            components.TerminalRenderer = { display = "|", layer = 0 }
+        "#;
+
+        fn world_with_goblins() -> World {
+            let mut world = make_world();
+
+            world.add_prefabs(
+                Prefabs::from_ron(
+                    r#"{ "goblin": { "TerminalRenderer": (display: 'g', layer: 1) } }"#,
+                )
+                .unwrap(),
+            );
+
+            world
+        }
+
+        #[test]
+        fn makes_the_same_entities_from_ron_and_toml() {
+            for scene in [
+                Scene::from_ron("level", LEVEL_RON).unwrap(),
+                Scene::from_toml("level", LEVEL_TOML).unwrap(),
+            ] {
+                let mut world = world_with_goblins();
+
+                assert!(scene.validate(&world).is_ok());
+
+                let entities = scene.spawn_into(&mut world);
+
+                assert_eq!(entities.len(), 2);
+
+                let goblin_renderer = world
+                    .entity_manager()
+                    .get_component::<TerminalRenderer>(&entities[0])
+                    .unwrap();
+
+                assert_eq!(goblin_renderer.display, 'g');
+                assert_eq!(goblin_renderer.layer, Layer(1));
+                assert_eq!(
+                    world
+                        .entity_manager()
+                        .get_component::<TerminalTransform>(&entities[0])
+                        .unwrap()
+                        .coords,
+                    IntCoords2d::new(4, 2)
+                );
+                assert_eq!(
+                    world
+                        .entity_manager()
+                        .get_component::<TerminalRenderer>(&entities[1])
+                        .unwrap()
+                        .display,
+                    '|'
+                );
+                assert!(world
+                    .entity_manager()
+                    .get_component::<InScene>(&entities[1])
+                    .is_some());
+            }
+        }
+
+        #[test]
+        fn is_invalid_when_a_prefab_does_not_exist() {
+            let scene = Scene::from_ron("level", LEVEL_RON).unwrap();
+
+            assert!(scene.validate(&make_world()).is_err());
+        }
+    }
+}
@@ -0,0 +1,30 @@
+// The pointer and cell types that anything `System`s share is kept in. With the `parallel` feature, they're the
+// thread-safe versions so systems can run on more than one thread at once. Otherwise, they're the cheaper
+// single-threaded ones. Both sets have the same methods, so code written against these names works either way.
+
+#[cfg(not(feature = "parallel"))]
+pub use std::{
+    cell::{Ref, RefCell as SharedCell, RefMut},
+    rc::Rc as Shared,
+};
+
+#[cfg(feature = "parallel")]
+pub use atomic_refcell::{AtomicRef as Ref, AtomicRefCell as SharedCell, AtomicRefMut as RefMut};
+#[cfg(feature = "parallel")]
+pub use std::sync::Arc as Shared;
+
+/// Anything the game shares between `System`s has to be `ParallelSafe`, which includes every `Component` and
+/// `Resource`. With the `parallel` feature, that means being `Send` and `Sync` so it can be used from more than one
+/// thread. Without the feature, everything is `ParallelSafe`.
+#[cfg(feature = "parallel")]
+pub trait ParallelSafe: Send + Sync {}
+#[cfg(feature = "parallel")]
+impl<T: Send + Sync + ?Sized> ParallelSafe for T {}
+
+/// Anything the game shares between `System`s has to be `ParallelSafe`, which includes every `Component` and
+/// `Resource`. With the `parallel` feature, that means being `Send` and `Sync` so it can be used from more than one
+/// thread. Without the feature, everything is `ParallelSafe`.
+#[cfg(not(feature = "parallel"))]
+pub trait ParallelSafe {}
+#[cfg(not(feature = "parallel"))]
+impl<T: ?Sized> ParallelSafe for T {}
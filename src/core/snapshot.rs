@@ -0,0 +1,542 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error,
+    fmt, fs,
+    path::Path,
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{Entity, Parent, Query, World};
+
+/// A serialized component, in a form that can be written out as JSON or RON.
+pub type SnapshotValue = serde_json::Value;
+
+pub type SnapshotResult<T> = Result<T, SnapshotError>;
+
+/// Something went wrong writing or reading a snapshot or prefab.
+#[derive(Debug)]
+pub struct SnapshotError {
+    message: String,
+}
+impl SnapshotError {
+    pub(crate) fn new(message: String) -> Self {
+        Self { message }
+    }
+}
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+impl Error for SnapshotError {}
+impl From<serde_json::Error> for SnapshotError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::new(error.to_string())
+    }
+}
+impl From<ron::Error> for SnapshotError {
+    fn from(error: ron::Error) -> Self {
+        Self::new(error.to_string())
+    }
+}
+impl From<ron::error::SpannedError> for SnapshotError {
+    fn from(error: ron::error::SpannedError) -> Self {
+        Self::new(error.to_string())
+    }
+}
+impl From<toml::de::Error> for SnapshotError {
+    fn from(error: toml::de::Error) -> Self {
+        Self::new(error.to_string())
+    }
+}
+impl From<std::io::Error> for SnapshotError {
+    fn from(error: std::io::Error) -> Self {
+        Self::new(error.to_string())
+    }
+}
+
+/// Serializes a component into a `SnapshotValue`. `#[derive(Component)]` uses this for components marked with
+/// `#[component(serialize)]`; you shouldn't need to call it yourself.
+pub fn to_snapshot_value<T: Serialize>(component: &T) -> SnapshotResult<SnapshotValue> {
+    Ok(serde_json::to_value(component)?)
+}
+
+/// Reads data written by hand in RON, like prefabs and scenes.
+pub(crate) fn from_ron_data<T: DeserializeOwned>(ron: &str) -> SnapshotResult<T> {
+    // RON can't deserialize structs with more than one field straight into a `SnapshotValue`, so it's read as RON's
+    // own value type first.
+    let value = serde_json::to_value(ron::from_str::<ron::Value>(ron)?)?;
+
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Reads data from the file at `path` as RON or TOML, depending on whether its extension is `.ron` or `.toml`.
+pub(crate) fn load_data_file<T: DeserializeOwned>(path: &Path) -> SnapshotResult<T> {
+    let contents = fs::read_to_string(path)?;
+
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("ron") => from_ron_data(&contents),
+        Some("toml") => Ok(toml::from_str(&contents)?),
+        _ => Err(SnapshotError::new(format!(
+            "{} must be a .ron or .toml file.",
+            path.display()
+        ))),
+    }
+}
+
+/// The serialized components of one entity in a `WorldSnapshot`, keyed by the name each component was registered with.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EntitySnapshot {
+    /// The index in the snapshot of the entity's parent, if its parent is also in the snapshot.
+    pub(crate) parent: Option<usize>,
+    pub(crate) components: BTreeMap<String, SnapshotValue>,
+}
+impl EntitySnapshot {
+    pub fn parent(&self) -> Option<usize> {
+        self.parent
+    }
+
+    pub fn components(&self) -> &BTreeMap<String, SnapshotValue> {
+        &self.components
+    }
+}
+
+/// A copy of entities in the world, made with `World::snapshot` or `World::snapshot_matching`, that can be saved as
+/// JSON or RON and later put back into the world with `GameCommand::RestoreSnapshot`. This is how save games and
+/// checkpoints are made.
+///
+/// Only components that are both marked with `#[component(serialize)]` and registered with
+/// `Game::register_component` are included. Entities without any such components are left out entirely. Parent/child
+/// relationships between entities in the snapshot are kept, but any `Entity` stored inside a component will point at
+/// the original entity rather than the restored one.
+///
+/// Snapshots need Thomas's `serde` feature. Serializable components look like this:
+/// ```
+/// use serde::{Deserialize, Serialize};
+/// use thomas::Component;
+///
+/// #[derive(Component, Serialize, Deserialize)]
+/// #[component(serialize)]
+/// pub struct Inventory {
+///     pub gold: u32,
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WorldSnapshot {
+    pub(crate) entities: Vec<EntitySnapshot>,
+}
+impl WorldSnapshot {
+    pub fn entities(&self) -> &Vec<EntitySnapshot> {
+        &self.entities
+    }
+
+    pub fn to_json(&self) -> SnapshotResult<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &str) -> SnapshotResult<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_ron(&self) -> SnapshotResult<String> {
+        Ok(ron::ser::to_string_pretty(
+            self,
+            ron::ser::PrettyConfig::default(),
+        )?)
+    }
+
+    pub fn from_ron(ron: &str) -> SnapshotResult<Self> {
+        Ok(ron::from_str(ron)?)
+    }
+}
+
+impl World {
+    /// Makes a snapshot of every entity in the world. See `WorldSnapshot` for what's included.
+    pub fn snapshot(&self) -> SnapshotResult<WorldSnapshot> {
+        self.snapshot_entities(self.entity_manager().entities())
+    }
+
+    /// Makes a snapshot of the entities that match the query. Every serializable component of a matching entity is
+    /// included, not just the ones the query asks for.
+    pub fn snapshot_matching(&self, query: &Query) -> SnapshotResult<WorldSnapshot> {
+        let mut entities: Vec<Entity> = self
+            .entity_manager()
+            .query(query)
+            .iter()
+            .map(|query_result| *query_result.entity())
+            .collect();
+
+        entities.sort();
+
+        self.snapshot_entities(entities)
+    }
+
+    /// Adds the entities in the snapshot to the world, returning the new entities in the order they're in the
+    /// snapshot. Nothing is added if any component can't be read back, or if an entity's parent isn't in the snapshot.
+    pub(crate) fn restore_snapshot(
+        &mut self,
+        snapshot: &WorldSnapshot,
+    ) -> SnapshotResult<Vec<Entity>> {
+        let mut entities_components = vec![];
+
+        for (index, entity_snapshot) in snapshot.entities.iter().enumerate() {
+            if let Some(parent) = entity_snapshot.parent {
+                if snapshot.entities.get(parent).is_none() {
+                    return Err(SnapshotError::new(format!(
+                        "Entity {index} in the snapshot has parent {parent}, but the snapshot only has {} entities.",
+                        snapshot.entities.len()
+                    )));
+                }
+            }
+
+            let components = entity_snapshot
+                .components
+                .iter()
+                .map(|(name, value)| self.component_registry().deserialize(name, value.clone()))
+                .collect::<SnapshotResult<Vec<_>>>()?;
+
+            entities_components.push(components);
+        }
+
+        let entities: Vec<Entity> = entities_components
+            .into_iter()
+            .map(|components| self.entity_manager_mut().add_entity(components))
+            .collect();
+
+        for (entity_snapshot, entity) in snapshot.entities.iter().zip(&entities) {
+            if let Some(parent) = entity_snapshot.parent {
+                self.set_parent(entity, &entities[parent]);
+            }
+        }
+
+        Ok(entities)
+    }
+
+    fn snapshot_entities(&self, entities: Vec<Entity>) -> SnapshotResult<WorldSnapshot> {
+        let mut entity_snapshots = vec![];
+        let mut snapshot_indices = HashMap::new();
+        let mut parents = vec![];
+
+        for entity in entities {
+            let mut components = BTreeMap::new();
+
+            for component in self.entity_manager().get_all_components(&entity) {
+                let component = component.borrow();
+
+                if let (Some(name), Some(value)) = (
+                    self.component_registry().name_of(&component.component_id()),
+                    component.to_snapshot_value(),
+                ) {
+                    components.insert(name.to_string(), value?);
+                }
+            }
+
+            if !components.is_empty() {
+                snapshot_indices.insert(entity, entity_snapshots.len());
+                parents.push(
+                    self.entity_manager()
+                        .get_component::<Parent>(&entity)
+                        .map(|parent| *parent.entity()),
+                );
+                entity_snapshots.push(EntitySnapshot {
+                    parent: None,
+                    components,
+                });
+            }
+        }
+
+        for (entity_snapshot, parent) in entity_snapshots.iter_mut().zip(parents) {
+            entity_snapshot.parent =
+                parent.and_then(|parent| snapshot_indices.get(&parent).copied());
+        }
+
+        Ok(WorldSnapshot {
+            entities: entity_snapshots,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Component, Dimensions2d, EventReader, Game, GameCommand, GameOptions, Renderer,
+        TerminalRendererOptions,
+    };
+
+    #[derive(Component, Serialize, Deserialize, Debug, PartialEq)]
+    #[component(serialize)]
+    struct Health {
+        value: u32,
+    }
+
+    #[derive(Component, Serialize, Deserialize, Debug, PartialEq)]
+    #[component(serialize)]
+    enum Faction {
+        Friendly,
+        Hostile,
+    }
+
+    #[derive(Component)]
+    struct Marker {}
+
+    fn make_world() -> World {
+        let mut world = World::new();
+
+        world.component_registry_mut().register::<Health>("health");
+        world
+            .component_registry_mut()
+            .register::<Faction>("faction");
+
+        world
+    }
+
+    fn healths(world: &World) -> Vec<u32> {
+        let mut healths: Vec<u32> = world
+            .entity_manager()
+            .query(&Query::new().has::<Health>())
+            .iter()
+            .map(|result| result.components().get::<Health>().value)
+            .collect();
+
+        healths.sort();
+
+        healths
+    }
+
+    mod test_snapshot {
+        use super::*;
+
+        #[test]
+        fn leaves_out_components_that_cannot_be_serialized() {
+            let mut world = make_world();
+
+            world
+                .entity_manager_mut()
+                .add_entity(vec![Box::new(Health { value: 3 }), Box::new(Marker {})]);
+            world
+                .entity_manager_mut()
+                .add_entity(vec![Box::new(Marker {})]);
+
+            let snapshot = world.snapshot().unwrap();
+
+            assert_eq!(snapshot.entities().len(), 1);
+            assert_eq!(
+                snapshot.entities()[0]
+                    .components()
+                    .keys()
+                    .collect::<Vec<_>>(),
+                vec!["health"]
+            );
+        }
+
+        #[test]
+        fn keeps_parents_that_are_in_the_snapshot() {
+            let mut world = make_world();
+
+            let parent = world
+                .entity_manager_mut()
+                .add_entity(vec![Box::new(Health { value: 1 })]);
+            world.add_child_entity(&parent, vec![Box::new(Health { value: 2 })]);
+
+            let snapshot = world.snapshot().unwrap();
+
+            assert_eq!(snapshot.entities()[0].parent(), None);
+            assert_eq!(snapshot.entities()[1].parent(), Some(0));
+        }
+    }
+
+    mod test_snapshot_matching {
+        use super::*;
+
+        #[test]
+        fn only_includes_matching_entities() {
+            let mut world = make_world();
+
+            world.entity_manager_mut().add_entity(vec![
+                Box::new(Health { value: 1 }),
+                Box::new(Faction::Hostile),
+            ]);
+            world
+                .entity_manager_mut()
+                .add_entity(vec![Box::new(Health { value: 2 })]);
+
+            let snapshot = world
+                .snapshot_matching(&Query::new().has::<Faction>())
+                .unwrap();
+
+            assert_eq!(snapshot.entities().len(), 1);
+            assert_eq!(snapshot.entities()[0].components().len(), 2);
+        }
+    }
+
+    mod test_restore_snapshot {
+        use super::*;
+
+        #[test]
+        fn round_trips_through_json_and_ron() {
+            let mut world = make_world();
+
+            world.entity_manager_mut().add_entity(vec![
+                Box::new(Health { value: 4 }),
+                Box::new(Faction::Friendly),
+            ]);
+            world
+                .entity_manager_mut()
+                .add_entity(vec![Box::new(Health { value: 9 })]);
+
+            let snapshot = world.snapshot().unwrap();
+
+            for restored in [
+                WorldSnapshot::from_json(&snapshot.to_json().unwrap()).unwrap(),
+                WorldSnapshot::from_ron(&snapshot.to_ron().unwrap()).unwrap(),
+            ] {
+                let mut new_world = make_world();
+
+                new_world.restore_snapshot(&restored).unwrap();
+
+                assert_eq!(healths(&new_world), vec![4, 9]);
+                assert_eq!(
+                    *new_world
+                        .entity_manager()
+                        .query(&Query::new().has::<Faction>())
+                        .get_only::<Faction>(),
+                    Faction::Friendly
+                );
+            }
+        }
+
+        #[test]
+        fn restores_parents() {
+            let mut world = make_world();
+
+            let parent = world
+                .entity_manager_mut()
+                .add_entity(vec![Box::new(Health { value: 1 })]);
+            world.add_child_entity(&parent, vec![Box::new(Health { value: 2 })]);
+
+            let snapshot = world.snapshot().unwrap();
+            let mut new_world = make_world();
+
+            let entities = new_world.restore_snapshot(&snapshot).unwrap();
+
+            assert_eq!(
+                new_world
+                    .entity_manager()
+                    .get_component::<Parent>(&entities[1])
+                    .map(|parent| *parent.entity()),
+                Some(entities[0])
+            );
+        }
+
+        #[test]
+        fn rejects_parents_outside_the_snapshot() {
+            let mut world = make_world();
+
+            let parent = world
+                .entity_manager_mut()
+                .add_entity(vec![Box::new(Health { value: 1 })]);
+            world.add_child_entity(&parent, vec![Box::new(Health { value: 2 })]);
+
+            let json = world
+                .snapshot()
+                .unwrap()
+                .to_json()
+                .unwrap()
+                .replace("\"parent\": 0", "\"parent\": 7");
+            let snapshot = WorldSnapshot::from_json(&json).unwrap();
+            let mut new_world = make_world();
+
+            let result = new_world.restore_snapshot(&snapshot);
+
+            assert!(result.is_err());
+            assert!(healths(&new_world).is_empty());
+        }
+
+        #[test]
+        fn rejects_components_that_cannot_be_read_back() {
+            let mut world = make_world();
+
+            world
+                .entity_manager_mut()
+                .add_entity(vec![Box::new(Health { value: 1 })]);
+            world.entity_manager_mut().add_entity(vec![
+                Box::new(Health { value: 2 }),
+                Box::new(Faction::Hostile),
+            ]);
+
+            let snapshot = world.snapshot().unwrap();
+            let mut new_world = World::new();
+
+            new_world
+                .component_registry_mut()
+                .register::<Health>("health");
+
+            let result = new_world.restore_snapshot(&snapshot);
+
+            assert!(result.is_err());
+            assert!(healths(&new_world).is_empty());
+        }
+
+        #[test]
+        fn works_through_game_commands() {
+            let mut world = make_world();
+
+            world
+                .entity_manager_mut()
+                .add_entity(vec![Box::new(Health { value: 12 })]);
+
+            let snapshot = world.snapshot().unwrap();
+
+            let mut game = Game::new(GameOptions {
+                press_escape_to_quit: false,
+                max_frame_rate: 60,
+            })
+            .register_component_with_name::<Health>("health")
+            .start_stepped(Renderer::Headless(TerminalRendererOptions {
+                screen_resolution: Dimensions2d::new(1, 1),
+                include_default_camera: true,
+                default_foreground_color: None,
+                default_background_color: None,
+            }));
+
+            game.issue(GameCommand::RestoreSnapshot(snapshot));
+
+            assert_eq!(healths(game.world()), vec![12]);
+        }
+
+        #[test]
+        fn game_commands_send_the_error_as_an_event() {
+            let mut world = make_world();
+
+            world
+                .entity_manager_mut()
+                .add_entity(vec![Box::new(Faction::Hostile)]);
+
+            let snapshot = world.snapshot().unwrap();
+            let error_reader = EventReader::<SnapshotError>::new();
+
+            let mut game = Game::new(GameOptions {
+                press_escape_to_quit: false,
+                max_frame_rate: 60,
+            })
+            .start_stepped(Renderer::Headless(TerminalRendererOptions {
+                screen_resolution: Dimensions2d::new(1, 1),
+                include_default_camera: true,
+                default_foreground_color: None,
+                default_background_color: None,
+            }));
+
+            game.issue(GameCommand::RestoreSnapshot(snapshot));
+
+            let errors = error_reader.read(game.world());
+
+            assert_eq!(errors.len(), 1);
+            assert_eq!(
+                errors[0].to_string(),
+                "No component is registered with the name faction."
+            );
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use std::{
+    any::{type_name, Any},
+    fmt::Debug,
+};
+
+use crate::{Component, ParallelSafe, Resource};
+
+/// A state the game can be in, like being on the title screen, playing, or paused. Any type that's `Clone`,
+/// `PartialEq` and `Debug` can be used as a state, though an enum is usually the most natural fit:
+/// ```
+/// #[derive(Clone, PartialEq, Debug)]
+/// enum AppState {
+///     Title,
+///     Playing,
+///     Paused,
+/// }
+/// ```
+/// Add the state to your game with `Game::add_state`, and change it with `GameCommand::ChangeState`.
+pub trait GameState: Clone + PartialEq + Debug + ParallelSafe + 'static {}
+impl<T> GameState for T where T: Clone + PartialEq + Debug + ParallelSafe + 'static {}
+
+/// A state boxed up to be given to `GameCommand::ChangeState`. With the `parallel` feature, states have to be `Send` and
+/// `Sync` so commands can be issued from any thread.
+#[cfg(not(feature = "parallel"))]
+pub type BoxedGameState = Box<dyn Any>;
+/// A state boxed up to be given to `GameCommand::ChangeState`. With the `parallel` feature, states have to be `Send` and
+/// `Sync` so commands can be issued from any thread.
+#[cfg(feature = "parallel")]
+pub type BoxedGameState = Box<dyn Any + Send + Sync>;
+
+/// The state the game is currently in. Thomas adds this resource for each state type you add with `Game::add_state`:
+/// ```
+/// use thomas::{State, System};
+///
+/// #[derive(Clone, PartialEq, Debug)]
+/// enum AppState {
+///     Playing,
+///     Paused,
+/// }
+///
+/// System::new(vec![], |_, _, world| {
+///     if world.resource::<State<AppState>>().is(&AppState::Paused) {
+///         // ...
+///     }
+/// });
+/// ```
+#[derive(Resource)]
+pub struct State<S> {
+    pub(crate) current: S,
+}
+impl<S: GameState> State<S> {
+    pub fn current(&self) -> &S {
+        &self.current
+    }
+
+    /// Whether the game is currently in the provided state.
+    pub fn is(&self, state: &S) -> bool {
+        self.current == *state
+    }
+}
+
+/// Marks an entity as belonging to a state. When the game leaves that state, the entity is destroyed, along with all
+/// of its descendants.
+#[derive(Component)]
+pub struct StateScoped<S> {
+    pub state: S,
+}
+
+/// The event that runs when the game enters the state.
+pub(crate) fn enter_state_event_name<S: GameState>(state: &S) -> String {
+    format!("enter-state:{}::{:?}", type_name::<S>(), state)
+}
+
+/// The event that runs when the game exits the state.
+pub(crate) fn exit_state_event_name<S: GameState>(state: &S) -> String {
+    format!("exit-state:{}::{:?}", type_name::<S>(), state)
+}
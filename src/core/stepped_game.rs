@@ -0,0 +1,288 @@
+use std::time::Duration;
+
+use crate::{
+    Game, GameCommand, GameCommandsArg, ManualClock, Query, QueryResultList, Shared, World,
+};
+
+/// A `Game` that's stepped forward one frame at a time rather than running its own game loop. Make one with
+/// `Game::start_stepped`.
+///
+/// Stepping is meant for tests of your gameplay systems. Step the game as far as you need, then check on the world:
+/// ```
+/// use std::time::Duration;
+/// use thomas::{
+///     Component, Dimensions2d, Game, GameCommand, GameOptions, Query, Renderer, System,
+///     TerminalRendererOptions,
+/// };
+///
+/// #[derive(Component)]
+/// struct Counter {
+///     count: u32,
+/// }
+///
+/// let mut game = Game::new(GameOptions {
+///     press_escape_to_quit: false,
+///     max_frame_rate: 60,
+/// })
+/// .add_init_system(System::new(vec![], |_, commands, _| {
+///     commands
+///         .borrow_mut()
+///         .issue(GameCommand::AddEntity(vec![Box::new(Counter { count: 0 })]));
+/// }))
+/// .add_update_system(System::new(vec![Query::new().has::<Counter>()], |results, _, _| {
+///     results[0].get_only_mut::<Counter>().count += 1;
+/// }))
+/// .start_stepped(Renderer::Headless(TerminalRendererOptions {
+///     screen_resolution: Dimensions2d::new(10, 10),
+///     include_default_camera: true,
+///     default_foreground_color: None,
+///     default_background_color: None,
+/// }));
+///
+/// game.step_frames(3, Duration::from_millis(16));
+///
+/// assert_eq!(game.query(&Query::new().has::<Counter>()).get_only::<Counter>().count, 3);
+/// ```
+pub struct SteppedGame {
+    game: Game,
+    commands: GameCommandsArg,
+    clock: ManualClock,
+}
+impl SteppedGame {
+    pub(crate) fn new(game: Game, commands: GameCommandsArg, clock: ManualClock) -> Self {
+        Self {
+            game,
+            commands,
+            clock,
+        }
+    }
+
+    /// Moves the game's clock forward by `frame_duration`, then runs a single frame. The game doesn't wait for the
+    /// frame to take up any real time. If the game is recording input, the frame is written to the recording. Nothing
+    /// happens if the game has quit.
+    pub fn step(&mut self, frame_duration: Duration) {
+        if self.game.is_playing() {
+            self.clock.advance(frame_duration);
+            self.game
+                .run_frame(frame_duration, Shared::clone(&self.commands));
+            self.game.record_frame(frame_duration);
+        }
+    }
+
+    /// Runs `frame_count` frames, each treated as taking `frame_duration`. Stops early if the game quits.
+    pub fn step_frames(&mut self, frame_count: u64, frame_duration: Duration) {
+        for _ in 0..frame_count {
+            self.step(frame_duration);
+        }
+    }
+
+    /// Carries out the command right away, rather than waiting for the next frame.
+    pub fn issue(&mut self, command: GameCommand) {
+        self.commands.borrow_mut().issue(command);
+
+        self.game
+            .process_command_queue(Shared::clone(&self.commands));
+    }
+
+    /// Runs the query against the world as it is now.
+    pub fn query(&self, query: &Query) -> QueryResultList {
+        self.game.world().entity_manager().query(query)
+    }
+
+    /// The clock the game is running on. Moving it forward yourself changes what `Timer`s made from it read, but
+    /// doesn't count towards the next frame's `Time::delta`.
+    pub fn clock(&self) -> &ManualClock {
+        &self.clock
+    }
+
+    pub fn world(&self) -> &World {
+        self.game.world()
+    }
+
+    /// Whether the game is still running. This becomes `false` once `GameCommand::Quit` is issued.
+    pub fn is_playing(&self) -> bool {
+        self.game.is_playing()
+    }
+
+    /// Ends the game, running the cleanup event.
+    pub fn finish(mut self) {
+        self.game.run_cleanup(Shared::clone(&self.commands));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Clock, Component, Dimensions2d, FixedTime, GameOptions, Input, Keycode, Renderer, System,
+        TerminalRendererOptions, Time,
+    };
+
+    #[derive(Component)]
+    struct Counter {
+        count: u32,
+    }
+
+    fn make_game(game: Game) -> SteppedGame {
+        game.add_init_system(System::new(vec![], |_, commands, _| {
+            commands
+                .borrow_mut()
+                .issue(GameCommand::AddEntity(vec![Box::new(Counter { count: 0 })]));
+        }))
+        .start_stepped(Renderer::Headless(TerminalRendererOptions {
+            screen_resolution: Dimensions2d::new(5, 5),
+            include_default_camera: true,
+            default_foreground_color: None,
+            default_background_color: None,
+        }))
+    }
+
+    fn count(game: &SteppedGame) -> u32 {
+        game.query(&Query::new().has::<Counter>())
+            .get_only::<Counter>()
+            .count
+    }
+
+    fn new_game() -> Game {
+        Game::new(GameOptions {
+            press_escape_to_quit: false,
+            max_frame_rate: 60,
+        })
+    }
+
+    mod test_step {
+        use super::*;
+
+        #[test]
+        fn runs_exactly_one_frame() {
+            let mut game = make_game(new_game().add_update_system(System::new(
+                vec![Query::new().has::<Counter>()],
+                |results, _, _| {
+                    results[0].get_only_mut::<Counter>().count += 1;
+                },
+            )));
+
+            assert_eq!(count(&game), 0);
+
+            game.step(Duration::from_millis(16));
+
+            assert_eq!(count(&game), 1);
+        }
+
+        #[test]
+        fn frame_duration_drives_fixed_updates() {
+            let mut game = make_game(
+                new_game()
+                    .insert_resource(FixedTime::new(Duration::from_millis(10)))
+                    .add_fixed_update_system(System::new(
+                        vec![Query::new().has::<Counter>()],
+                        |results, _, _| {
+                            results[0].get_only_mut::<Counter>().count += 1;
+                        },
+                    )),
+            );
+
+            game.step(Duration::from_millis(35));
+
+            assert_eq!(count(&game), 3);
+        }
+
+        #[test]
+        fn moves_time_forward_by_the_frame_duration() {
+            let mut game = make_game(new_game());
+
+            game.step_frames(4, Duration::from_micros(2_500));
+
+            let time = game.world().resource::<Time>();
+
+            assert_eq!(time.elapsed(), Duration::from_millis(10));
+            assert_eq!(time.frame_count(), 4);
+            assert_eq!(game.clock().now(), Duration::from_millis(10));
+        }
+
+        #[test]
+        fn does_nothing_once_the_game_has_quit() {
+            let mut game = make_game(new_game().add_update_system(System::new(
+                vec![Query::new().has::<Counter>()],
+                |results, commands, _| {
+                    results[0].get_only_mut::<Counter>().count += 1;
+
+                    commands.borrow_mut().issue(GameCommand::Quit);
+                },
+            )));
+
+            game.step_frames(5, Duration::from_millis(16));
+
+            assert!(!game.is_playing());
+            assert_eq!(count(&game), 1);
+        }
+    }
+
+    mod test_input {
+        use super::*;
+
+        #[test]
+        fn virtual_key_presses_reach_systems() {
+            let mut game = make_game(new_game().add_update_system(System::new(
+                vec![Query::new().has::<Counter>()],
+                |results, _, world| {
+                    if world.resource::<Input>().is_key_down(&Keycode::Space) {
+                        results[0].get_only_mut::<Counter>().count += 1;
+                    }
+                },
+            )));
+
+            game.world().resource_mut::<Input>().tap_key(Keycode::Space);
+            game.step_frames(3, Duration::from_millis(16));
+
+            assert_eq!(count(&game), 1);
+        }
+    }
+
+    mod test_record_input {
+        use std::{env, fs, process};
+
+        use super::*;
+        use crate::{InputRecorder, InputRecording};
+
+        #[test]
+        fn each_step_is_written_to_the_recording() {
+            let path = env::temp_dir().join(format!(
+                "thomas_stepped_game_record_input_test_{}.rec",
+                process::id()
+            ));
+
+            let mut game =
+                make_game(new_game().record_input(InputRecorder::create(&path).unwrap()));
+
+            game.world()
+                .resource_mut::<Input>()
+                .press_key(Keycode::Space);
+            game.step_frames(2, Duration::from_millis(5));
+
+            let recording = InputRecording::load(&path).unwrap();
+
+            fs::remove_file(&path).unwrap();
+
+            assert_eq!(recording.frames().len(), 2);
+            assert_eq!(
+                recording.frames()[0].frame_duration,
+                Duration::from_millis(5)
+            );
+            assert_eq!(recording.frames()[0].pressed_keys, vec![Keycode::Space]);
+        }
+    }
+
+    mod test_issue {
+        use super::*;
+
+        #[test]
+        fn carries_out_the_command_right_away() {
+            let mut game = make_game(new_game());
+
+            game.issue(GameCommand::AddEntity(vec![Box::new(Counter { count: 7 })]));
+
+            assert_eq!(game.query(&Query::new().has::<Counter>()).len(), 2);
+        }
+    }
+}
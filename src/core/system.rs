@@ -1,7 +1,21 @@
-use crate::{GameCommandsArg, Priority, Query, QueryResultList};
+use std::cell::Cell;
 
-/// The function that's given to a `System` to run against its queries' matches.
-pub type OperatorFn = dyn Fn(Vec<QueryResultList>, GameCommandsArg) -> ();
+use crate::{
+    check_typed_query_borrows, Component, GameCommandsArg, ParallelSafe, Priority, Query,
+    QueryResultList, Resource, SystemAccess, TypedQueries, World,
+};
+
+/// The function that's given to a `System` to run against its queries' matches. Along with the matches, the function
+/// is given the command queue to issue commands with, and a view of the `World` for anything queries can't answer.
+#[cfg(not(feature = "parallel"))]
+pub type OperatorFn = dyn Fn(Vec<QueryResultList>, GameCommandsArg, &World) -> ();
+/// The function that's given to a `System` to run against its queries' matches. Along with the matches, the function
+/// is given the command queue to issue commands with, and a view of the `World` for anything queries can't answer.
+#[cfg(feature = "parallel")]
+pub type OperatorFn = dyn Fn(Vec<QueryResultList>, GameCommandsArg, &World) -> () + Send + Sync;
+
+/// Decides whether a `System` should run when its event is triggered.
+pub(crate) type RunConditionFn = dyn Fn(&World) -> bool;
 
 /// A System represents a function that uses the result of a collection of queries to act on and potentially mutate
 /// the game world. Systems are a core aspect of ECS. Systems are where the bulk of the logic of your game will live,
@@ -14,6 +28,10 @@ pub struct System {
     queries: Vec<Query>,
     operator: Box<OperatorFn>,
     priority: Priority,
+    name: Option<String>,
+    access: SystemAccess,
+    last_run_tick: Cell<u64>,
+    run_condition: Option<Box<RunConditionFn>>,
 }
 impl System {
     /// Makes a new System that will operate on the results of the provided queries. Even if a system's queries have
@@ -22,8 +40,8 @@ impl System {
     /// 
     /// Because Systems always run even if they get no matches, your provided operator should be written to account
     /// for the possibility of no matches unless you can be confident a particular query will always produce a result.
-    /// For example, Thomas injects an entity with the Input component for you very early on in the game's lifecyle, so
-    /// you can be confident that query asking for Input will always give you exactly one match.
+    /// For example, if you know your game always has exactly one player, you can be confident that a query asking for
+    /// the player will always give you exactly one match.
     /// 
     /// The keen-eyed may also notice a potential issue with the fact that Systems can operate on the results of multiple
     /// queries: if you have two queries that match on the same component, attempting to borrow that component mutably
@@ -38,7 +56,7 @@ impl System {
     ///         .has::<Identity>(),
     ///     Query::new()
     ///         .has_where::<Identity>(|identity| identity.id == String::from("PLAYER")),
-    /// ], |_, _| {});
+    /// ], |_, _, _| {});
     /// ```
     /// When run, those queries will produce results where the first query has matched on the same `Identity` as the
     /// second query. You can disjoint your query by using `has_where` or `has_no` to further filter the results to make
@@ -51,16 +69,20 @@ impl System {
     ///         .has_where::<Identity>(|identity| identity.id != String::from("PLAYER")),
     ///     Query::new()
     ///         .has_where::<Identity>(|identity| identity.id == String::from("PLAYER")),
-    /// ], |_, _| {});
+    /// ], |_, _, _| {});
     /// ```
     pub fn new(
         queries: Vec<Query>,
-        operator: impl Fn(Vec<QueryResultList>, GameCommandsArg) -> () + 'static,
+        operator: impl Fn(Vec<QueryResultList>, GameCommandsArg, &World) -> () + ParallelSafe + 'static,
     ) -> Self {
         Self {
+            access: SystemAccess::from_queries(&queries),
             queries,
             operator: Box::new(operator),
             priority: Priority::default(),
+            name: None,
+            last_run_tick: Cell::new(0),
+            run_condition: None,
         }
     }
 
@@ -78,15 +100,139 @@ impl System {
     pub fn new_with_priority(
         priority: Priority,
         queries: Vec<Query>,
-        operator: impl Fn(Vec<QueryResultList>, GameCommandsArg) -> () + 'static,
+        operator: impl Fn(Vec<QueryResultList>, GameCommandsArg, &World) -> () + ParallelSafe + 'static,
     ) -> Self {
         Self {
+            access: SystemAccess::from_queries(&queries),
             queries,
             operator: Box::new(operator),
             priority,
+            name: None,
+            last_run_tick: Cell::new(0),
+            run_condition: None,
         }
     }
 
+    /// Makes a new `System` whose queries are spelled out in the types of its operator's first argument, which is
+    /// either a `TypedQuery` or a tuple of them. Each query gives the components it matched on directly, so there's no
+    /// need to pick the results apart or ask for components by type:
+    /// ```
+    /// use thomas::{Component, Identity, IntCoords2d, System, TerminalTransform, TypedQuery, With};
+    ///
+    /// #[derive(Component)]
+    /// struct Player {}
+    ///
+    /// System::typed(
+    ///     |(players, names): (
+    ///         TypedQuery<&mut TerminalTransform, With<Player>>,
+    ///         TypedQuery<&Identity>,
+    ///     ),
+    ///      _,
+    ///      _| {
+    ///         for mut transform in &players {
+    ///             transform.coords += IntCoords2d::right();
+    ///         }
+    ///
+    ///         for identity in &names {
+    ///             println!("{}", identity.name);
+    ///         }
+    ///     },
+    /// );
+    /// ```
+    /// Borrowing a component with `&mut` while it's borrowed anywhere else in the same query, or in another query that
+    /// could match the same entities, panics right away rather than when the `System` runs. Use `With` and `Without`
+    /// filters to make sure queries that borrow the same component can't match the same entities.
+    ///
+    /// # Panics
+    /// If the queries borrow a component in ways that clash.
+    pub fn typed<Q: TypedQueries>(
+        operator: impl Fn(Q, GameCommandsArg, &World) + ParallelSafe + 'static,
+    ) -> Self {
+        Self::typed_with_priority(Priority::default(), operator)
+    }
+
+    /// Like `typed`, but allows you to specify a `Priority` for the `System`, like `new_with_priority`.
+    ///
+    /// # Panics
+    /// If the queries borrow a component in ways that clash.
+    pub fn typed_with_priority<Q: TypedQueries>(
+        priority: Priority,
+        operator: impl Fn(Q, GameCommandsArg, &World) + ParallelSafe + 'static,
+    ) -> Self {
+        let typed_queries = Q::queries();
+
+        check_typed_query_borrows(&typed_queries);
+
+        let access =
+            SystemAccess::from_borrows(typed_queries.iter().flat_map(|(_, borrows)| borrows));
+        let queries = typed_queries.into_iter().map(|(query, _)| query).collect();
+
+        let mut system =
+            Self::new_with_priority(priority, queries, move |results, commands, world| {
+                operator(Q::from_results(&mut results.into_iter()), commands, world)
+            });
+
+        system.access = access;
+
+        system
+    }
+
+    /// Names the `System`. The name is what the `System` is reported as in the `EngineStats`, so it's easy to tell
+    /// which of your systems is taking up the frame.
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+
+        self
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Declares that the `System` reads components of type `T` straight from the `World`, with `World::component`.
+    /// Components in the `System`'s query results are already accounted for, but, with the `parallel` feature,
+    /// components borrowed from the `World` have to be declared so the `System` never runs at the same time as a
+    /// `System` that writes to them.
+    pub fn reads_component<T: Component + 'static>(mut self) -> Self {
+        self.access.add_component_read(T::id());
+
+        self
+    }
+
+    /// Declares that the `System` writes to components of type `T` straight from the `World`, with
+    /// `World::component_mut`. With the `parallel` feature, the `System` never runs at the same time as another `System`
+    /// that reads or writes them.
+    pub fn writes_component<T: Component + 'static>(mut self) -> Self {
+        self.access.add_component_write(T::id());
+
+        self
+    }
+
+    /// Declares that the `System` reads the resource. The components a `System` uses are worked out from its queries,
+    /// but the resources it gets from the `World` have to be declared so that, with the `parallel` feature, it never
+    /// runs at the same time as a `System` that writes to them.
+    pub fn reads_resource<T: Resource + 'static>(mut self) -> Self {
+        self.access.add_resource_read(T::id());
+
+        self
+    }
+
+    /// Declares that the `System` writes to the resource, like with `World::resource_mut`. With the `parallel` feature,
+    /// the `System` never runs at the same time as another `System` that reads or writes the resource.
+    pub fn writes_resource<T: Resource + 'static>(mut self) -> Self {
+        self.access.add_resource_write(T::id());
+
+        self
+    }
+
+    /// Makes the `System` always run on its own, even with the `parallel` feature. Useful for a `System` that touches
+    /// too much of the world to declare everything it uses.
+    pub fn exclusive(mut self) -> Self {
+        self.access.set_exclusive();
+
+        self
+    }
+
     pub(crate) fn queries(&self) -> &Vec<Query> {
         &self.queries
     }
@@ -98,6 +244,32 @@ impl System {
     pub(crate) fn priority(&self) -> &Priority {
         &self.priority
     }
+
+    pub(crate) fn access(&self) -> &SystemAccess {
+        &self.access
+    }
+
+    /// The change tick the `System` last ran at, which is used to find what's changed since then.
+    pub(crate) fn last_run_tick(&self) -> u64 {
+        self.last_run_tick.get()
+    }
+
+    pub(crate) fn set_last_run_tick(&self, tick: u64) {
+        self.last_run_tick.set(tick);
+    }
+
+    /// Makes the `System` only run when the condition is met. The `System` is skipped entirely otherwise.
+    pub(crate) fn with_run_condition(mut self, run_condition: impl Fn(&World) -> bool + 'static) -> Self {
+        self.run_condition = Some(Box::new(run_condition));
+
+        self
+    }
+
+    pub(crate) fn should_run(&self, world: &World) -> bool {
+        self.run_condition
+            .as_ref()
+            .is_none_or(|run_condition| run_condition(world))
+    }
 }
 
 /// A simple way to organize related systems into a unit. You can easily add all systems created by a `SystemsGenerator`
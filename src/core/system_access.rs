@@ -0,0 +1,261 @@
+use std::{any::TypeId, collections::HashSet};
+
+use crate::{ComponentBorrow, Query};
+
+/// What a `System` reads and writes while it runs. Systems whose access doesn't conflict can safely run at the same
+/// time, since neither can see the other's changes.
+#[derive(Clone, Default, Debug, PartialEq)]
+pub(crate) struct SystemAccess {
+    component_reads: HashSet<TypeId>,
+    component_writes: HashSet<TypeId>,
+    resource_reads: HashSet<TypeId>,
+    resource_writes: HashSet<TypeId>,
+    is_exclusive: bool,
+}
+impl SystemAccess {
+    /// Works out the components a `System` accesses from its queries. Every component a query can hand back is
+    /// considered written, unless the query is `read_only`. Components that are only used to rule out matches, like
+    /// those given to `has_no`, aren't accessed at all.
+    pub fn from_queries(queries: &[Query]) -> Self {
+        let mut access = Self::default();
+
+        for query in queries {
+            let component_ids = query
+                .allowed_component_ids()
+                .into_iter()
+                .chain(query.optional_component_ids().iter().copied())
+                .chain(query.any_of_component_groups().iter().flatten().copied());
+
+            if query.is_read_only() {
+                access.component_reads.extend(component_ids);
+            } else {
+                access.component_writes.extend(component_ids);
+            }
+        }
+
+        access
+            .component_reads
+            .retain(|component_id| !access.component_writes.contains(component_id));
+
+        access
+    }
+
+    /// Works out the components a `System` accesses from the borrows of its typed queries. Unlike with `from_queries`,
+    /// components are only considered written when they're borrowed exclusively.
+    pub fn from_borrows<'a>(borrows: impl IntoIterator<Item = &'a ComponentBorrow>) -> Self {
+        let mut access = Self::default();
+
+        for borrow in borrows {
+            if borrow.is_exclusive() {
+                access.component_writes.insert(borrow.component_id());
+            } else {
+                access.component_reads.insert(borrow.component_id());
+            }
+        }
+
+        access
+            .component_reads
+            .retain(|component_id| !access.component_writes.contains(component_id));
+
+        access
+    }
+
+    pub fn add_component_read(&mut self, component_id: TypeId) {
+        if !self.component_writes.contains(&component_id) {
+            self.component_reads.insert(component_id);
+        }
+    }
+
+    pub fn add_component_write(&mut self, component_id: TypeId) {
+        self.component_reads.remove(&component_id);
+        self.component_writes.insert(component_id);
+    }
+
+    pub fn add_resource_read(&mut self, resource_id: TypeId) {
+        if !self.resource_writes.contains(&resource_id) {
+            self.resource_reads.insert(resource_id);
+        }
+    }
+
+    pub fn add_resource_write(&mut self, resource_id: TypeId) {
+        self.resource_reads.remove(&resource_id);
+        self.resource_writes.insert(resource_id);
+    }
+
+    pub fn set_exclusive(&mut self) {
+        self.is_exclusive = true;
+    }
+
+    /// Whether a `System` with this access can run at the same time as one with the other access. That's the case
+    /// unless either is exclusive, or either writes something the other reads or writes.
+    pub fn is_compatible_with(&self, other: &SystemAccess) -> bool {
+        !self.is_exclusive
+            && !other.is_exclusive
+            && !Self::conflicts(
+                &self.component_reads,
+                &self.component_writes,
+                &other.component_reads,
+                &other.component_writes,
+            )
+            && !Self::conflicts(
+                &self.resource_reads,
+                &self.resource_writes,
+                &other.resource_reads,
+                &other.resource_writes,
+            )
+    }
+
+    fn conflicts(
+        reads: &HashSet<TypeId>,
+        writes: &HashSet<TypeId>,
+        other_reads: &HashSet<TypeId>,
+        other_writes: &HashSet<TypeId>,
+    ) -> bool {
+        !writes.is_disjoint(other_reads)
+            || !writes.is_disjoint(other_writes)
+            || !reads.is_disjoint(other_writes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Component, Resource};
+
+    #[derive(Component)]
+    struct Position {}
+
+    #[derive(Component)]
+    struct Velocity {}
+
+    #[derive(Component)]
+    struct Frozen {}
+
+    #[derive(Resource)]
+    struct Score {}
+
+    mod test_from_queries {
+        use super::*;
+
+        #[test]
+        fn components_are_written_by_default() {
+            let access = SystemAccess::from_queries(&[Query::new()
+                .has::<Position>()
+                .has_optional::<Velocity>()]);
+
+            assert_eq!(
+                access.component_writes,
+                HashSet::from([Position::id(), Velocity::id()])
+            );
+            assert!(access.component_reads.is_empty());
+        }
+
+        #[test]
+        fn read_only_queries_only_read() {
+            let access = SystemAccess::from_queries(&[Query::new()
+                .has::<Position>()
+                .has_any::<(Velocity,)>()
+                .read_only()]);
+
+            assert_eq!(
+                access.component_reads,
+                HashSet::from([Position::id(), Velocity::id()])
+            );
+            assert!(access.component_writes.is_empty());
+        }
+
+        #[test]
+        fn writes_win_over_reads_of_the_same_component() {
+            let access = SystemAccess::from_queries(&[
+                Query::new().has::<Position>().read_only(),
+                Query::new().has::<Position>(),
+            ]);
+
+            assert_eq!(access.component_writes, HashSet::from([Position::id()]));
+            assert!(access.component_reads.is_empty());
+        }
+
+        #[test]
+        fn forbidden_components_are_not_accessed() {
+            let access = SystemAccess::from_queries(&[Query::new().has_no::<Frozen>()]);
+
+            assert_eq!(access, SystemAccess::default());
+        }
+    }
+
+    mod test_from_borrows {
+        use super::*;
+
+        #[test]
+        fn only_exclusive_borrows_are_written() {
+            let access = SystemAccess::from_borrows(&[
+                ComponentBorrow::exclusive::<Position>(),
+                ComponentBorrow::shared::<Velocity>(),
+                ComponentBorrow::shared::<Position>(),
+            ]);
+
+            assert_eq!(access.component_writes, HashSet::from([Position::id()]));
+            assert_eq!(access.component_reads, HashSet::from([Velocity::id()]));
+        }
+    }
+
+    mod test_is_compatible_with {
+        use super::*;
+
+        #[test]
+        fn readers_of_the_same_component_are_compatible() {
+            let first = SystemAccess::from_queries(&[Query::new().has::<Position>().read_only()]);
+            let second = SystemAccess::from_queries(&[Query::new().has::<Position>().read_only()]);
+
+            assert!(first.is_compatible_with(&second));
+        }
+
+        #[test]
+        fn a_writer_is_not_compatible_with_a_reader_of_the_same_component() {
+            let first = SystemAccess::from_queries(&[Query::new().has::<Position>()]);
+            let second = SystemAccess::from_queries(&[Query::new().has::<Position>().read_only()]);
+
+            assert!(!first.is_compatible_with(&second));
+            assert!(!second.is_compatible_with(&first));
+        }
+
+        #[test]
+        fn writers_of_different_components_are_compatible() {
+            let first = SystemAccess::from_queries(&[Query::new().has::<Position>()]);
+            let second = SystemAccess::from_queries(&[Query::new().has::<Velocity>()]);
+
+            assert!(first.is_compatible_with(&second));
+        }
+
+        #[test]
+        fn declared_component_writes_conflict_with_query_reads() {
+            let mut first = SystemAccess::default();
+            let second = SystemAccess::from_queries(&[Query::new().has::<Position>().read_only()]);
+
+            first.add_component_write(Position::id());
+
+            assert!(!first.is_compatible_with(&second));
+        }
+
+        #[test]
+        fn writers_of_the_same_resource_are_not_compatible() {
+            let mut first = SystemAccess::default();
+            let mut second = SystemAccess::default();
+
+            first.add_resource_write(Score::id());
+            second.add_resource_write(Score::id());
+
+            assert!(!first.is_compatible_with(&second));
+        }
+
+        #[test]
+        fn exclusive_access_is_not_compatible_with_anything() {
+            let mut first = SystemAccess::default();
+
+            first.set_exclusive();
+
+            assert!(!first.is_compatible_with(&SystemAccess::default()));
+            assert!(!SystemAccess::default().is_compatible_with(&first));
+        }
+    }
+}
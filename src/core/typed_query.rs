@@ -0,0 +1,532 @@
+use std::{any::TypeId, marker::PhantomData, slice::Iter};
+
+use crate::{Component, Entity, Query, QueryResult, QueryResultList, Ref, RefMut};
+
+/// How a `TypedQuery` borrows one of the components it gives back.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ComponentBorrow {
+    component_id: TypeId,
+    component_name: &'static str,
+    is_exclusive: bool,
+}
+impl ComponentBorrow {
+    /// A shared borrow of the component, like `&T`. Any number of shared borrows of a component can be held at once.
+    pub fn shared<T: Component + 'static>() -> Self {
+        Self {
+            component_id: T::id(),
+            component_name: T::name(),
+            is_exclusive: false,
+        }
+    }
+
+    /// An exclusive borrow of the component, like `&mut T`. An exclusive borrow can't be held alongside any other
+    /// borrow of the same component.
+    pub fn exclusive<T: Component + 'static>() -> Self {
+        Self {
+            component_id: T::id(),
+            component_name: T::name(),
+            is_exclusive: true,
+        }
+    }
+
+    pub fn component_id(&self) -> TypeId {
+        self.component_id
+    }
+
+    pub fn is_exclusive(&self) -> bool {
+        self.is_exclusive
+    }
+}
+
+/// What a `TypedQuery` gives for each of its matches. `&T` gives a shared borrow of the `T` component, `&mut T` gives
+/// an exclusive one, and `Option<&T>` and `Option<&mut T>` give the component if the match has it. `Entity` gives the
+/// matching entity. A tuple of any of these gives each of them.
+pub trait QueryData {
+    type Item<'a>;
+
+    /// Adds what's needed to get the data to the query.
+    fn add_to_query(query: Query) -> Query;
+    /// The components that are borrowed to get the data.
+    fn borrows() -> Vec<ComponentBorrow>;
+    /// Gets the data out of a match of the query.
+    fn fetch(result: &QueryResult) -> Self::Item<'_>;
+}
+
+impl<T: Component + 'static> QueryData for &T {
+    type Item<'a> = Ref<'a, T>;
+
+    fn add_to_query(query: Query) -> Query {
+        query.has::<T>()
+    }
+
+    fn borrows() -> Vec<ComponentBorrow> {
+        vec![ComponentBorrow::shared::<T>()]
+    }
+
+    fn fetch(result: &QueryResult) -> Self::Item<'_> {
+        result.components().get::<T>()
+    }
+}
+
+impl<T: Component + 'static> QueryData for &mut T {
+    type Item<'a> = RefMut<'a, T>;
+
+    fn add_to_query(query: Query) -> Query {
+        query.has::<T>()
+    }
+
+    fn borrows() -> Vec<ComponentBorrow> {
+        vec![ComponentBorrow::exclusive::<T>()]
+    }
+
+    fn fetch(result: &QueryResult) -> Self::Item<'_> {
+        result.components().get_mut::<T>()
+    }
+}
+
+impl<T: Component + 'static> QueryData for Option<&T> {
+    type Item<'a> = Option<Ref<'a, T>>;
+
+    fn add_to_query(query: Query) -> Query {
+        query.has_optional::<T>()
+    }
+
+    fn borrows() -> Vec<ComponentBorrow> {
+        vec![ComponentBorrow::shared::<T>()]
+    }
+
+    fn fetch(result: &QueryResult) -> Self::Item<'_> {
+        result.components().try_get::<T>()
+    }
+}
+
+impl<T: Component + 'static> QueryData for Option<&mut T> {
+    type Item<'a> = Option<RefMut<'a, T>>;
+
+    fn add_to_query(query: Query) -> Query {
+        query.has_optional::<T>()
+    }
+
+    fn borrows() -> Vec<ComponentBorrow> {
+        vec![ComponentBorrow::exclusive::<T>()]
+    }
+
+    fn fetch(result: &QueryResult) -> Self::Item<'_> {
+        result.components().try_get_mut::<T>()
+    }
+}
+
+impl QueryData for Entity {
+    type Item<'a> = Entity;
+
+    fn add_to_query(query: Query) -> Query {
+        query
+    }
+
+    fn borrows() -> Vec<ComponentBorrow> {
+        vec![]
+    }
+
+    fn fetch(result: &QueryResult) -> Self::Item<'_> {
+        *result.entity()
+    }
+}
+
+macro_rules! impl_query_data_for_tuple {
+    ($($data:ident),*) => {
+        impl<$($data: QueryData),*> QueryData for ($($data,)*) {
+            type Item<'a> = ($($data::Item<'a>,)*);
+
+            fn add_to_query(query: Query) -> Query {
+                $(let query = $data::add_to_query(query);)*
+
+                query
+            }
+
+            fn borrows() -> Vec<ComponentBorrow> {
+                let mut borrows = vec![];
+
+                $(borrows.extend($data::borrows());)*
+
+                borrows
+            }
+
+            fn fetch(result: &QueryResult) -> Self::Item<'_> {
+                ($($data::fetch(result),)*)
+            }
+        }
+    };
+}
+
+impl_query_data_for_tuple!(A);
+impl_query_data_for_tuple!(A, B);
+impl_query_data_for_tuple!(A, B, C);
+impl_query_data_for_tuple!(A, B, C, D);
+impl_query_data_for_tuple!(A, B, C, D, E);
+impl_query_data_for_tuple!(A, B, C, D, E, F);
+impl_query_data_for_tuple!(A, B, C, D, E, F, G);
+impl_query_data_for_tuple!(A, B, C, D, E, F, G, H);
+
+/// Narrows down the matches of a `TypedQuery` without borrowing anything. A tuple of filters narrows down the matches
+/// by all of them, and `()` doesn't narrow them down at all.
+pub trait QueryFilter {
+    /// Adds the filter to the query.
+    fn add_to_query(query: Query) -> Query;
+}
+
+/// Only matches entities that have the component. Like `Query::has`, but the component isn't borrowed.
+pub struct With<T: Component> {
+    _component: PhantomData<T>,
+}
+impl<T: Component + 'static> QueryFilter for With<T> {
+    fn add_to_query(query: Query) -> Query {
+        query.has::<T>()
+    }
+}
+
+/// Only matches entities that don't have the component. Like `Query::has_no`.
+pub struct Without<T: Component> {
+    _component: PhantomData<T>,
+}
+impl<T: Component + 'static> QueryFilter for Without<T> {
+    fn add_to_query(query: Query) -> Query {
+        query.has_no::<T>()
+    }
+}
+
+/// Only matches entities whose component was added since the `System` last ran. Like `Query::added`.
+pub struct Added<T: Component> {
+    _component: PhantomData<T>,
+}
+impl<T: Component + 'static> QueryFilter for Added<T> {
+    fn add_to_query(query: Query) -> Query {
+        query.added::<T>()
+    }
+}
+
+/// Only matches entities whose component changed since the `System` last ran. Like `Query::changed`.
+pub struct Changed<T: Component> {
+    _component: PhantomData<T>,
+}
+impl<T: Component + 'static> QueryFilter for Changed<T> {
+    fn add_to_query(query: Query) -> Query {
+        query.changed::<T>()
+    }
+}
+
+macro_rules! impl_query_filter_for_tuple {
+    ($($filter:ident),*) => {
+        impl<$($filter: QueryFilter),*> QueryFilter for ($($filter,)*) {
+            fn add_to_query(query: Query) -> Query {
+                $(let query = $filter::add_to_query(query);)*
+
+                query
+            }
+        }
+    };
+}
+
+impl_query_filter_for_tuple!();
+impl_query_filter_for_tuple!(A);
+impl_query_filter_for_tuple!(A, B);
+impl_query_filter_for_tuple!(A, B, C);
+impl_query_filter_for_tuple!(A, B, C, D);
+impl_query_filter_for_tuple!(A, B, C, D, E);
+impl_query_filter_for_tuple!(A, B, C, D, E, F);
+impl_query_filter_for_tuple!(A, B, C, D, E, F, G);
+impl_query_filter_for_tuple!(A, B, C, D, E, F, G, H);
+
+/// The matches of a query whose components are spelled out in its type. The first type parameter is what each match
+/// gives, and the second is any filters on the matches:
+/// ```
+/// use thomas::{Component, IntCoords2d, System, TerminalTransform, TypedQuery, Without};
+///
+/// #[derive(Component)]
+/// struct Velocity {
+///     value: IntCoords2d,
+/// }
+///
+/// #[derive(Component)]
+/// struct Frozen {}
+///
+/// System::typed(
+///     |movers: TypedQuery<(&mut TerminalTransform, &Velocity), Without<Frozen>>, _, _| {
+///         for (mut transform, velocity) in &movers {
+///             transform.coords += velocity.value;
+///         }
+///     },
+/// );
+/// ```
+/// Unlike with `QueryResultList`, there's no need to ask for each component by type, so there's no way to ask for one
+/// the query didn't match on.
+pub struct TypedQuery<D: QueryData, F: QueryFilter = ()> {
+    results: QueryResultList,
+    _data: PhantomData<fn() -> (D, F)>,
+}
+impl<D: QueryData, F: QueryFilter> TypedQuery<D, F> {
+    pub(crate) fn new(results: QueryResultList) -> Self {
+        Self {
+            results,
+            _data: PhantomData,
+        }
+    }
+
+    /// The `Query` the typed query runs.
+    pub fn query() -> Query {
+        F::add_to_query(D::add_to_query(Query::new()))
+    }
+
+    /// Goes through the data of each match.
+    pub fn iter(&self) -> TypedQueryIter<'_, D> {
+        self.into_iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+
+    /// The data of the only match. This is useful when the query will only ever match on exactly **one** entity in
+    /// the world, like the player.
+    ///
+    /// # Panics
+    /// If there isn't at least one match.
+    pub fn only(&self) -> D::Item<'_> {
+        D::fetch(&self.results[0])
+    }
+
+    /// Like `only`, but doesn't panic.
+    pub fn try_only(&self) -> Option<D::Item<'_>> {
+        self.results.first().map(D::fetch)
+    }
+}
+
+/// Goes through the data of each of a `TypedQuery`'s matches.
+pub type TypedQueryIter<'a, D> =
+    std::iter::Map<Iter<'a, QueryResult>, fn(&'a QueryResult) -> <D as QueryData>::Item<'a>>;
+
+impl<'a, D: QueryData, F: QueryFilter> IntoIterator for &'a TypedQuery<D, F> {
+    type Item = D::Item<'a>;
+    type IntoIter = TypedQueryIter<'a, D>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.results.iter().map(D::fetch)
+    }
+}
+
+/// The queries of a `System` made with `System::typed`. This is either a single `TypedQuery`, or a tuple of them.
+pub trait TypedQueries {
+    /// Each query the `System` runs, along with the components it borrows.
+    fn queries() -> Vec<(Query, Vec<ComponentBorrow>)>;
+    /// Makes the typed queries from the results of running `queries`, in the same order.
+    fn from_results(results: &mut dyn Iterator<Item = QueryResultList>) -> Self;
+}
+
+impl<D: QueryData, F: QueryFilter> TypedQueries for TypedQuery<D, F> {
+    fn queries() -> Vec<(Query, Vec<ComponentBorrow>)> {
+        vec![(Self::query(), D::borrows())]
+    }
+
+    fn from_results(results: &mut dyn Iterator<Item = QueryResultList>) -> Self {
+        Self::new(
+            results
+                .next()
+                .expect("There are results for every typed query."),
+        )
+    }
+}
+
+macro_rules! impl_typed_queries_for_tuple {
+    ($($queries:ident),*) => {
+        impl<$($queries: TypedQueries),*> TypedQueries for ($($queries,)*) {
+            fn queries() -> Vec<(Query, Vec<ComponentBorrow>)> {
+                let mut queries = vec![];
+
+                $(queries.extend($queries::queries());)*
+
+                queries
+            }
+
+            fn from_results(results: &mut dyn Iterator<Item = QueryResultList>) -> Self {
+                ($($queries::from_results(results),)*)
+            }
+        }
+    };
+}
+
+impl_typed_queries_for_tuple!(A);
+impl_typed_queries_for_tuple!(A, B);
+impl_typed_queries_for_tuple!(A, B, C);
+impl_typed_queries_for_tuple!(A, B, C, D);
+impl_typed_queries_for_tuple!(A, B, C, D, E);
+impl_typed_queries_for_tuple!(A, B, C, D, E, F);
+impl_typed_queries_for_tuple!(A, B, C, D, E, F, G);
+impl_typed_queries_for_tuple!(A, B, C, D, E, F, G, H);
+
+/// Makes sure none of the queries' borrows can clash when the `System` runs. A component can be borrowed exclusively
+/// by a query as long as it's not borrowed anywhere else in the same query, and any other query that borrows it can
+/// never match the same entities.
+///
+/// # Panics
+/// If two of the borrows clash.
+pub(crate) fn check_typed_query_borrows(queries: &[(Query, Vec<ComponentBorrow>)]) {
+    let borrows: Vec<(usize, &ComponentBorrow)> = queries
+        .iter()
+        .enumerate()
+        .flat_map(|(query_index, (_, borrows))| {
+            borrows.iter().map(move |borrow| (query_index, borrow))
+        })
+        .collect();
+
+    for (index, (query_index, borrow)) in borrows.iter().enumerate() {
+        for (other_query_index, other_borrow) in &borrows[index + 1..] {
+            let can_clash = borrow.component_id == other_borrow.component_id
+                && (borrow.is_exclusive || other_borrow.is_exclusive)
+                && (query_index == other_query_index
+                    || !are_disjoint(&queries[*query_index].0, &queries[*other_query_index].0));
+
+            if can_clash {
+                panic!(
+                    "The typed queries borrow {} exclusively alongside another borrow of it. Borrow it through only one query, or use With and Without filters so the queries can't match the same entities.",
+                    borrow.component_name
+                );
+            }
+        }
+    }
+}
+
+/// Whether the queries can never match the same entity, because one requires a component the other rules out.
+fn are_disjoint(query: &Query, other_query: &Query) -> bool {
+    let rules_out_required = |query: &Query, other_query: &Query| {
+        let forbidden_component_ids = other_query.forbidden_component_ids();
+
+        query
+            .allowed_component_ids()
+            .iter()
+            .any(|component_id| forbidden_component_ids.contains(component_id))
+    };
+
+    rules_out_required(query, other_query) || rules_out_required(other_query, query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Component)]
+    struct Position {
+        x: i64,
+    }
+
+    #[derive(Component)]
+    struct Velocity {
+        x: i64,
+    }
+
+    #[derive(Component)]
+    struct Frozen {}
+
+    fn query_results(components: Vec<Vec<Box<dyn Component>>>) -> QueryResultList {
+        QueryResultList::new(
+            components
+                .into_iter()
+                .enumerate()
+                .map(|(index, components)| {
+                    QueryResult::new(
+                        Entity(index as u64, 0),
+                        crate::StoredComponentList::new(
+                            components
+                                .into_iter()
+                                .map(|component| {
+                                    crate::Shared::new(crate::SharedCell::new(component))
+                                })
+                                .collect(),
+                        ),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    mod test_iter {
+        use super::*;
+
+        #[test]
+        fn gives_the_data_of_each_match() {
+            let movers =
+                TypedQuery::<(Entity, &mut Position, &Velocity)>::new(query_results(vec![
+                    vec![Box::new(Position { x: 0 }), Box::new(Velocity { x: 2 })],
+                    vec![Box::new(Position { x: 5 }), Box::new(Velocity { x: -1 })],
+                ]));
+
+            for (_, mut position, velocity) in &movers {
+                position.x += velocity.x;
+            }
+
+            let positions: Vec<(Entity, i64)> = movers
+                .iter()
+                .map(|(entity, position, _)| (entity, position.x))
+                .collect();
+
+            assert_eq!(positions, vec![(Entity(0, 0), 2), (Entity(1, 0), 4)]);
+        }
+
+        #[test]
+        fn optional_data_is_none_when_the_match_does_not_have_the_component() {
+            let positions = TypedQuery::<(&Position, Option<&Velocity>)>::new(query_results(vec![
+                vec![Box::new(Position { x: 0 })],
+            ]));
+
+            assert!(positions.only().1.is_none());
+        }
+    }
+
+    mod test_query {
+        use super::*;
+
+        #[test]
+        fn includes_the_data_and_filters() {
+            let query = TypedQuery::<(&Position, Option<&mut Velocity>), Without<Frozen>>::query();
+
+            assert_eq!(query.allowed_component_ids(), vec![Position::id()]);
+            assert_eq!(query.optional_component_ids(), &vec![Velocity::id()]);
+            assert_eq!(query.forbidden_component_ids(), vec![Frozen::id()]);
+        }
+    }
+
+    mod test_check_typed_query_borrows {
+        use super::*;
+
+        #[test]
+        fn shared_borrows_do_not_clash() {
+            check_typed_query_borrows(
+                &<(TypedQuery<&Position>, TypedQuery<(&Position, &Velocity)>)>::queries(),
+            );
+        }
+
+        #[test]
+        #[should_panic]
+        fn exclusive_borrow_clashes_with_a_borrow_in_the_same_query() {
+            check_typed_query_borrows(&TypedQuery::<(&mut Position, &Position)>::queries());
+        }
+
+        #[test]
+        #[should_panic]
+        fn exclusive_borrow_clashes_with_a_borrow_in_another_query() {
+            check_typed_query_borrows(
+                &<(TypedQuery<&mut Position>, TypedQuery<Option<&Position>>)>::queries(),
+            );
+        }
+
+        #[test]
+        fn exclusive_borrow_does_not_clash_with_a_query_that_cannot_match_the_same_entities() {
+            check_typed_query_borrows(&<(
+                TypedQuery<&mut Position, With<Frozen>>,
+                TypedQuery<&Position, Without<Frozen>>,
+            )>::queries());
+        }
+    }
+}
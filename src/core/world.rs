@@ -0,0 +1,877 @@
+use std::any::TypeId;
+
+use crate::{
+    Children, Component, Entity, EntityManager, EventChannels, GlobalTerminalTransform,
+    IntCoords2d, ParallelSafe, Parent, Ref, RefMut, Resource, ResourceStore, TerminalTransform,
+};
+
+/// A view of the game world that's given to every `System` alongside its query results. Queries are still the main way
+/// a `System` gets at the world, but the `World` answers questions about it that a query can't, like whether an
+/// `Entity` you held onto is still around.
+///
+/// The `World` is also where the game's `Resource`s live. Resources can be borrowed, and mutably borrowed, straight
+/// from the `World`.
+///
+/// Otherwise, the `World` can't be changed directly. Like always, use `GameCommand`s to make changes to the world,
+/// including adding and removing resources.
+pub struct World {
+    entity_manager: EntityManager,
+    resources: ResourceStore,
+    event_channels: EventChannels,
+    #[cfg(feature = "serde")]
+    component_registry: crate::ComponentRegistry,
+    #[cfg(feature = "serde")]
+    prefabs: crate::Prefabs,
+}
+impl World {
+    pub(crate) fn new() -> Self {
+        Self {
+            entity_manager: EntityManager::new(),
+            resources: ResourceStore::new(),
+            event_channels: EventChannels::new(),
+            #[cfg(feature = "serde")]
+            component_registry: crate::ComponentRegistry::new(),
+            #[cfg(feature = "serde")]
+            prefabs: crate::Prefabs::new(),
+        }
+    }
+
+    /// Whether the entity is still in the world. This is useful when you've held onto an `Entity`, say, as the target
+    /// of a homing missile. If that target has since been destroyed, this will be `false`, even if the target's ID
+    /// has been reused by some new `Entity`.
+    pub fn is_alive(&self, entity: &Entity) -> bool {
+        self.entity_manager.is_alive(entity)
+    }
+
+    /// Whether the entity has a component of type `T`. Always `false` if the entity isn't alive.
+    pub fn has_component<T>(&self, entity: &Entity) -> bool
+    where
+        T: Component + 'static,
+    {
+        self.entity_manager.has_component(entity, &T::id())
+    }
+
+    /// Gets a reference to the entity's component of type `T`. This is how a `System` gets at the components of an
+    /// `Entity` it got from somewhere other than its query results, like a `TerminalCollision`.
+    ///
+    /// # Panics
+    /// If the entity isn't alive, doesn't have a component of type `T`, or you've already mutably borrowed it, say,
+    /// through the running `System`'s query results.
+    pub fn component<T>(&self, entity: &Entity) -> Ref<'_, T>
+    where
+        T: Component + 'static,
+    {
+        if let Some(component) = self.try_component::<T>(entity) {
+            return component;
+        }
+
+        panic!("Component {} was not present on entity {:?}, or you're trying to borrow it while it's already mutably borrowed.", T::name(), entity);
+    }
+
+    /// Like `component`, but gets a mutable reference. The component is marked as changed.
+    ///
+    /// # Panics
+    /// If the entity isn't alive, doesn't have a component of type `T`, or you've already borrowed it.
+    pub fn component_mut<T>(&self, entity: &Entity) -> RefMut<'_, T>
+    where
+        T: Component + 'static,
+    {
+        if let Some(component) = self.try_component_mut::<T>(entity) {
+            return component;
+        }
+
+        panic!("Component {} was not present on entity {:?}, or you're trying to borrow it while it's already borrowed.", T::name(), entity);
+    }
+
+    /// Like `component`, but doesn't panic.
+    pub fn try_component<T>(&self, entity: &Entity) -> Option<Ref<'_, T>>
+    where
+        T: Component + 'static,
+    {
+        self.entity_manager.get_component::<T>(entity)
+    }
+
+    /// Like `component_mut`, but doesn't panic.
+    pub fn try_component_mut<T>(&self, entity: &Entity) -> Option<RefMut<'_, T>>
+    where
+        T: Component + 'static,
+    {
+        self.entity_manager.get_component_mut::<T>(entity)
+    }
+
+    /// The components of type `T` that were removed from entities since the current `System` last ran, along with the
+    /// `Entity` each was removed from. Components are considered removed when they're removed from their entity, or
+    /// when their entity is destroyed.
+    ///
+    /// Removed components are only kept around for a frame after they're removed, so a `System` that doesn't run every
+    /// frame may miss some.
+    pub fn removed_components<T>(&self) -> Vec<(Entity, Ref<'_, T>)>
+    where
+        T: Component + 'static,
+    {
+        self.entity_manager.removed_components::<T>()
+    }
+
+    /// Whether a resource of type `T` is in the world.
+    pub fn has_resource<T>(&self) -> bool
+    where
+        T: Resource + 'static,
+    {
+        self.resources.contains(&T::id())
+    }
+
+    /// Gets a reference to the resource of type `T`.
+    ///
+    /// # Panics
+    /// If there's no resource of type `T` in the world, or you've already mutably borrowed it.
+    pub fn resource<T>(&self) -> Ref<'_, T>
+    where
+        T: Resource + 'static,
+    {
+        if let Some(resource) = self.try_resource::<T>() {
+            return resource;
+        }
+
+        panic!("Resource {} was not present, or you're trying to borrow it while it's already mutably borrowed.", T::name());
+    }
+
+    /// Like `resource`, but gets a mutable reference.
+    ///
+    /// # Panics
+    /// If there's no resource of type `T` in the world, or you've already borrowed it.
+    pub fn resource_mut<T>(&self) -> RefMut<'_, T>
+    where
+        T: Resource + 'static,
+    {
+        if let Some(resource) = self.try_resource_mut::<T>() {
+            return resource;
+        }
+
+        panic!("Resource {} was not present, or you're trying to borrow it while it's already borrowed.", T::name());
+    }
+
+    /// Like `resource`, but doesn't panic.
+    pub fn try_resource<T>(&self) -> Option<Ref<'_, T>>
+    where
+        T: Resource + 'static,
+    {
+        self.resources.try_get::<T>()
+    }
+
+    /// Like `resource_mut`, but doesn't panic.
+    pub fn try_resource_mut<T>(&self) -> Option<RefMut<'_, T>>
+    where
+        T: Resource + 'static,
+    {
+        self.resources.try_get_mut::<T>()
+    }
+
+    /// Sends an event that other `System`s can read with an `EventReader`. Unlike commands, the event is available to
+    /// be read immediately. Events only live for a limited number of frames before they're dropped.
+    pub fn send_event<T: ParallelSafe + 'static>(&self, event: T) {
+        self.event_channels.send(event);
+    }
+
+    /// Adds an entity to the world as a child of `parent`. Returns the new `Entity`. Nothing is added if `parent` isn't
+    /// alive.
+    pub(crate) fn add_child_entity(
+        &mut self,
+        parent: &Entity,
+        components: Vec<Box<dyn Component>>,
+    ) -> Option<Entity> {
+        if !self.is_alive(parent) {
+            return None;
+        }
+
+        let child = self.entity_manager.add_entity(components);
+
+        self.set_parent(&child, parent);
+
+        Some(child)
+    }
+
+    /// Makes `child` a child of `parent`, taking it away from any parent it already had. The child's `TerminalTransform`
+    /// is treated as its offset from `parent` from then on. Returns whether the parent was set. Nothing happens if
+    /// either entity isn't alive, or if `parent` is `child` or one of its descendants.
+    pub(crate) fn set_parent(&mut self, child: &Entity, parent: &Entity) -> bool {
+        if !self.is_alive(child)
+            || !self.is_alive(parent)
+            || self.is_self_or_ancestor(child, parent)
+        {
+            return false;
+        }
+
+        self.detach_from_parent(child);
+
+        self.entity_manager
+            .add_component_to_entity(child, Box::new(Parent { entity: *parent }));
+
+        let added_to_existing_children = self
+            .entity_manager
+            .get_component_mut::<Children>(parent)
+            .map(|mut children| children.entities.push(*child))
+            .is_some();
+
+        if !added_to_existing_children {
+            self.entity_manager.add_component_to_entity(
+                parent,
+                Box::new(Children {
+                    entities: vec![*child],
+                }),
+            );
+        }
+
+        let coords = self.terminal_world_coords(child);
+
+        self.entity_manager
+            .add_component_to_entity(child, Box::new(GlobalTerminalTransform { coords }));
+
+        true
+    }
+
+    /// Takes `child` away from its parent. The child's `TerminalTransform` is updated to where it was in the world, so
+    /// it stays in place. Returns whether the entity had a parent to remove.
+    pub(crate) fn remove_parent(&mut self, child: &Entity) -> bool {
+        if self.entity_manager.get_component::<Parent>(child).is_none() {
+            return false;
+        }
+
+        let coords = self.terminal_world_coords(child);
+
+        self.detach_from_parent(child);
+
+        if let Some(mut transform) = self
+            .entity_manager
+            .get_component_mut::<TerminalTransform>(child)
+        {
+            transform.coords = coords;
+        }
+
+        true
+    }
+
+    /// Removes the component from the entity, keeping parents and children linked up. Removing `Parent` takes the
+    /// entity away from its parent, and removing `Children` takes every child away from the entity, with everything
+    /// left in the same place in the world. A child's `GlobalTerminalTransform` is kept up to date by Thomas, so it
+    /// isn't removed while the child has a parent. Returns whether the component was removed.
+    pub(crate) fn remove_component(&mut self, entity: &Entity, component_id: TypeId) -> bool {
+        if component_id == Parent::id() {
+            self.remove_parent(entity)
+        } else if component_id == Children::id() {
+            let children = self.children_of(entity);
+
+            for child in &children {
+                self.remove_parent(child);
+            }
+
+            !children.is_empty()
+        } else if component_id == GlobalTerminalTransform::id()
+            && self
+                .entity_manager
+                .get_component::<Parent>(entity)
+                .is_some()
+        {
+            false
+        } else {
+            self.entity_manager
+                .remove_component_from_entity(entity, component_id)
+        }
+    }
+
+    /// Removes the entity from the world. Its children are left in the world without a parent, in the same place they
+    /// were. Returns whether the entity was removed.
+    pub(crate) fn destroy_entity(&mut self, entity: &Entity) -> bool {
+        if !self.is_alive(entity) {
+            return false;
+        }
+
+        self.detach_from_parent(entity);
+
+        for child in self.children_of(entity) {
+            self.remove_parent(&child);
+        }
+
+        self.entity_manager.remove_entity(entity)
+    }
+
+    /// Removes the entity and all of its descendants from the world. Returns whether the entity was removed.
+    pub(crate) fn destroy_entity_and_descendants(&mut self, entity: &Entity) -> bool {
+        if !self.is_alive(entity) {
+            return false;
+        }
+
+        self.detach_from_parent(entity);
+
+        let mut entities_to_destroy = vec![*entity];
+
+        while let Some(entity_to_destroy) = entities_to_destroy.pop() {
+            entities_to_destroy.extend(self.children_of(&entity_to_destroy));
+
+            self.entity_manager.remove_entity(&entity_to_destroy);
+        }
+
+        true
+    }
+
+    /// Where the entity is in the terminal world: the sum of its `TerminalTransform` and those of all its ancestors.
+    /// Ancestors without a `TerminalTransform` don't contribute to the position.
+    pub(crate) fn terminal_world_coords(&self, entity: &Entity) -> IntCoords2d {
+        let mut coords = IntCoords2d::zero();
+        let mut current_entity = Some(*entity);
+
+        while let Some(entity) = current_entity {
+            if let Some(transform) = self
+                .entity_manager
+                .get_component::<TerminalTransform>(&entity)
+            {
+                coords += transform.coords;
+            }
+
+            current_entity = self
+                .entity_manager
+                .get_component::<Parent>(&entity)
+                .map(|parent| parent.entity);
+        }
+
+        coords
+    }
+
+    pub(crate) fn insert_resource(&mut self, resource: Box<dyn Resource>) {
+        self.resources.insert(resource);
+    }
+
+    pub(crate) fn remove_resource(&mut self, resource_id: &TypeId) -> bool {
+        self.resources.remove(resource_id)
+    }
+
+    /// Moves the world on to a new frame, dropping removed components and events that have outlived their lifetimes.
+    pub(crate) fn start_frame(&mut self) {
+        self.entity_manager.start_frame();
+        self.event_channels.start_frame();
+    }
+
+    pub(crate) fn event_channels(&self) -> &EventChannels {
+        &self.event_channels
+    }
+
+    pub(crate) fn event_channels_mut(&mut self) -> &mut EventChannels {
+        &mut self.event_channels
+    }
+
+    fn children_of(&self, entity: &Entity) -> Vec<Entity> {
+        self.entity_manager
+            .get_component::<Children>(entity)
+            .map(|children| children.entities.clone())
+            .unwrap_or_default()
+    }
+
+    fn is_self_or_ancestor(&self, ancestor: &Entity, entity: &Entity) -> bool {
+        let mut current_entity = Some(*entity);
+
+        while let Some(entity) = current_entity {
+            if entity == *ancestor {
+                return true;
+            }
+
+            current_entity = self
+                .entity_manager
+                .get_component::<Parent>(&entity)
+                .map(|parent| parent.entity);
+        }
+
+        false
+    }
+
+    /// Removes the entity's `Parent`, taking it out of its parent's `Children`.
+    fn detach_from_parent(&mut self, child: &Entity) {
+        let parent = match self.entity_manager.get_component::<Parent>(child) {
+            Some(parent) => parent.entity,
+            None => return,
+        };
+
+        let has_other_children = self
+            .entity_manager
+            .get_component_mut::<Children>(&parent)
+            .is_some_and(|mut children| {
+                children.entities.retain(|entity| entity != child);
+
+                !children.entities.is_empty()
+            });
+
+        if !has_other_children {
+            self.entity_manager
+                .remove_component_from_entity(&parent, Children::id());
+        }
+
+        self.entity_manager
+            .remove_component_from_entity(child, Parent::id());
+        self.entity_manager
+            .remove_component_from_entity(child, GlobalTerminalTransform::id());
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn component_registry(&self) -> &crate::ComponentRegistry {
+        &self.component_registry
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn component_registry_mut(&mut self) -> &mut crate::ComponentRegistry {
+        &mut self.component_registry
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn prefabs(&self) -> &crate::Prefabs {
+        &self.prefabs
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn add_prefabs(&mut self, prefabs: crate::Prefabs) {
+        self.prefabs.extend(prefabs);
+    }
+
+    pub(crate) fn entity_manager(&self) -> &EntityManager {
+        &self.entity_manager
+    }
+
+    pub(crate) fn entity_manager_mut(&mut self) -> &mut EntityManager {
+        &mut self.entity_manager
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Resource;
+
+    #[derive(Resource)]
+    struct Score {
+        value: u64,
+    }
+
+    mod test_resource {
+        use super::*;
+
+        #[test]
+        fn gives_back_the_resource() {
+            let mut world = World::new();
+
+            world.insert_resource(Box::new(Score { value: 10 }));
+
+            assert_eq!(world.resource::<Score>().value, 10);
+        }
+
+        #[test]
+        #[should_panic(
+            expected = "Resource Score was not present, or you're trying to borrow it while it's already mutably borrowed."
+        )]
+        fn panics_when_the_resource_is_not_present() {
+            let world = World::new();
+
+            world.resource::<Score>();
+        }
+    }
+
+    mod test_resource_mut {
+        use super::*;
+
+        #[test]
+        fn changes_are_kept_in_the_world() {
+            let mut world = World::new();
+
+            world.insert_resource(Box::new(Score { value: 10 }));
+
+            world.resource_mut::<Score>().value += 1;
+
+            assert_eq!(world.resource::<Score>().value, 11);
+        }
+
+        #[test]
+        #[should_panic(
+            expected = "Resource Score was not present, or you're trying to borrow it while it's already borrowed."
+        )]
+        fn panics_when_the_resource_is_already_borrowed() {
+            let mut world = World::new();
+
+            world.insert_resource(Box::new(Score { value: 10 }));
+
+            let _score = world.resource::<Score>();
+            world.resource_mut::<Score>();
+        }
+    }
+
+    mod test_has_component {
+        use super::*;
+
+        #[test]
+        fn is_true_only_for_components_the_entity_has() {
+            let mut world = World::new();
+
+            let entity = add_entity_at(&mut world, 0, 0);
+
+            assert!(world.has_component::<TerminalTransform>(&entity));
+            assert!(!world.has_component::<Parent>(&entity));
+        }
+
+        #[test]
+        fn is_false_when_the_entity_is_not_alive() {
+            let mut world = World::new();
+
+            let entity = add_entity_at(&mut world, 0, 0);
+            world.destroy_entity(&entity);
+
+            assert!(!world.has_component::<TerminalTransform>(&entity));
+        }
+    }
+
+    mod test_component {
+        use super::*;
+
+        #[test]
+        fn gives_back_the_entity_s_component() {
+            let mut world = World::new();
+
+            add_entity_at(&mut world, 1, 1);
+            let entity = add_entity_at(&mut world, 2, 3);
+
+            assert_eq!(
+                world.component::<TerminalTransform>(&entity).coords,
+                IntCoords2d::new(2, 3)
+            );
+        }
+
+        #[test]
+        #[should_panic(
+            expected = "Component thomas::components::comp_parent::Parent was not present on entity"
+        )]
+        fn panics_when_the_entity_does_not_have_the_component() {
+            let mut world = World::new();
+
+            let entity = add_entity_at(&mut world, 0, 0);
+
+            world.component::<Parent>(&entity);
+        }
+    }
+
+    mod test_component_mut {
+        use super::*;
+
+        #[test]
+        fn changes_are_kept_in_the_world() {
+            let mut world = World::new();
+
+            let entity = add_entity_at(&mut world, 0, 0);
+
+            world.component_mut::<TerminalTransform>(&entity).coords = IntCoords2d::new(4, 5);
+
+            assert_eq!(
+                world.component::<TerminalTransform>(&entity).coords,
+                IntCoords2d::new(4, 5)
+            );
+        }
+
+        #[test]
+        fn is_none_when_the_component_is_already_borrowed() {
+            let mut world = World::new();
+
+            let entity = add_entity_at(&mut world, 0, 0);
+
+            let _transform = world.component::<TerminalTransform>(&entity);
+
+            assert!(world
+                .try_component_mut::<TerminalTransform>(&entity)
+                .is_none());
+        }
+    }
+
+    fn add_entity_at(world: &mut World, x: i64, y: i64) -> Entity {
+        world
+            .entity_manager_mut()
+            .add_entity(vec![Box::new(TerminalTransform {
+                coords: IntCoords2d::new(x, y),
+            })])
+    }
+
+    fn children_of(world: &World, entity: &Entity) -> Option<Vec<Entity>> {
+        world
+            .entity_manager()
+            .get_component::<Children>(entity)
+            .map(|children| children.entities().clone())
+    }
+
+    mod test_set_parent {
+        use super::*;
+
+        #[test]
+        fn links_the_parent_and_child() {
+            let mut world = World::new();
+
+            let parent = add_entity_at(&mut world, 0, 0);
+            let child = add_entity_at(&mut world, 1, 1);
+
+            assert!(world.set_parent(&child, &parent));
+
+            assert_eq!(
+                *world
+                    .entity_manager()
+                    .get_component::<Parent>(&child)
+                    .unwrap()
+                    .entity(),
+                parent
+            );
+            assert_eq!(children_of(&world, &parent), Some(vec![child]));
+        }
+
+        #[test]
+        fn gives_the_child_its_world_position() {
+            let mut world = World::new();
+
+            let parent = add_entity_at(&mut world, 5, 2);
+            let child = add_entity_at(&mut world, 1, 1);
+
+            world.set_parent(&child, &parent);
+
+            assert_eq!(
+                *world
+                    .entity_manager()
+                    .get_component::<GlobalTerminalTransform>(&child)
+                    .unwrap()
+                    .coords(),
+                IntCoords2d::new(6, 3)
+            );
+        }
+
+        #[test]
+        fn moves_the_child_away_from_its_old_parent() {
+            let mut world = World::new();
+
+            let old_parent = add_entity_at(&mut world, 0, 0);
+            let new_parent = add_entity_at(&mut world, 0, 0);
+            let child = add_entity_at(&mut world, 0, 0);
+
+            world.set_parent(&child, &old_parent);
+            world.set_parent(&child, &new_parent);
+
+            assert_eq!(children_of(&world, &old_parent), None);
+            assert_eq!(children_of(&world, &new_parent), Some(vec![child]));
+        }
+
+        #[test]
+        fn an_entity_cannot_be_the_parent_of_its_ancestor() {
+            let mut world = World::new();
+
+            let grandparent = add_entity_at(&mut world, 0, 0);
+            let parent = add_entity_at(&mut world, 0, 0);
+            let child = add_entity_at(&mut world, 0, 0);
+
+            world.set_parent(&parent, &grandparent);
+            world.set_parent(&child, &parent);
+
+            assert!(!world.set_parent(&grandparent, &child));
+            assert!(!world.set_parent(&child, &child));
+            assert!(world
+                .entity_manager()
+                .get_component::<Parent>(&grandparent)
+                .is_none());
+        }
+    }
+
+    mod test_remove_parent {
+        use super::*;
+
+        #[test]
+        fn the_child_stays_where_it_was_in_the_world() {
+            let mut world = World::new();
+
+            let parent = add_entity_at(&mut world, 5, 2);
+            let child = add_entity_at(&mut world, 1, 1);
+
+            world.set_parent(&child, &parent);
+
+            assert!(world.remove_parent(&child));
+
+            assert_eq!(
+                world
+                    .entity_manager()
+                    .get_component::<TerminalTransform>(&child)
+                    .unwrap()
+                    .coords,
+                IntCoords2d::new(6, 3)
+            );
+            assert!(world
+                .entity_manager()
+                .get_component::<GlobalTerminalTransform>(&child)
+                .is_none());
+            assert_eq!(children_of(&world, &parent), None);
+        }
+
+        #[test]
+        fn is_false_when_the_entity_has_no_parent() {
+            let mut world = World::new();
+
+            let entity = add_entity_at(&mut world, 0, 0);
+
+            assert!(!world.remove_parent(&entity));
+        }
+    }
+
+    mod test_remove_component {
+        use super::*;
+
+        #[test]
+        fn removing_parent_takes_the_child_out_of_its_parents_children() {
+            let mut world = World::new();
+
+            let parent = add_entity_at(&mut world, 5, 2);
+            let first_child = add_entity_at(&mut world, 1, 1);
+            let second_child = add_entity_at(&mut world, 0, 0);
+
+            world.set_parent(&first_child, &parent);
+            world.set_parent(&second_child, &parent);
+
+            assert!(world.remove_component(&first_child, Parent::id()));
+
+            assert_eq!(children_of(&world, &parent), Some(vec![second_child]));
+            assert_eq!(
+                world
+                    .entity_manager()
+                    .get_component::<TerminalTransform>(&first_child)
+                    .unwrap()
+                    .coords,
+                IntCoords2d::new(6, 3)
+            );
+        }
+
+        #[test]
+        fn removing_children_takes_every_child_away_from_the_parent() {
+            let mut world = World::new();
+
+            let parent = add_entity_at(&mut world, 5, 2);
+            let child = add_entity_at(&mut world, 1, 1);
+
+            world.set_parent(&child, &parent);
+
+            assert!(world.remove_component(&parent, Children::id()));
+
+            assert_eq!(children_of(&world, &parent), None);
+            assert!(world
+                .entity_manager()
+                .get_component::<Parent>(&child)
+                .is_none());
+
+            world.destroy_entity_and_descendants(&parent);
+
+            assert!(world.is_alive(&child));
+        }
+
+        #[test]
+        fn does_not_remove_the_global_transform_of_a_child() {
+            let mut world = World::new();
+
+            let parent = add_entity_at(&mut world, 5, 2);
+            let child = add_entity_at(&mut world, 1, 1);
+
+            world.set_parent(&child, &parent);
+
+            assert!(!world.remove_component(&child, GlobalTerminalTransform::id()));
+            assert!(world
+                .entity_manager()
+                .get_component::<GlobalTerminalTransform>(&child)
+                .is_some());
+        }
+
+        #[test]
+        fn removes_other_components() {
+            let mut world = World::new();
+
+            let entity = add_entity_at(&mut world, 0, 0);
+
+            assert!(world.remove_component(&entity, TerminalTransform::id()));
+            assert!(!world.has_component::<TerminalTransform>(&entity));
+        }
+    }
+
+    mod test_destroy_entity {
+        use super::*;
+
+        #[test]
+        fn leaves_children_in_the_world_without_a_parent() {
+            let mut world = World::new();
+
+            let parent = add_entity_at(&mut world, 5, 2);
+            let child = add_entity_at(&mut world, 1, 1);
+
+            world.set_parent(&child, &parent);
+
+            assert!(world.destroy_entity(&parent));
+
+            assert!(!world.is_alive(&parent));
+            assert!(world.is_alive(&child));
+            assert!(world
+                .entity_manager()
+                .get_component::<Parent>(&child)
+                .is_none());
+            assert_eq!(world.terminal_world_coords(&child), IntCoords2d::new(6, 3));
+        }
+
+        #[test]
+        fn removes_the_entity_from_its_parents_children() {
+            let mut world = World::new();
+
+            let parent = add_entity_at(&mut world, 0, 0);
+            let child1 = add_entity_at(&mut world, 0, 0);
+            let child2 = add_entity_at(&mut world, 0, 0);
+
+            world.set_parent(&child1, &parent);
+            world.set_parent(&child2, &parent);
+
+            world.destroy_entity(&child1);
+
+            assert_eq!(children_of(&world, &parent), Some(vec![child2]));
+        }
+    }
+
+    mod test_destroy_entity_and_descendants {
+        use super::*;
+
+        #[test]
+        fn destroys_every_descendant() {
+            let mut world = World::new();
+
+            let parent = add_entity_at(&mut world, 0, 0);
+            let child = world
+                .add_child_entity(&parent, vec![])
+                .expect("Child is added.");
+            let grandchild = world
+                .add_child_entity(&child, vec![])
+                .expect("Grandchild is added.");
+            let unrelated = add_entity_at(&mut world, 0, 0);
+
+            assert!(world.destroy_entity_and_descendants(&child));
+
+            assert!(world.is_alive(&parent));
+            assert!(!world.is_alive(&child));
+            assert!(!world.is_alive(&grandchild));
+            assert!(world.is_alive(&unrelated));
+            assert_eq!(children_of(&world, &parent), None);
+        }
+    }
+
+    mod test_terminal_world_coords {
+        use super::*;
+
+        #[test]
+        fn adds_up_the_transforms_of_every_ancestor() {
+            let mut world = World::new();
+
+            let grandparent = add_entity_at(&mut world, 10, 10);
+            let parent = world
+                .add_child_entity(&grandparent, vec![])
+                .expect("Parent is added.");
+            let child = add_entity_at(&mut world, -1, 2);
+
+            world.set_parent(&child, &parent);
+
+            assert_eq!(world.terminal_world_coords(&child), IntCoords2d::new(9, 12));
+        }
+    }
+}
@@ -1,3 +1,5 @@
+extern crate self as thomas;
+
 mod core;
 pub use crate::core::*;
 
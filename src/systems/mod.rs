@@ -1,9 +1,15 @@
 mod sys_services;
 pub(crate) use sys_services::*;
 
+mod sys_hierarchy;
+pub(crate) use sys_hierarchy::*;
+
 mod sys_terminal_renderer;
 pub use sys_terminal_renderer::*;
 
+mod sys_headless_renderer;
+pub use sys_headless_renderer::*;
+
 mod sys_terminal_ui_renderer;
 pub use sys_terminal_ui_renderer::*;
 
@@ -12,3 +18,6 @@ pub use sys_terminal_collisions::*;
 
 mod sys_engine_analysis;
 pub use sys_engine_analysis::*;
+
+mod sys_inspector;
+pub use sys_inspector::*;
\ No newline at end of file
@@ -1,18 +1,27 @@
+use std::{cmp::Reverse, time::Duration};
+
 use crate::{
     Alignment, EngineStats, GameCommand, GameCommandsArg, Identity, IntCoords2d, Query,
-    QueryResultList, Rgb, System, SystemsGenerator, Text, Timer, UiAnchor, EVENT_BEFORE_UPDATE,
+    QueryResultList, RefMut, Rgb, System, SystemProfile, SystemsGenerator, Text, Time, Timer,
+    Timing, UiAnchor, World, EVENT_AFTER_UPDATE, EVENT_BEFORE_UPDATE, EVENT_FIXED_UPDATE,
     EVENT_INIT, EVENT_UPDATE,
 };
 
 pub const FPS_TRACKER_ID: &str = "thomas_fps_tracking_tag";
+pub const PROFILE_TRACKER_ID: &str = "thomas_profile_tracking_tag";
+
+/// How many of the slowest systems are listed by the tracking UI.
+const PROFILE_TRACKER_SYSTEMS_SHOWN: usize = 5;
 
 pub struct EngineAnalysisOptions {
-    /// Whether to include UI on the screen that will display current analysis stats.
+    /// Whether to include UI on the screen that will display current analysis stats. Along with the FPS, the UI shows
+    /// how long rendering and command processing take each frame, and which systems take the longest to run.
     pub include_tracking_ui: bool,
 }
 
 /// Generator responsible for setting up and performing engine performance analysis. To use the data generated by the
-/// analysis, you can query for the `EngineStats` component.
+/// analysis, you can get the `EngineStats` resource from the `World`. The resource is added in the init event, so it's
+/// available from the first frame onward.
 pub struct EngineAnalysisSystemsGenerator {
     options: EngineAnalysisOptions,
 }
@@ -26,19 +35,20 @@ impl SystemsGenerator for EngineAnalysisSystemsGenerator {
         let mut systems = vec![
             (
                 EVENT_INIT,
-                System::new(vec![], |_, commands| {
+                System::new(vec![], |_, commands, world| {
                     commands
                         .borrow_mut()
-                        .issue(GameCommand::AddEntity(vec![Box::new(EngineStats {
-                            fps: 0,
-                            frame_timer: Timer::new(),
-                            frame_counter: 0,
-                        })]));
-                }),
+                        .issue(GameCommand::InsertResource(Box::new(EngineStats::new(
+                            Timer::with_clock(world.resource::<Time>().clock()),
+                        ))));
+                })
+                .reads_resource::<Time>(),
             ),
             (
                 EVENT_BEFORE_UPDATE,
-                System::new(vec![Query::new().has::<EngineStats>()], gather_stats),
+                System::new(vec![], gather_stats)
+                    .with_name("thomas_engine_analysis")
+                    .writes_resource::<EngineStats>(),
             ),
         ];
 
@@ -46,7 +56,7 @@ impl SystemsGenerator for EngineAnalysisSystemsGenerator {
             systems.append(&mut vec![
                 (
                     EVENT_INIT,
-                    System::new(vec![], |_, commands| {
+                    System::new(vec![], |_, commands, _| {
                         commands.borrow_mut().issue(GameCommand::AddEntity(vec![
                             Box::new(Text {
                                 anchor: UiAnchor::TopLeft,
@@ -61,6 +71,23 @@ impl SystemsGenerator for EngineAnalysisSystemsGenerator {
                                 name: String::from("FPS Tracking Tag"),
                             }),
                         ]));
+
+                        for line in 1..=PROFILE_TRACKER_SYSTEMS_SHOWN + 2 {
+                            commands.borrow_mut().issue(GameCommand::AddEntity(vec![
+                                Box::new(Text {
+                                    anchor: UiAnchor::TopLeft,
+                                    justification: Alignment::Left,
+                                    offset: IntCoords2d::new(0, line as i64),
+                                    value: String::from(""),
+                                    foreground_color: Some(Rgb::magenta()),
+                                    background_color: None,
+                                }),
+                                Box::new(Identity {
+                                    id: String::from(PROFILE_TRACKER_ID),
+                                    name: String::from("Profile Tracking Tag"),
+                                }),
+                            ]));
+                        }
                     }),
                 ),
                 (
@@ -70,10 +97,14 @@ impl SystemsGenerator for EngineAnalysisSystemsGenerator {
                             Query::new()
                                 .has::<Text>()
                                 .has_where::<Identity>(|id| id.id == FPS_TRACKER_ID),
-                            Query::new().has::<EngineStats>(),
+                            Query::new()
+                                .has::<Text>()
+                                .has_where::<Identity>(|id| id.id == PROFILE_TRACKER_ID),
                         ],
                         update_tracking_ui,
-                    ),
+                    )
+                    .with_name("thomas_engine_analysis_ui")
+                    .reads_resource::<EngineStats>(),
                 ),
             ])
         }
@@ -82,30 +113,96 @@ impl SystemsGenerator for EngineAnalysisSystemsGenerator {
     }
 }
 
-fn gather_stats(results: Vec<QueryResultList>, _: GameCommandsArg) {
-    if let [engine_stats_results, ..] = &results[..] {
-        let mut engine_stats = engine_stats_results.get_only_mut::<EngineStats>();
+fn gather_stats(_: Vec<QueryResultList>, _: GameCommandsArg, world: &World) {
+    let mut engine_stats = world.resource_mut::<EngineStats>();
 
-        engine_stats.frame_counter += 1;
+    engine_stats.frame_counter += 1;
 
-        if !engine_stats.frame_timer.is_running() {
-            engine_stats.frame_timer.start();
-        }
+    if !engine_stats.frame_timer.is_running() {
+        engine_stats.frame_timer.start();
+    }
 
-        if engine_stats.frame_timer.elapsed_millis() >= 1000 {
-            engine_stats.fps = engine_stats.frame_counter;
-            engine_stats.frame_counter = 0;
+    if engine_stats.frame_timer.elapsed_millis() >= 1000 {
+        engine_stats.fps = engine_stats.frame_counter;
+        engine_stats.frame_counter = 0;
 
-            engine_stats.frame_timer.restart();
-        }
+        engine_stats.frame_timer.restart();
     }
 }
 
-fn update_tracking_ui(results: Vec<QueryResultList>, _: GameCommandsArg) {
-    if let [fps_tag_results, engine_stats_results, ..] = &results[..] {
+fn update_tracking_ui(results: Vec<QueryResultList>, _: GameCommandsArg, world: &World) {
+    if let [fps_tag_results, profile_tag_results, ..] = &results[..] {
         let mut fps_tag = fps_tag_results.get_only_mut::<Text>();
-        let stats = engine_stats_results.get_only::<EngineStats>();
+        let stats = world.resource::<EngineStats>();
 
         fps_tag.value = format!("FPS: {}", stats.fps);
+
+        let mut profile_tags: Vec<RefMut<Text>> = profile_tag_results
+            .iter()
+            .map(|result| result.components().get_mut::<Text>())
+            .collect();
+        profile_tags.sort_by_key(|text| text.offset.y());
+
+        for (text, line) in profile_tags.iter_mut().zip(make_profile_lines(&stats)) {
+            text.value = line;
+        }
     }
 }
+
+fn make_profile_lines(stats: &EngineStats) -> Vec<String> {
+    let command_processing: Vec<&Timing> = [
+        EVENT_BEFORE_UPDATE,
+        EVENT_FIXED_UPDATE,
+        EVENT_UPDATE,
+        EVENT_AFTER_UPDATE,
+    ]
+    .iter()
+    .filter_map(|event_name| stats.command_processing(event_name))
+    .collect();
+
+    let mut lines = vec![
+        format!("Render: {}", describe_timing(stats.rendering())),
+        format!(
+            "Commands: avg {}, max {}",
+            format_duration(
+                command_processing
+                    .iter()
+                    .map(|timing| timing.average())
+                    .sum()
+            ),
+            format_duration(command_processing.iter().map(|timing| timing.max()).sum()),
+        ),
+    ];
+
+    let mut systems: Vec<&SystemProfile> = stats.systems().collect();
+    systems.sort_by_key(|system| Reverse(system.average()));
+
+    lines.extend(
+        systems
+            .iter()
+            .take(PROFILE_TRACKER_SYSTEMS_SHOWN)
+            .map(|system| {
+                format!(
+                    "{} ({}): query {}, run {}",
+                    system.name(),
+                    system.event_name(),
+                    describe_timing(system.queries()),
+                    describe_timing(system.operator()),
+                )
+            }),
+    );
+
+    lines
+}
+
+fn describe_timing(timing: &Timing) -> String {
+    format!(
+        "avg {}, max {}",
+        format_duration(timing.average()),
+        format_duration(timing.max())
+    )
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.2}ms", duration.as_secs_f64() * 1000.0)
+}
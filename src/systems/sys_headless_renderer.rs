@@ -0,0 +1,144 @@
+use std::time::Instant;
+
+use crate::{
+    add_default_camera, render_cells, Dimensions2d, EngineStats, GlobalTerminalTransform,
+    Inspector, Matrix, Priority, Query, Resource, Rgb, System, SystemsGenerator, TerminalCamera,
+    TerminalRenderer, TerminalRendererOptions, TerminalTransform, EVENT_AFTER_UPDATE, EVENT_INIT,
+};
+
+/// A single cell of a `HeadlessFrame`: the character in the cell and its colors. A color of `None` means the
+/// terminal's default color would be used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeadlessFrameCell {
+    pub display: char,
+    pub foreground_color: Option<Rgb>,
+    pub background_color: Option<Rgb>,
+}
+
+/// The last frame drawn by the headless renderer. It holds exactly what the terminal renderer would have drawn, but in
+/// memory instead of on a terminal, so it can be checked by tests or anything else that can't use a real terminal.
+///
+/// The frame is drawn at the end of every frame. If there's no main camera, the frame is left as it was.
+#[derive(Resource, Debug)]
+pub struct HeadlessFrame {
+    cells: Matrix<HeadlessFrameCell>,
+    frames_drawn: u64,
+}
+impl HeadlessFrame {
+    pub(crate) fn new() -> Self {
+        Self {
+            cells: Matrix::new(Dimensions2d::new(0, 0), || HeadlessFrameCell {
+                display: ' ',
+                foreground_color: None,
+                background_color: None,
+            }),
+            frames_drawn: 0,
+        }
+    }
+
+    /// The dimensions of the frame, which match the main camera's field of view. Before anything is drawn, the frame
+    /// is empty.
+    pub fn dimensions(&self) -> &Dimensions2d {
+        self.cells.dimensions()
+    }
+
+    /// The cell at the provided position on screen, with (0, 0) as the top-left corner.
+    pub fn get(&self, x: u64, y: u64) -> Option<&HeadlessFrameCell> {
+        self.cells.get(x, y).map(|cell| cell.data())
+    }
+
+    /// The characters in the frame, one `String` per row.
+    pub fn lines(&self) -> Vec<String> {
+        (0..self.dimensions().height())
+            .map(|y| {
+                (0..self.dimensions().width())
+                    .filter_map(|x| self.get(x, y))
+                    .map(|cell| cell.display)
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// How many frames have been drawn since the game started.
+    pub fn frames_drawn(&self) -> u64 {
+        self.frames_drawn
+    }
+}
+
+/// Draws the world the same way the terminal renderer does, but into the `HeadlessFrame` resource rather than onto a
+/// terminal.
+pub(crate) struct HeadlessRendererSystemsGenerator {
+    options: TerminalRendererOptions,
+}
+impl HeadlessRendererSystemsGenerator {
+    pub(crate) fn new(options: TerminalRendererOptions) -> Self {
+        Self { options }
+    }
+}
+impl SystemsGenerator for HeadlessRendererSystemsGenerator {
+    fn generate(&self) -> Vec<(&'static str, System)> {
+        let options = self.options;
+
+        vec![
+            (
+                EVENT_INIT,
+                System::new_with_priority(Priority::highest(), vec![], move |_, commands, _| {
+                    add_default_camera(&options, commands);
+                }),
+            ),
+            (
+                EVENT_AFTER_UPDATE,
+                System::new_with_priority(
+                    Priority::lowest(),
+                    vec![
+                        Query::new()
+                            .has::<TerminalRenderer>()
+                            .has::<TerminalTransform>()
+                            .has_optional::<GlobalTerminalTransform>()
+                            .read_only(),
+                        Query::new()
+                            .has_where::<TerminalCamera>(|camera| camera.is_main)
+                            .has::<TerminalTransform>()
+                            .has_optional::<GlobalTerminalTransform>()
+                            .read_only(),
+                    ],
+                    move |results, _, world| {
+                        if let [renderables_results, main_camera_results, ..] = &results[..] {
+                            if let Some(camera_result) = main_camera_results.first() {
+                                let main_camera =
+                                    camera_result.components().get::<TerminalCamera>();
+                                let main_camera_transform = TerminalTransform {
+                                    coords: TerminalTransform::world_coords(
+                                        camera_result.components(),
+                                    ),
+                                };
+
+                                let mut frame = world.resource_mut::<HeadlessFrame>();
+                                let render_start = Instant::now();
+
+                                frame.cells = render_cells(
+                                    &main_camera,
+                                    &main_camera_transform,
+                                    renderables_results,
+                                    world.try_resource::<Inspector>().as_deref(),
+                                    &options,
+                                );
+                                frame.frames_drawn += 1;
+
+                                if let Some(mut engine_stats) =
+                                    world.try_resource_mut::<EngineStats>()
+                                {
+                                    engine_stats.record_rendering(render_start.elapsed());
+                                }
+                            }
+                        }
+                    },
+                )
+                .with_name("thomas_headless_renderer")
+                .writes_resource::<HeadlessFrame>()
+                .writes_resource::<EngineStats>()
+                .reads_resource::<Inspector>(),
+            ),
+        ]
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{
+    GameCommandsArg, GlobalTerminalTransform, Parent, Priority, Query, QueryResultList, System,
+    SystemsGenerator, TerminalTransform, World, EVENT_AFTER_UPDATE,
+};
+
+/// A generator responsible for keeping children positioned relative to their parents. This systems generator is
+/// included for you, you don't need to include it.
+///
+/// A child's `TerminalTransform` is its offset from its parent. Just before the terminal renderer draws, every child's
+/// `GlobalTerminalTransform` is updated to where that offset puts it in the world.
+pub(crate) struct HierarchySystemsGenerator {}
+impl HierarchySystemsGenerator {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+impl SystemsGenerator for HierarchySystemsGenerator {
+    fn generate(&self) -> Vec<(&'static str, System)> {
+        vec![(
+            EVENT_AFTER_UPDATE,
+            System::new_with_priority(
+                Priority::higher_than(&Priority::lowest()),
+                vec![Query::new()
+                    .has::<Parent>()
+                    .has::<GlobalTerminalTransform>()],
+                propagate_terminal_transforms,
+            )
+            .with_name("thomas_hierarchy")
+            .reads_component::<TerminalTransform>()
+            .reads_component::<Parent>(),
+        )]
+    }
+}
+
+fn propagate_terminal_transforms(results: Vec<QueryResultList>, _: GameCommandsArg, world: &World) {
+    if let [children_results, ..] = &results[..] {
+        for child_result in children_results {
+            let coords = world.terminal_world_coords(child_result.entity());
+
+            if child_result
+                .components()
+                .get::<GlobalTerminalTransform>()
+                .coords
+                != coords
+            {
+                child_result
+                    .components()
+                    .get_mut::<GlobalTerminalTransform>()
+                    .coords = coords;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameCommandQueue, IntCoords2d, Shared, SharedCell};
+
+    mod test_propagate_terminal_transforms {
+        use super::*;
+
+        #[test]
+        fn children_follow_their_parent() {
+            let mut world = World::new();
+
+            let parent = world
+                .entity_manager_mut()
+                .add_entity(vec![Box::new(TerminalTransform {
+                    coords: IntCoords2d::new(0, 0),
+                })]);
+            let child = world
+                .add_child_entity(
+                    &parent,
+                    vec![Box::new(TerminalTransform {
+                        coords: IntCoords2d::new(1, 0),
+                    })],
+                )
+                .expect("Child is added.");
+
+            world
+                .entity_manager()
+                .get_component_mut::<TerminalTransform>(&parent)
+                .unwrap()
+                .coords = IntCoords2d::new(3, 4);
+
+            let query = Query::new()
+                .has::<Parent>()
+                .has::<GlobalTerminalTransform>();
+
+            propagate_terminal_transforms(
+                vec![world.entity_manager().query(&query)],
+                Shared::new(SharedCell::new(GameCommandQueue::new())),
+                &world,
+            );
+
+            assert_eq!(
+                *world
+                    .entity_manager()
+                    .get_component::<GlobalTerminalTransform>(&child)
+                    .unwrap()
+                    .coords(),
+                IntCoords2d::new(4, 4)
+            );
+        }
+    }
+}
@@ -0,0 +1,381 @@
+use device_query::Keycode;
+
+use crate::{
+    short_type_name, Entity, GameCommand, Identity, Input, IntCoords2d, Priority, Resource, Rgb,
+    System, SystemsGenerator, TerminalTextCharacter, TerminalTransform, World, EVENT_AFTER_UPDATE,
+    EVENT_INIT,
+};
+
+/// How many entities are listed at once, centered on the selected one.
+const INSPECTOR_ENTITY_LIST_SIZE: usize = 5;
+
+pub struct InspectorOptions {
+    /// The key that opens and closes the inspector.
+    pub toggle_key: Keycode,
+    /// The key that selects the entity before the selected one while the inspector is open.
+    pub previous_entity_key: Keycode,
+    /// The key that selects the entity after the selected one while the inspector is open.
+    pub next_entity_key: Keycode,
+    /// The background color the selected entity is drawn with while the inspector is open.
+    pub highlight_color: Rgb,
+}
+
+/// The state of the inspector added by the `InspectorSystemsGenerator`. The inspector can be opened and closed, and
+/// have its selection changed, from your own systems as well as with the keyboard.
+#[derive(Resource)]
+pub struct Inspector {
+    is_open: bool,
+    selected: Option<Entity>,
+    pub(crate) lines: Vec<String>,
+    pub(crate) highlighted_coords: Option<IntCoords2d>,
+    pub(crate) highlight_color: Rgb,
+}
+impl Inspector {
+    pub(crate) fn new(highlight_color: Rgb) -> Self {
+        Self {
+            is_open: false,
+            selected: None,
+            lines: vec![],
+            highlighted_coords: None,
+            highlight_color,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    /// Opens the inspector. It's drawn from the end of the frame onward.
+    pub fn open(&mut self) {
+        self.is_open = true;
+    }
+
+    pub fn close(&mut self) {
+        self.is_open = false;
+    }
+
+    pub fn selected(&self) -> Option<Entity> {
+        self.selected
+    }
+
+    /// Selects the entity. If the entity isn't alive by the time the inspector next updates, the first entity in the
+    /// world is selected instead.
+    pub fn select(&mut self, entity: Entity) {
+        self.selected = Some(entity);
+    }
+
+    /// What the inspector is showing, one `String` per line. Empty while the inspector is closed.
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+}
+
+/// Adds an inspector to the game: an overlay drawn above everything else that lists the entities in the world, and
+/// shows the components and field values of the selected one. The selected entity is highlighted on screen. Useful
+/// for seeing what's going on in a running terminal game, where printing anything would mess up the screen.
+///
+/// Fields whose types implement `ReflectField` show their values, and other fields show their types. The entities
+/// Thomas makes to draw `Text` and `WorldText` aren't listed. The inspector is updated at the end of every frame, and
+/// its state is kept in the `Inspector` resource.
+pub struct InspectorSystemsGenerator {
+    options: InspectorOptions,
+}
+impl InspectorSystemsGenerator {
+    pub fn new(options: InspectorOptions) -> Self {
+        Self { options }
+    }
+}
+impl SystemsGenerator for InspectorSystemsGenerator {
+    fn generate(&self) -> Vec<(&'static str, System)> {
+        let InspectorOptions {
+            toggle_key,
+            previous_entity_key,
+            next_entity_key,
+            highlight_color,
+        } = self.options;
+
+        vec![
+            (
+                EVENT_INIT,
+                System::new(vec![], move |_, commands, _| {
+                    commands
+                        .borrow_mut()
+                        .issue(GameCommand::InsertResource(Box::new(Inspector::new(
+                            highlight_color,
+                        ))));
+                }),
+            ),
+            (
+                EVENT_AFTER_UPDATE,
+                System::new_with_priority(
+                    Priority::higher_than(&Priority::lowest()),
+                    vec![],
+                    move |_, _, world| {
+                        let input = world.resource::<Input>();
+                        let mut inspector = world.resource_mut::<Inspector>();
+
+                        if input.is_key_down(&toggle_key) {
+                            inspector.is_open = !inspector.is_open;
+                        }
+
+                        if inspector.is_open {
+                            update_inspector(
+                                &mut inspector,
+                                world,
+                                input.is_key_down(&previous_entity_key),
+                                input.is_key_down(&next_entity_key),
+                            );
+                        } else {
+                            inspector.lines.clear();
+                            inspector.highlighted_coords = None;
+                        }
+                    },
+                )
+                .with_name("thomas_inspector")
+                .exclusive(),
+            ),
+        ]
+    }
+}
+
+fn update_inspector(
+    inspector: &mut Inspector,
+    world: &World,
+    select_previous: bool,
+    select_next: bool,
+) {
+    let entities: Vec<Entity> = world
+        .entity_manager()
+        .entities()
+        .into_iter()
+        .filter(|entity| {
+            world
+                .entity_manager()
+                .get_component::<TerminalTextCharacter>(entity)
+                .is_none()
+        })
+        .collect();
+
+    let selected_index = inspector
+        .selected
+        .and_then(|selected| entities.iter().position(|entity| *entity == selected));
+    let selected_index = match selected_index {
+        _ if entities.is_empty() => None,
+        Some(index) if select_previous => Some((index + entities.len() - 1) % entities.len()),
+        Some(index) if select_next => Some((index + 1) % entities.len()),
+        Some(index) => Some(index),
+        None => Some(0),
+    };
+
+    inspector.selected = selected_index.map(|index| entities[index]);
+    inspector.lines = make_lines(world, &entities, selected_index);
+    inspector.highlighted_coords = inspector.selected.and_then(|selected| {
+        world
+            .entity_manager()
+            .get_component::<TerminalTransform>(&selected)
+            .is_some()
+            .then(|| world.terminal_world_coords(&selected))
+    });
+}
+
+fn make_lines(world: &World, entities: &[Entity], selected_index: Option<usize>) -> Vec<String> {
+    let selected_index = match selected_index {
+        Some(selected_index) => selected_index,
+        None => return vec![String::from("Inspector: no entities")],
+    };
+
+    let mut lines = vec![format!(
+        "Inspector: entity {} of {}",
+        selected_index + 1,
+        entities.len()
+    )];
+
+    let list_start = selected_index
+        .saturating_sub(INSPECTOR_ENTITY_LIST_SIZE / 2)
+        .min(entities.len().saturating_sub(INSPECTOR_ENTITY_LIST_SIZE));
+
+    for (index, entity) in entities
+        .iter()
+        .enumerate()
+        .skip(list_start)
+        .take(INSPECTOR_ENTITY_LIST_SIZE)
+    {
+        let marker = if index == selected_index { '>' } else { ' ' };
+
+        lines.push(format!(
+            "{marker} {}: {}",
+            entity.id(),
+            describe_entity(world, entity)
+        ));
+    }
+
+    for component in world
+        .entity_manager()
+        .get_all_components(&entities[selected_index])
+    {
+        let component = component.borrow();
+
+        lines.push(short_type_name(component.component_name()));
+
+        for field in component.fields() {
+            let value = match component.field(field.name()) {
+                Some(value) => value.to_string(),
+                None => format!("<{}>", field.type_name()),
+            };
+
+            lines.push(format!("  {}: {value}", field.name()));
+        }
+    }
+
+    lines
+}
+
+/// The entity's `Identity` name if it has one, otherwise the names of its components.
+fn describe_entity(world: &World, entity: &Entity) -> String {
+    if let Some(identity) = world.entity_manager().get_component::<Identity>(entity) {
+        return identity.name.clone();
+    }
+
+    world
+        .entity_manager()
+        .get_all_components(entity)
+        .iter()
+        .map(|component| short_type_name(component.borrow().component_name()))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Component, Dimensions2d, Game, GameOptions, HeadlessFrame, Layer, Renderer, SteppedGame,
+        TerminalRenderer, TerminalRendererOptions,
+    };
+    use std::time::Duration;
+
+    #[derive(Component)]
+    struct Health {
+        value: u32,
+    }
+
+    fn make_game() -> SteppedGame {
+        Game::new(GameOptions {
+            press_escape_to_quit: false,
+            max_frame_rate: 60,
+        })
+        .add_systems_from_generator(InspectorSystemsGenerator::new(InspectorOptions {
+            toggle_key: Keycode::F1,
+            previous_entity_key: Keycode::PageUp,
+            next_entity_key: Keycode::PageDown,
+            highlight_color: Rgb::red(),
+        }))
+        .add_init_system(System::new(vec![], |_, commands, _| {
+            commands.borrow_mut().issue(GameCommand::AddEntity(vec![
+                Box::new(Identity {
+                    id: String::from("player"),
+                    name: String::from("Player"),
+                }),
+                Box::new(Health { value: 7 }),
+                Box::new(TerminalTransform {
+                    coords: IntCoords2d::new(70, 1),
+                }),
+                Box::new(TerminalRenderer {
+                    display: '@',
+                    layer: Layer::base(),
+                    foreground_color: None,
+                    background_color: None,
+                }),
+            ]));
+        }))
+        .start_stepped(Renderer::Headless(TerminalRendererOptions {
+            screen_resolution: Dimensions2d::new(12, 80),
+            include_default_camera: true,
+            default_foreground_color: None,
+            default_background_color: None,
+        }))
+    }
+
+    /// Taps the key, then steps another frame so the key is let go before it's tapped again.
+    fn tap_and_step(game: &mut SteppedGame, key: Keycode) {
+        game.world().resource_mut::<Input>().tap_key(key);
+        game.step_frames(2, Duration::from_millis(16));
+    }
+
+    mod test_inspector_systems_generator {
+        use super::*;
+
+        #[test]
+        fn shows_nothing_until_it_is_opened() {
+            let mut game = make_game();
+
+            game.step(Duration::from_millis(16));
+
+            assert!(!game.world().resource::<Inspector>().is_open());
+            assert!(game.world().resource::<Inspector>().lines().is_empty());
+        }
+
+        #[test]
+        fn shows_the_selected_entitys_fields() {
+            let mut game = make_game();
+
+            tap_and_step(&mut game, Keycode::F1);
+            tap_and_step(&mut game, Keycode::PageDown);
+
+            let lines = game.world().resource::<Inspector>().lines().to_vec();
+
+            assert!(lines
+                .iter()
+                .any(|line| line.starts_with('>') && line.ends_with(": Player")));
+            assert!(lines.contains(&String::from("Health")));
+            assert!(lines.contains(&String::from("  value: 7")));
+            assert!(lines.contains(&String::from("  coords: (70, 1)")));
+        }
+
+        #[test]
+        fn toggles_closed() {
+            let mut game = make_game();
+
+            tap_and_step(&mut game, Keycode::F1);
+            tap_and_step(&mut game, Keycode::F1);
+
+            assert!(!game.world().resource::<Inspector>().is_open());
+            assert!(game.world().resource::<Inspector>().lines().is_empty());
+        }
+
+        #[test]
+        fn the_selection_wraps_around() {
+            let mut game = make_game();
+
+            tap_and_step(&mut game, Keycode::F1);
+
+            let first = game.world().resource::<Inspector>().selected();
+
+            tap_and_step(&mut game, Keycode::PageDown);
+
+            assert_ne!(game.world().resource::<Inspector>().selected(), first);
+
+            tap_and_step(&mut game, Keycode::PageDown);
+
+            assert_eq!(game.world().resource::<Inspector>().selected(), first);
+
+            tap_and_step(&mut game, Keycode::PageUp);
+
+            assert_ne!(game.world().resource::<Inspector>().selected(), first);
+        }
+
+        #[test]
+        fn is_drawn_over_the_world_and_highlights_the_selection() {
+            let mut game = make_game();
+
+            tap_and_step(&mut game, Keycode::F1);
+            tap_and_step(&mut game, Keycode::PageDown);
+
+            let frame = game.world().resource::<HeadlessFrame>();
+
+            assert!(frame.lines()[0].starts_with("Inspector: entity 2 of 2"));
+            assert_eq!(frame.get(70, 1).unwrap().display, '@');
+            assert_eq!(frame.get(70, 1).unwrap().background_color, Some(Rgb::red()));
+        }
+    }
+}
@@ -1,9 +1,9 @@
-use std::{cell::Ref, collections::HashMap};
+use std::collections::HashMap;
 
 use crate::{
-    Entity, GameCommand, GameCommandsArg, IntCoords2d, Query, QueryResultList, System,
-    SystemsGenerator, TerminalCollider, TerminalCollision, TerminalTransform, EVENT_AFTER_UPDATE,
-    EVENT_BEFORE_UPDATE,
+    Entity, GameCommand, GameCommandsArg, GlobalTerminalTransform, IntCoords2d, Query,
+    QueryResultList, Ref, System, SystemsGenerator, TerminalCollider, TerminalCollision,
+    TerminalTransform, World, EVENT_AFTER_UPDATE, EVENT_BEFORE_UPDATE,
 };
 
 /// A generator responsible for setting up and performing collision detection between active `TerminalCollider`s in
@@ -27,29 +27,32 @@ impl SystemsGenerator for TerminalCollisionsSystemsGenerator {
                 System::new(
                     vec![Query::new()
                         .has_where::<TerminalCollider>(|collider| collider.is_active)
-                        .has::<TerminalTransform>()],
+                        .has::<TerminalTransform>()
+                        .has_optional::<GlobalTerminalTransform>()],
                     detect_collisions,
-                ),
+                )
+                .with_name("thomas_detect_collisions"),
             ),
             (
                 EVENT_AFTER_UPDATE,
                 System::new(
                     vec![Query::new().has::<TerminalCollision>()],
                     cleanup_collisions,
-                ),
+                )
+                .with_name("thomas_cleanup_collisions"),
             ),
         ]
     }
 }
 
-fn detect_collisions(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+fn detect_collisions(results: Vec<QueryResultList>, commands: GameCommandsArg, _: &World) {
     if let [bodies_query, ..] = &results[..] {
         let mut used_coords: HashMap<String, Vec<(&Entity, Ref<TerminalCollider>)>> =
             HashMap::new();
 
         for body in bodies_query {
             let collider = body.components().get::<TerminalCollider>();
-            let coords = body.components().get::<TerminalTransform>().coords;
+            let coords = TerminalTransform::world_coords(body.components());
             let entity = body.entity();
             let hash_string = get_coords_hash_string(&coords);
 
@@ -72,7 +75,7 @@ fn detect_collisions(results: Vec<QueryResultList>, commands: GameCommandsArg) {
     }
 }
 
-fn cleanup_collisions(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+fn cleanup_collisions(results: Vec<QueryResultList>, commands: GameCommandsArg, _: &World) {
     if let [collision_query, ..] = &results[..] {
         for collision_result in collision_query {
             commands
@@ -1,6 +1,7 @@
 use std::{
     io::stdout,
     ops::{Deref, DerefMut},
+    time::Instant,
 };
 
 use crossterm::{
@@ -10,14 +11,15 @@ use crossterm::{
 };
 
 use crate::{
-    Component, Dimensions2d, GameCommand, IntCoords2d, Layer, Matrix, Priority, Query,
-    QueryResultList, Rgb, System, SystemsGenerator, TerminalCamera, TerminalRenderer,
-    TerminalTransform, EVENT_AFTER_UPDATE, EVENT_CLEANUP, EVENT_INIT,
+    Dimensions2d, EngineStats, GameCommand, GameCommandsArg, GlobalTerminalTransform,
+    HeadlessFrameCell, Inspector, IntCoords2d, Layer, Matrix, Priority, Query, QueryResultList,
+    Resource, Rgb, System, SystemsGenerator, TerminalCamera, TerminalRenderer, TerminalTransform,
+    EVENT_AFTER_UPDATE, EVENT_CLEANUP, EVENT_INIT,
 };
 
 const TERMINAL_DIMENSIONS_PADDING: u16 = 0;
 
-#[derive(Component, Debug)]
+#[derive(Resource, Debug)]
 pub struct TerminalRendererState {
     initial_terminal_size: (u16, u16),
     pub options: TerminalRendererOptions,
@@ -46,83 +48,56 @@ impl SystemsGenerator for TerminalRendererSystemsGenerator {
                 EVENT_INIT,
                 System::new_with_priority(
                     Priority::highest(),
-                    vec![Query::new().has::<TerminalRendererState>()],
-                    move |results, commands| {
-                        if let [state_query, ..] = &results[..] {
-                            assert!(
-                                state_query.len() == 1,
-                                "There must be exactly 1 {} in the game. Found {}",
-                                TerminalRendererState::name(),
-                                state_query.len()
-                            );
+                    vec![],
+                    move |_, commands, world| {
+                        let mut state = world.resource_mut::<TerminalRendererState>();
 
-                            let mut state = state_query
-                                .get(0)
-                                .expect(&format!(
-                                    "There is a {} available in the world at init.",
-                                    TerminalRendererState::name()
-                                ))
-                                .components()
-                                .get_mut::<TerminalRendererState>();
-
-                            if let Ok(size) = terminal::size() {
-                                state.initial_terminal_size = size;
-                            } else {
-                                panic!(
-                                    "TerminalRenderer could not get the terminal's starting size."
-                                );
-                            }
+                        if let Ok(size) = terminal::size() {
+                            state.initial_terminal_size = size;
+                        } else {
+                            panic!("TerminalRenderer could not get the terminal's starting size.");
+                        }
 
-                            if state.options.screen_resolution.height()
+                        if state.options.screen_resolution.height()
+                            + TERMINAL_DIMENSIONS_PADDING as u64
+                            > u16::MAX as u64
+                            || state.options.screen_resolution.width()
                                 + TERMINAL_DIMENSIONS_PADDING as u64
                                 > u16::MAX as u64
-                                || state.options.screen_resolution.width()
-                                    + TERMINAL_DIMENSIONS_PADDING as u64
-                                    > u16::MAX as u64
-                            {
-                                panic!("TerminalRenderer's screen resolution is too large. Neither the width nor height can be greater than {}", u16::MAX - TERMINAL_DIMENSIONS_PADDING);
-                            }
-
-                            if let Err(e) = execute!(
-                                stdout(),
-                                Clear(ClearType::All),
-                                SetSize(
-                                    state.options.screen_resolution.width() as u16
-                                        + TERMINAL_DIMENSIONS_PADDING,
-                                    state.options.screen_resolution.height() as u16
-                                        + TERMINAL_DIMENSIONS_PADDING
-                                ),
-                                cursor::Hide,
-                                cursor::SavePosition,
-                                cursor::MoveTo(0, 0),
-                            ) {
-                                panic!(
+                        {
+                            panic!("TerminalRenderer's screen resolution is too large. Neither the width nor height can be greater than {}", u16::MAX - TERMINAL_DIMENSIONS_PADDING);
+                        }
+
+                        if let Err(e) = execute!(
+                            stdout(),
+                            Clear(ClearType::All),
+                            SetSize(
+                                state.options.screen_resolution.width() as u16
+                                    + TERMINAL_DIMENSIONS_PADDING,
+                                state.options.screen_resolution.height() as u16
+                                    + TERMINAL_DIMENSIONS_PADDING
+                            ),
+                            cursor::Hide,
+                            cursor::SavePosition,
+                            cursor::MoveTo(0, 0),
+                        ) {
+                            panic!(
                                         "TerminalRenderer could not do initial setup of game screen. Error: {}",
                                         e
                                     );
-                            }
+                        }
 
-                            if let Err(e) = enable_raw_mode() {
-                                panic!(
+                        if let Err(e) = enable_raw_mode() {
+                            panic!(
                                         "TerminalRenderer could not set raw mode, cannot continue. Error: {}",
                                         e
                                     );
-                            }
-
-                            if state.options.include_default_camera {
-                                commands.borrow_mut().issue(GameCommand::AddEntity(vec![
-                                    Box::new(TerminalCamera {
-                                        field_of_view: state.options.screen_resolution.clone(),
-                                        is_main: true,
-                                    }),
-                                    Box::new(TerminalTransform {
-                                        coords: IntCoords2d::zero(),
-                                    }),
-                                ]))
-                            }
                         }
+
+                        add_default_camera(&state.options, commands);
                     },
-                ),
+                )
+                .writes_resource::<TerminalRendererState>(),
             ),
             (
                 EVENT_AFTER_UPDATE,
@@ -131,23 +106,27 @@ impl SystemsGenerator for TerminalRendererSystemsGenerator {
                     vec![
                         Query::new()
                             .has::<TerminalRenderer>()
-                            .has::<TerminalTransform>(),
-                        Query::new().has::<TerminalRendererState>(),
+                            .has::<TerminalTransform>()
+                            .has_optional::<GlobalTerminalTransform>()
+                            .read_only(),
                         Query::new()
                             .has_where::<TerminalCamera>(|camera| camera.is_main)
-                            .has::<TerminalTransform>(),
+                            .has::<TerminalTransform>()
+                            .has_optional::<GlobalTerminalTransform>()
+                            .read_only(),
                     ],
-                    move |results, _| {
-                        if let [renderables_results, state_results, main_camera_results, ..] =
-                            &results[..]
-                        {
-                            let mut state = state_results.get_only_mut::<TerminalRendererState>();
+                    move |results, _, world| {
+                        if let [renderables_results, main_camera_results, ..] = &results[..] {
+                            let mut state = world.resource_mut::<TerminalRendererState>();
 
                             if let Some(camera_result) = main_camera_results.get(0) {
                                 let main_camera =
                                     camera_result.components().get::<TerminalCamera>();
-                                let main_camera_transform =
-                                    camera_result.components().get::<TerminalTransform>();
+                                let main_camera_transform = TerminalTransform {
+                                    coords: TerminalTransform::world_coords(
+                                        camera_result.components(),
+                                    ),
+                                };
 
                                 if main_camera.field_of_view.width()
                                     > state.options.screen_resolution.width()
@@ -162,58 +141,57 @@ impl SystemsGenerator for TerminalRendererSystemsGenerator {
                                     );
                                 }
 
+                                let render_start = Instant::now();
+
                                 state.prev_render = Some(draw(
                                     &*main_camera,
-                                    &*main_camera_transform,
+                                    &main_camera_transform,
                                     &renderables_results,
+                                    world.try_resource::<Inspector>().as_deref(),
                                     &state.options,
                                     &state.prev_render,
                                 ));
+
+                                if let Some(mut engine_stats) =
+                                    world.try_resource_mut::<EngineStats>()
+                                {
+                                    engine_stats.record_rendering(render_start.elapsed());
+                                }
                             }
                         }
                     },
-                ),
+                )
+                .with_name("thomas_terminal_renderer")
+                .writes_resource::<TerminalRendererState>()
+                .writes_resource::<EngineStats>()
+                .reads_resource::<Inspector>(),
             ),
             (
                 EVENT_CLEANUP,
-                System::new(
-                    vec![Query::new().has::<TerminalRendererState>()],
-                    |results, _| {
-                        if let [state_query, ..] = &results[..] {
-                            let state = state_query
-                                .get(0)
-                                .expect(&format!(
-                                    "The {} component is available on cleanup.",
-                                    TerminalRendererState::name()
-                                ))
-                                .components()
-                                .get::<TerminalRendererState>();
-
-                            let error_message =
+                System::new(vec![], |_, _, world| {
+                    let state = world.resource::<TerminalRendererState>();
+
+                    let error_message =
                             "The terminal may be in a bad state. It's recommended you don't continue to use this terminal instance.";
 
-                            if let Err(e) = execute!(
-                                stdout(),
-                                SetSize(
-                                    state.initial_terminal_size.0,
-                                    state.initial_terminal_size.1
-                                ),
-                                cursor::Show,
-                                cursor::RestorePosition,
-                                ResetColor,
-                                Clear(ClearType::All),
-                            ) {
-                                println!("Could not reset terminal size and cursor visibility. {error_message} Error: {e}");
-                            }
+                    if let Err(e) = execute!(
+                        stdout(),
+                        SetSize(state.initial_terminal_size.0, state.initial_terminal_size.1),
+                        cursor::Show,
+                        cursor::RestorePosition,
+                        ResetColor,
+                        Clear(ClearType::All),
+                    ) {
+                        println!("Could not reset terminal size and cursor visibility. {error_message} Error: {e}");
+                    }
 
-                            if let Err(e) = disable_raw_mode() {
-                                println!("Could not disable raw mode. {error_message} Error: {e}");
-                            }
+                    if let Err(e) = disable_raw_mode() {
+                        println!("Could not disable raw mode. {error_message} Error: {e}");
+                    }
 
-                            println!("Thanks for playing a game powered by Thomas!");
-                        }
-                    },
-                ),
+                    println!("Thanks for playing a game powered by Thomas!");
+                })
+                .reads_resource::<TerminalRendererState>(),
             ),
         ]
     }
@@ -223,6 +201,7 @@ fn draw(
     main_camera: &TerminalCamera,
     main_camera_transform: &TerminalTransform,
     renderables_query_result: &QueryResultList,
+    inspector: Option<&Inspector>,
     renderer_options: &TerminalRendererOptions,
     previous_render: &Option<TerminalRendererMatrix>,
 ) -> TerminalRendererMatrix {
@@ -230,6 +209,7 @@ fn draw(
         main_camera,
         main_camera_transform,
         renderables_query_result,
+        inspector,
         renderer_options,
     );
 
@@ -334,10 +314,67 @@ fn get_crossterm_color(color_option: &Option<Rgb>, default_color_option: &Option
     }
 }
 
+/// Adds a main camera that sees the whole screen, if the options ask for one.
+pub(crate) fn add_default_camera(options: &TerminalRendererOptions, commands: GameCommandsArg) {
+    if options.include_default_camera {
+        commands.borrow_mut().issue(GameCommand::AddEntity(vec![
+            Box::new(TerminalCamera {
+                field_of_view: options.screen_resolution,
+                is_main: true,
+            }),
+            Box::new(TerminalTransform {
+                coords: IntCoords2d::zero(),
+            }),
+        ]));
+    }
+}
+
+/// Works out what the main camera sees without drawing anything, giving the character and colors for each cell on
+/// screen.
+pub(crate) fn render_cells(
+    main_camera: &TerminalCamera,
+    main_camera_transform: &TerminalTransform,
+    renderables_query_result: &QueryResultList,
+    inspector: Option<&Inspector>,
+    renderer_options: &TerminalRendererOptions,
+) -> Matrix<HeadlessFrameCell> {
+    let render_matrix = make_render_matrix(
+        main_camera,
+        main_camera_transform,
+        renderables_query_result,
+        inspector,
+        renderer_options,
+    );
+
+    let mut cells = Matrix::new(*render_matrix.dimensions(), || HeadlessFrameCell {
+        display: ' ',
+        foreground_color: None,
+        background_color: None,
+    });
+
+    for cell in &*render_matrix {
+        let (x, y) = cell.location().values();
+        let cell_data = get_cell_data_to_display(cell.data());
+
+        cells.update_cell_at(
+            x as u64,
+            y as u64,
+            HeadlessFrameCell {
+                display: cell_data.display,
+                foreground_color: cell_data.foreground_color,
+                background_color: cell_data.background_color,
+            },
+        );
+    }
+
+    cells
+}
+
 fn make_render_matrix(
     main_camera: &TerminalCamera,
     main_camera_transform: &TerminalTransform,
     renderables_query_result: &QueryResultList,
+    inspector: Option<&Inspector>,
     renderer_options: &TerminalRendererOptions,
 ) -> TerminalRendererMatrix {
     let mut render_matrix = TerminalRendererMatrix::new(
@@ -347,7 +384,7 @@ fn make_render_matrix(
     );
 
     for result in renderables_query_result {
-        let renderable_transform = result.components().get::<TerminalTransform>();
+        let renderable_coords = TerminalTransform::world_coords(result.components());
 
         let TerminalRenderer {
             display,
@@ -356,10 +393,10 @@ fn make_render_matrix(
             background_color,
         } = &*result.components().get::<TerminalRenderer>();
 
-        if is_renderable_visible(main_camera, main_camera_transform, &*renderable_transform) {
+        if is_renderable_visible(main_camera, main_camera_transform, &renderable_coords) {
             let renderable_screen_position = convert_world_position_to_screen_position(
                 main_camera_transform,
-                &renderable_transform.coords,
+                &renderable_coords,
             );
             let (x, y) = (
                 renderable_screen_position.x() as u64,
@@ -377,18 +414,69 @@ fn make_render_matrix(
         }
     }
 
+    if let Some(inspector) = inspector {
+        add_inspector_overlay(&mut render_matrix, inspector, main_camera_transform);
+    }
+
     render_matrix
 }
 
+/// Draws the inspector above everything else: the selected entity gets the highlight color as its background, and the
+/// inspector's lines are drawn as a panel in the top-left corner of the screen.
+fn add_inspector_overlay(
+    render_matrix: &mut TerminalRendererMatrix,
+    inspector: &Inspector,
+    main_camera_transform: &TerminalTransform,
+) {
+    if let Some(highlighted_coords) = &inspector.highlighted_coords {
+        let screen_position =
+            convert_world_position_to_screen_position(main_camera_transform, highlighted_coords);
+
+        if screen_position.x() >= 0 && screen_position.y() >= 0 {
+            if let Some(cell) =
+                render_matrix.get_mut(screen_position.x() as u64, screen_position.y() as u64)
+            {
+                let highlighted_item = TerminalRendererMatrixCellItem {
+                    layer_of_value: Layer::furthest_foreground(),
+                    background_color: Some(inspector.highlight_color),
+                    ..get_cell_data_to_display(cell.data())
+                };
+
+                cell.data_mut().push(highlighted_item);
+            }
+        }
+    }
+
+    let panel_width = inspector
+        .lines
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    for (y, line) in inspector.lines.iter().enumerate() {
+        let mut chars = line.chars();
+
+        for x in 0..panel_width {
+            if let Some(cell) = render_matrix.get_mut(x as u64, y as u64) {
+                cell.data_mut().push(TerminalRendererMatrixCellItem {
+                    display: chars.next().unwrap_or(' '),
+                    layer_of_value: Layer::furthest_foreground(),
+                    foreground_color: Some(Rgb::white()),
+                    background_color: Some(Rgb::black()),
+                });
+            }
+        }
+    }
+}
+
 fn is_renderable_visible(
     main_camera: &TerminalCamera,
     main_camera_transform: &TerminalTransform,
-    renderable_transform: &TerminalTransform,
+    renderable_coords: &IntCoords2d,
 ) -> bool {
-    let screen_position = convert_world_position_to_screen_position(
-        main_camera_transform,
-        &renderable_transform.coords,
-    );
+    let screen_position =
+        convert_world_position_to_screen_position(main_camera_transform, renderable_coords);
 
     (screen_position.x() >= 0 && screen_position.x() < main_camera.field_of_view.width() as i64)
         && (screen_position.y() >= 0
@@ -801,9 +889,7 @@ mod tests {
         use super::*;
 
         mod without_camera_offset {
-            use std::{cell::RefCell, rc::Rc};
-
-            use crate::{Entity, QueryResult, StoredComponentList};
+            use crate::{Entity, QueryResult, Shared, SharedCell, StoredComponentList};
 
             use super::*;
 
@@ -819,34 +905,35 @@ mod tests {
                     },
                     &QueryResultList::new(vec![
                         QueryResult::new(
-                            Entity(0),
+                            Entity(0, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: '*',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(0, 3),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(1),
+                            Entity(1, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'A',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(5, 2),
                                 }))),
                             ]),
                         ),
                     ]),
+                    None,
                     &TerminalRendererOptions {
                         screen_resolution: Dimensions2d::new(10, 10),
                         include_default_camera: true,
@@ -906,48 +993,49 @@ mod tests {
                     },
                     &QueryResultList::new(vec![
                         QueryResult::new(
-                            Entity(0),
+                            Entity(0, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: '*',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(0, 3),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(1),
+                            Entity(1, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'A',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(5, 2),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(2),
+                            Entity(2, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'A',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(-1, 2),
                                 }))),
                             ]),
                         ),
                     ]),
+                    None,
                     &TerminalRendererOptions {
                         screen_resolution: Dimensions2d::new(10, 10),
                         include_default_camera: true,
@@ -1007,48 +1095,49 @@ mod tests {
                     },
                     &QueryResultList::new(vec![
                         QueryResult::new(
-                            Entity(0),
+                            Entity(0, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: '*',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(0, 3),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(1),
+                            Entity(1, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'A',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(5, 2),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(2),
+                            Entity(2, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'A',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(10, 2),
                                 }))),
                             ]),
                         ),
                     ]),
+                    None,
                     &TerminalRendererOptions {
                         screen_resolution: Dimensions2d::new(10, 10),
                         include_default_camera: true,
@@ -1108,48 +1197,49 @@ mod tests {
                     },
                     &QueryResultList::new(vec![
                         QueryResult::new(
-                            Entity(0),
+                            Entity(0, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: '*',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(0, 3),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(1),
+                            Entity(1, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'A',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(5, 2),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(2),
+                            Entity(2, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'A',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(3, -1),
                                 }))),
                             ]),
                         ),
                     ]),
+                    None,
                     &TerminalRendererOptions {
                         screen_resolution: Dimensions2d::new(10, 10),
                         include_default_camera: true,
@@ -1209,48 +1299,49 @@ mod tests {
                     },
                     &QueryResultList::new(vec![
                         QueryResult::new(
-                            Entity(0),
+                            Entity(0, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: '*',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(0, 3),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(1),
+                            Entity(1, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'A',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(5, 2),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(2),
+                            Entity(2, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'A',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(3, 10),
                                 }))),
                             ]),
                         ),
                     ]),
+                    None,
                     &TerminalRendererOptions {
                         screen_resolution: Dimensions2d::new(10, 10),
                         include_default_camera: true,
@@ -1310,48 +1401,49 @@ mod tests {
                     },
                     &QueryResultList::new(vec![
                         QueryResult::new(
-                            Entity(0),
+                            Entity(0, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: '*',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(9, 3),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(1),
+                            Entity(1, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'A',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(5, 2),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(2),
+                            Entity(2, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'A',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(-1, 2),
                                 }))),
                             ]),
                         ),
                     ]),
+                    None,
                     &TerminalRendererOptions {
                         screen_resolution: Dimensions2d::new(10, 10),
                         include_default_camera: true,
@@ -1411,48 +1503,49 @@ mod tests {
                     },
                     &QueryResultList::new(vec![
                         QueryResult::new(
-                            Entity(0),
+                            Entity(0, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: '*',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(0, 3),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(1),
+                            Entity(1, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'A',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(5, 2),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(2),
+                            Entity(2, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'A',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(-1, 2),
                                 }))),
                             ]),
                         ),
                     ]),
+                    None,
                     &TerminalRendererOptions {
                         screen_resolution: Dimensions2d::new(10, 10),
                         include_default_camera: true,
@@ -1512,48 +1605,49 @@ mod tests {
                     },
                     &QueryResultList::new(vec![
                         QueryResult::new(
-                            Entity(0),
+                            Entity(0, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: '*',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(0, 3),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(1),
+                            Entity(1, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'A',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(5, 2),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(2),
+                            Entity(2, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'A',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(1, 0),
                                 }))),
                             ]),
                         ),
                     ]),
+                    None,
                     &TerminalRendererOptions {
                         screen_resolution: Dimensions2d::new(10, 10),
                         include_default_camera: true,
@@ -1624,48 +1718,49 @@ mod tests {
                     },
                     &QueryResultList::new(vec![
                         QueryResult::new(
-                            Entity(0),
+                            Entity(0, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: '*',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(0, 3),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(1),
+                            Entity(1, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'A',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(5, 2),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(2),
+                            Entity(2, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'A',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(1, 9),
                                 }))),
                             ]),
                         ),
                     ]),
+                    None,
                     &TerminalRendererOptions {
                         screen_resolution: Dimensions2d::new(10, 10),
                         include_default_camera: true,
@@ -1736,48 +1831,49 @@ mod tests {
                     },
                     &QueryResultList::new(vec![
                         QueryResult::new(
-                            Entity(0),
+                            Entity(0, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: '*',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(0, 3),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(1),
+                            Entity(1, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'A',
                                     layer: Layer::above(&Layer::base()),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(5, 2),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(2),
+                            Entity(2, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: '^',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(5, 2),
                                 }))),
                             ]),
                         ),
                     ]),
+                    None,
                     &TerminalRendererOptions {
                         screen_resolution: Dimensions2d::new(10, 10),
                         include_default_camera: true,
@@ -1827,9 +1923,7 @@ mod tests {
         }
 
         mod with_camera_offset {
-            use std::{cell::RefCell, rc::Rc};
-
-            use crate::{Entity, QueryResult, StoredComponentList};
+            use crate::{Entity, QueryResult, Shared, SharedCell, StoredComponentList};
 
             use super::*;
 
@@ -1845,48 +1939,49 @@ mod tests {
                     },
                     &QueryResultList::new(vec![
                         QueryResult::new(
-                            Entity(0),
+                            Entity(0, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: '*',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(0, 3),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(1),
+                            Entity(1, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'A',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(5, 2),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(2),
+                            Entity(2, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'B',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(-1, 2),
                                 }))),
                             ]),
                         ),
                     ]),
+                    None,
                     &TerminalRendererOptions {
                         screen_resolution: Dimensions2d::new(10, 10),
                         include_default_camera: true,
@@ -1946,48 +2041,49 @@ mod tests {
                     },
                     &QueryResultList::new(vec![
                         QueryResult::new(
-                            Entity(0),
+                            Entity(0, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: '*',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(0, 3),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(1),
+                            Entity(1, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'A',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(-7, 2),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(2),
+                            Entity(2, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'B',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(-1, 2),
                                 }))),
                             ]),
                         ),
                     ]),
+                    None,
                     &TerminalRendererOptions {
                         screen_resolution: Dimensions2d::new(10, 10),
                         include_default_camera: true,
@@ -2047,48 +2143,49 @@ mod tests {
                     },
                     &QueryResultList::new(vec![
                         QueryResult::new(
-                            Entity(0),
+                            Entity(0, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: '*',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(0, 3),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(1),
+                            Entity(1, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'A',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(4, 2),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(2),
+                            Entity(2, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'B',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(-1, 2),
                                 }))),
                             ]),
                         ),
                     ]),
+                    None,
                     &TerminalRendererOptions {
                         screen_resolution: Dimensions2d::new(10, 10),
                         include_default_camera: true,
@@ -2148,48 +2245,49 @@ mod tests {
                     },
                     &QueryResultList::new(vec![
                         QueryResult::new(
-                            Entity(0),
+                            Entity(0, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: '*',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(0, 3),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(1),
+                            Entity(1, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'A',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(-3, 1),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(2),
+                            Entity(2, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'B',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(-1, 2),
                                 }))),
                             ]),
                         ),
                     ]),
+                    None,
                     &TerminalRendererOptions {
                         screen_resolution: Dimensions2d::new(10, 10),
                         include_default_camera: true,
@@ -2249,48 +2347,49 @@ mod tests {
                     },
                     &QueryResultList::new(vec![
                         QueryResult::new(
-                            Entity(0),
+                            Entity(0, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: '*',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(0, 3),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(1),
+                            Entity(1, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'A',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(-3, 12),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(2),
+                            Entity(2, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'B',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(-1, 2),
                                 }))),
                             ]),
                         ),
                     ]),
+                    None,
                     &TerminalRendererOptions {
                         screen_resolution: Dimensions2d::new(10, 10),
                         include_default_camera: true,
@@ -2350,48 +2449,49 @@ mod tests {
                     },
                     &QueryResultList::new(vec![
                         QueryResult::new(
-                            Entity(0),
+                            Entity(0, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: '*',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(0, 3),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(1),
+                            Entity(1, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'A',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(3, 5),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(2),
+                            Entity(2, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'B',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(-1, 2),
                                 }))),
                             ]),
                         ),
                     ]),
+                    None,
                     &TerminalRendererOptions {
                         screen_resolution: Dimensions2d::new(10, 10),
                         include_default_camera: true,
@@ -2462,48 +2562,49 @@ mod tests {
                     },
                     &QueryResultList::new(vec![
                         QueryResult::new(
-                            Entity(0),
+                            Entity(0, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: '*',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(0, 3),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(1),
+                            Entity(1, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'A',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(-6, 5),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(2),
+                            Entity(2, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'B',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(-1, 2),
                                 }))),
                             ]),
                         ),
                     ]),
+                    None,
                     &TerminalRendererOptions {
                         screen_resolution: Dimensions2d::new(10, 10),
                         include_default_camera: true,
@@ -2574,48 +2675,49 @@ mod tests {
                     },
                     &QueryResultList::new(vec![
                         QueryResult::new(
-                            Entity(0),
+                            Entity(0, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: '*',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(0, 3),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(1),
+                            Entity(1, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'A',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(0, 2),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(2),
+                            Entity(2, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'B',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(-1, 2),
                                 }))),
                             ]),
                         ),
                     ]),
+                    None,
                     &TerminalRendererOptions {
                         screen_resolution: Dimensions2d::new(10, 10),
                         include_default_camera: true,
@@ -2686,48 +2788,49 @@ mod tests {
                     },
                     &QueryResultList::new(vec![
                         QueryResult::new(
-                            Entity(0),
+                            Entity(0, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: '*',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(0, 3),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(1),
+                            Entity(1, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'A',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(-1, 11),
                                 }))),
                             ]),
                         ),
                         QueryResult::new(
-                            Entity(2),
+                            Entity(2, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalRenderer {
+                                Shared::new(SharedCell::new(Box::new(TerminalRenderer {
                                     display: 'B',
                                     layer: Layer::base(),
                                     foreground_color: None,
                                     background_color: None,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(-1, 2),
                                 }))),
                             ]),
                         ),
                     ]),
+                    None,
                     &TerminalRendererOptions {
                         screen_resolution: Dimensions2d::new(10, 10),
                         include_default_camera: true,
@@ -1,9 +1,9 @@
-use std::{collections::HashMap, rc::Rc};
+use std::collections::HashMap;
 
 use crate::{
-    Alignment, GameCommand, GameCommandsArg, IntCoords2d, Layer, Query, QueryResultList, Rgb,
-    System, SystemsGenerator, TerminalCamera, TerminalRenderer, TerminalTextCharacter,
-    TerminalTransform, Text, UiAnchor, WorldText, EVENT_UPDATE,
+    Alignment, GameCommand, GameCommandsArg, IntCoords2d, Layer, Parent, Query, QueryResultList,
+    Rgb, Shared, StoredComponentList, System, SystemsGenerator, TerminalCamera, TerminalRenderer,
+    TerminalTextCharacter, TerminalTransform, Text, UiAnchor, World, WorldText, EVENT_UPDATE,
 };
 
 /// A generator responsible for setting up and performing UI rendering in a terminal game. This systems generator is
@@ -21,30 +21,37 @@ impl SystemsGenerator for TerminalUiRendererSystemsGenerator {
             System::new(
                 vec![
                     Query::new().has::<Text>(),
-                    Query::new().has::<WorldText>().has::<TerminalTransform>(),
+                    Query::new()
+                        .has::<WorldText>()
+                        .has::<TerminalTransform>()
+                        .has_optional::<Parent>(),
                     Query::new()
                         .has::<TerminalTextCharacter>()
                         .has::<TerminalRenderer>(),
                     Query::new()
                         .has_where::<TerminalCamera>(|cam| cam.is_main)
-                        .has::<TerminalTransform>(),
+                        .has::<TerminalTransform>()
+                        .has_optional::<Parent>(),
                 ],
                 update_text_ui,
-            ),
+            )
+            .with_name("thomas_ui_renderer"),
         )]
     }
 }
 
-fn update_text_ui(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+fn update_text_ui(results: Vec<QueryResultList>, commands: GameCommandsArg, world: &World) {
     if let [text_results, world_text_results, drawn_text_results, main_cam_results, ..] =
         &results[..]
     {
         let main_cam = main_cam_results.get_only::<TerminalCamera>();
-        let main_cam_transform = main_cam_results.get_only::<TerminalTransform>();
+        let main_cam_transform = TerminalTransform {
+            coords: world_coords(main_cam_results[0].components(), world),
+        };
 
         let anchor_positions = get_anchor_positions(&main_cam, &main_cam_transform);
 
-        wipe_existing_text(drawn_text_results, Rc::clone(&commands));
+        wipe_existing_text(drawn_text_results, Shared::clone(&commands));
 
         for text_result in text_results {
             let text = text_result.components().get::<Text>();
@@ -66,33 +73,43 @@ fn update_text_ui(results: Vec<QueryResultList>, commands: GameCommandsArg) {
                 &starting_position,
                 &text.foreground_color,
                 &text.background_color,
-                Rc::clone(&commands),
+                Shared::clone(&commands),
             );
         }
 
         for world_text_result in world_text_results {
             let world_text = world_text_result.components().get::<WorldText>();
-            let world_text_transform = world_text_result.components().get::<TerminalTransform>();
+            let world_text_coords = world_coords(world_text_result.components(), world);
 
             let chars = world_text.value.chars().collect::<Vec<char>>();
 
             let justification_offset =
                 get_justification_offset(&world_text.justification, chars.len());
 
-            let starting_position =
-                world_text_transform.coords + justification_offset + world_text.offset;
+            let starting_position = world_text_coords + justification_offset + world_text.offset;
 
             add_text_entities(
                 &chars,
                 &starting_position,
                 &world_text.foreground_color,
                 &world_text.background_color,
-                Rc::clone(&commands),
+                Shared::clone(&commands),
             );
         }
     }
 }
 
+/// Where the entity is in the world. `GlobalTerminalTransform` isn't brought up to date until the after-update event,
+/// so it's worked out from the parent here instead, to keep UI on a child in step with its parent.
+fn world_coords(components: &StoredComponentList, world: &World) -> IntCoords2d {
+    let coords = components.get::<TerminalTransform>().coords;
+
+    match components.try_get::<Parent>() {
+        Some(parent) => coords + world.terminal_world_coords(parent.entity()),
+        None => coords,
+    }
+}
+
 fn wipe_existing_text(text_character_query_results: &QueryResultList, commands: GameCommandsArg) {
     for text_character in text_character_query_results {
         commands
@@ -188,9 +205,7 @@ mod tests {
     use super::*;
 
     mod test_update_text_ui {
-        use std::cell::RefCell;
-
-        use crate::{Dimensions2d, Entity, QueryResult, StoredComponentList};
+        use crate::{Dimensions2d, Entity, QueryResult, Shared, SharedCell, StoredComponentList};
 
         use super::*;
 
@@ -208,13 +223,13 @@ mod tests {
                         QueryResultList::new(vec![]),
                         QueryResultList::new(vec![]),
                         QueryResultList::new(vec![QueryResult::new(
-                            Entity(1),
+                            Entity(1, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalCamera {
+                                Shared::new(SharedCell::new(Box::new(TerminalCamera {
                                     field_of_view: Dimensions2d::new(10, 10),
                                     is_main: true,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::zero(),
                                 }))),
                             ]),
@@ -229,20 +244,22 @@ mod tests {
                     let mut results = make_basic_results();
 
                     results[0].push(QueryResult::new(
-                        Entity(0),
-                        StoredComponentList::new(vec![Rc::new(RefCell::new(Box::new(Text {
-                            value: String::from("T"),
-                            anchor: UiAnchor::TopLeft,
-                            justification: Alignment::Left,
-                            offset: IntCoords2d::zero(),
-                            foreground_color: None,
-                            background_color: None,
-                        })))]),
+                        Entity(0, 0),
+                        StoredComponentList::new(vec![Shared::new(SharedCell::new(Box::new(
+                            Text {
+                                value: String::from("T"),
+                                anchor: UiAnchor::TopLeft,
+                                justification: Alignment::Left,
+                                offset: IntCoords2d::zero(),
+                                foreground_color: None,
+                                background_color: None,
+                            },
+                        )))]),
                     ));
 
-                    let commands = Rc::new(RefCell::new(GameCommandQueue::new()));
+                    let commands = Shared::new(SharedCell::new(GameCommandQueue::new()));
 
-                    update_text_ui(results, Rc::clone(&commands));
+                    update_text_ui(results, Shared::clone(&commands), &World::new());
 
                     assert!(commands
                         .borrow()
@@ -271,20 +288,22 @@ mod tests {
                     let mut results = make_basic_results();
 
                     results[0].push(QueryResult::new(
-                        Entity(0),
-                        StoredComponentList::new(vec![Rc::new(RefCell::new(Box::new(Text {
-                            value: String::from("T"),
-                            anchor: UiAnchor::MiddleTop,
-                            justification: Alignment::Left,
-                            offset: IntCoords2d::zero(),
-                            foreground_color: None,
-                            background_color: None,
-                        })))]),
+                        Entity(0, 0),
+                        StoredComponentList::new(vec![Shared::new(SharedCell::new(Box::new(
+                            Text {
+                                value: String::from("T"),
+                                anchor: UiAnchor::MiddleTop,
+                                justification: Alignment::Left,
+                                offset: IntCoords2d::zero(),
+                                foreground_color: None,
+                                background_color: None,
+                            },
+                        )))]),
                     ));
 
-                    let commands = Rc::new(RefCell::new(GameCommandQueue::new()));
+                    let commands = Shared::new(SharedCell::new(GameCommandQueue::new()));
 
-                    update_text_ui(results, Rc::clone(&commands));
+                    update_text_ui(results, Shared::clone(&commands), &World::new());
 
                     assert!(commands
                         .borrow()
@@ -313,20 +332,22 @@ mod tests {
                     let mut results = make_basic_results();
 
                     results[0].push(QueryResult::new(
-                        Entity(0),
-                        StoredComponentList::new(vec![Rc::new(RefCell::new(Box::new(Text {
-                            value: String::from("T"),
-                            anchor: UiAnchor::TopRight,
-                            justification: Alignment::Left,
-                            offset: IntCoords2d::zero(),
-                            foreground_color: None,
-                            background_color: None,
-                        })))]),
+                        Entity(0, 0),
+                        StoredComponentList::new(vec![Shared::new(SharedCell::new(Box::new(
+                            Text {
+                                value: String::from("T"),
+                                anchor: UiAnchor::TopRight,
+                                justification: Alignment::Left,
+                                offset: IntCoords2d::zero(),
+                                foreground_color: None,
+                                background_color: None,
+                            },
+                        )))]),
                     ));
 
-                    let commands = Rc::new(RefCell::new(GameCommandQueue::new()));
+                    let commands = Shared::new(SharedCell::new(GameCommandQueue::new()));
 
-                    update_text_ui(results, Rc::clone(&commands));
+                    update_text_ui(results, Shared::clone(&commands), &World::new());
 
                     assert!(commands
                         .borrow()
@@ -355,20 +376,22 @@ mod tests {
                     let mut results = make_basic_results();
 
                     results[0].push(QueryResult::new(
-                        Entity(0),
-                        StoredComponentList::new(vec![Rc::new(RefCell::new(Box::new(Text {
-                            value: String::from("T"),
-                            anchor: UiAnchor::MiddleRight,
-                            justification: Alignment::Left,
-                            offset: IntCoords2d::zero(),
-                            foreground_color: None,
-                            background_color: None,
-                        })))]),
+                        Entity(0, 0),
+                        StoredComponentList::new(vec![Shared::new(SharedCell::new(Box::new(
+                            Text {
+                                value: String::from("T"),
+                                anchor: UiAnchor::MiddleRight,
+                                justification: Alignment::Left,
+                                offset: IntCoords2d::zero(),
+                                foreground_color: None,
+                                background_color: None,
+                            },
+                        )))]),
                     ));
 
-                    let commands = Rc::new(RefCell::new(GameCommandQueue::new()));
+                    let commands = Shared::new(SharedCell::new(GameCommandQueue::new()));
 
-                    update_text_ui(results, Rc::clone(&commands));
+                    update_text_ui(results, Shared::clone(&commands), &World::new());
 
                     assert!(commands
                         .borrow()
@@ -397,20 +420,22 @@ mod tests {
                     let mut results = make_basic_results();
 
                     results[0].push(QueryResult::new(
-                        Entity(0),
-                        StoredComponentList::new(vec![Rc::new(RefCell::new(Box::new(Text {
-                            value: String::from("T"),
-                            anchor: UiAnchor::BottomRight,
-                            justification: Alignment::Left,
-                            offset: IntCoords2d::zero(),
-                            foreground_color: None,
-                            background_color: None,
-                        })))]),
+                        Entity(0, 0),
+                        StoredComponentList::new(vec![Shared::new(SharedCell::new(Box::new(
+                            Text {
+                                value: String::from("T"),
+                                anchor: UiAnchor::BottomRight,
+                                justification: Alignment::Left,
+                                offset: IntCoords2d::zero(),
+                                foreground_color: None,
+                                background_color: None,
+                            },
+                        )))]),
                     ));
 
-                    let commands = Rc::new(RefCell::new(GameCommandQueue::new()));
+                    let commands = Shared::new(SharedCell::new(GameCommandQueue::new()));
 
-                    update_text_ui(results, Rc::clone(&commands));
+                    update_text_ui(results, Shared::clone(&commands), &World::new());
 
                     assert!(commands
                         .borrow()
@@ -439,20 +464,22 @@ mod tests {
                     let mut results = make_basic_results();
 
                     results[0].push(QueryResult::new(
-                        Entity(0),
-                        StoredComponentList::new(vec![Rc::new(RefCell::new(Box::new(Text {
-                            value: String::from("T"),
-                            anchor: UiAnchor::MiddleBottom,
-                            justification: Alignment::Left,
-                            offset: IntCoords2d::zero(),
-                            foreground_color: None,
-                            background_color: None,
-                        })))]),
+                        Entity(0, 0),
+                        StoredComponentList::new(vec![Shared::new(SharedCell::new(Box::new(
+                            Text {
+                                value: String::from("T"),
+                                anchor: UiAnchor::MiddleBottom,
+                                justification: Alignment::Left,
+                                offset: IntCoords2d::zero(),
+                                foreground_color: None,
+                                background_color: None,
+                            },
+                        )))]),
                     ));
 
-                    let commands = Rc::new(RefCell::new(GameCommandQueue::new()));
+                    let commands = Shared::new(SharedCell::new(GameCommandQueue::new()));
 
-                    update_text_ui(results, Rc::clone(&commands));
+                    update_text_ui(results, Shared::clone(&commands), &World::new());
 
                     assert!(commands
                         .borrow()
@@ -481,20 +508,22 @@ mod tests {
                     let mut results = make_basic_results();
 
                     results[0].push(QueryResult::new(
-                        Entity(0),
-                        StoredComponentList::new(vec![Rc::new(RefCell::new(Box::new(Text {
-                            value: String::from("T"),
-                            anchor: UiAnchor::BottomLeft,
-                            justification: Alignment::Left,
-                            offset: IntCoords2d::zero(),
-                            foreground_color: None,
-                            background_color: None,
-                        })))]),
+                        Entity(0, 0),
+                        StoredComponentList::new(vec![Shared::new(SharedCell::new(Box::new(
+                            Text {
+                                value: String::from("T"),
+                                anchor: UiAnchor::BottomLeft,
+                                justification: Alignment::Left,
+                                offset: IntCoords2d::zero(),
+                                foreground_color: None,
+                                background_color: None,
+                            },
+                        )))]),
                     ));
 
-                    let commands = Rc::new(RefCell::new(GameCommandQueue::new()));
+                    let commands = Shared::new(SharedCell::new(GameCommandQueue::new()));
 
-                    update_text_ui(results, Rc::clone(&commands));
+                    update_text_ui(results, Shared::clone(&commands), &World::new());
 
                     assert!(commands
                         .borrow()
@@ -523,20 +552,22 @@ mod tests {
                     let mut results = make_basic_results();
 
                     results[0].push(QueryResult::new(
-                        Entity(0),
-                        StoredComponentList::new(vec![Rc::new(RefCell::new(Box::new(Text {
-                            value: String::from("T"),
-                            anchor: UiAnchor::MiddleLeft,
-                            justification: Alignment::Left,
-                            offset: IntCoords2d::zero(),
-                            foreground_color: None,
-                            background_color: None,
-                        })))]),
+                        Entity(0, 0),
+                        StoredComponentList::new(vec![Shared::new(SharedCell::new(Box::new(
+                            Text {
+                                value: String::from("T"),
+                                anchor: UiAnchor::MiddleLeft,
+                                justification: Alignment::Left,
+                                offset: IntCoords2d::zero(),
+                                foreground_color: None,
+                                background_color: None,
+                            },
+                        )))]),
                     ));
 
-                    let commands = Rc::new(RefCell::new(GameCommandQueue::new()));
+                    let commands = Shared::new(SharedCell::new(GameCommandQueue::new()));
 
-                    update_text_ui(results, Rc::clone(&commands));
+                    update_text_ui(results, Shared::clone(&commands), &World::new());
 
                     assert!(commands
                         .borrow()
@@ -571,13 +602,13 @@ mod tests {
                         QueryResultList::new(vec![]),
                         QueryResultList::new(vec![]),
                         QueryResultList::new(vec![QueryResult::new(
-                            Entity(1),
+                            Entity(1, 0),
                             StoredComponentList::new(vec![
-                                Rc::new(RefCell::new(Box::new(TerminalCamera {
+                                Shared::new(SharedCell::new(Box::new(TerminalCamera {
                                     field_of_view: Dimensions2d::new(5, 5),
                                     is_main: true,
                                 }))),
-                                Rc::new(RefCell::new(Box::new(TerminalTransform {
+                                Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                     coords: IntCoords2d::new(-3, 2),
                                 }))),
                             ]),
@@ -592,20 +623,22 @@ mod tests {
                     let mut results = make_basic_results();
 
                     results[0].push(QueryResult::new(
-                        Entity(0),
-                        StoredComponentList::new(vec![Rc::new(RefCell::new(Box::new(Text {
-                            value: String::from("T"),
-                            anchor: UiAnchor::TopLeft,
-                            justification: Alignment::Left,
-                            offset: IntCoords2d::zero(),
-                            foreground_color: None,
-                            background_color: None,
-                        })))]),
+                        Entity(0, 0),
+                        StoredComponentList::new(vec![Shared::new(SharedCell::new(Box::new(
+                            Text {
+                                value: String::from("T"),
+                                anchor: UiAnchor::TopLeft,
+                                justification: Alignment::Left,
+                                offset: IntCoords2d::zero(),
+                                foreground_color: None,
+                                background_color: None,
+                            },
+                        )))]),
                     ));
 
-                    let commands = Rc::new(RefCell::new(GameCommandQueue::new()));
+                    let commands = Shared::new(SharedCell::new(GameCommandQueue::new()));
 
-                    update_text_ui(results, Rc::clone(&commands));
+                    update_text_ui(results, Shared::clone(&commands), &World::new());
 
                     assert!(commands
                         .borrow()
@@ -634,20 +667,22 @@ mod tests {
                     let mut results = make_basic_results();
 
                     results[0].push(QueryResult::new(
-                        Entity(0),
-                        StoredComponentList::new(vec![Rc::new(RefCell::new(Box::new(Text {
-                            value: String::from("T"),
-                            anchor: UiAnchor::MiddleTop,
-                            justification: Alignment::Left,
-                            offset: IntCoords2d::zero(),
-                            foreground_color: None,
-                            background_color: None,
-                        })))]),
+                        Entity(0, 0),
+                        StoredComponentList::new(vec![Shared::new(SharedCell::new(Box::new(
+                            Text {
+                                value: String::from("T"),
+                                anchor: UiAnchor::MiddleTop,
+                                justification: Alignment::Left,
+                                offset: IntCoords2d::zero(),
+                                foreground_color: None,
+                                background_color: None,
+                            },
+                        )))]),
                     ));
 
-                    let commands = Rc::new(RefCell::new(GameCommandQueue::new()));
+                    let commands = Shared::new(SharedCell::new(GameCommandQueue::new()));
 
-                    update_text_ui(results, Rc::clone(&commands));
+                    update_text_ui(results, Shared::clone(&commands), &World::new());
 
                     assert!(commands
                         .borrow()
@@ -676,20 +711,22 @@ mod tests {
                     let mut results = make_basic_results();
 
                     results[0].push(QueryResult::new(
-                        Entity(0),
-                        StoredComponentList::new(vec![Rc::new(RefCell::new(Box::new(Text {
-                            value: String::from("T"),
-                            anchor: UiAnchor::TopRight,
-                            justification: Alignment::Left,
-                            offset: IntCoords2d::zero(),
-                            foreground_color: None,
-                            background_color: None,
-                        })))]),
+                        Entity(0, 0),
+                        StoredComponentList::new(vec![Shared::new(SharedCell::new(Box::new(
+                            Text {
+                                value: String::from("T"),
+                                anchor: UiAnchor::TopRight,
+                                justification: Alignment::Left,
+                                offset: IntCoords2d::zero(),
+                                foreground_color: None,
+                                background_color: None,
+                            },
+                        )))]),
                     ));
 
-                    let commands = Rc::new(RefCell::new(GameCommandQueue::new()));
+                    let commands = Shared::new(SharedCell::new(GameCommandQueue::new()));
 
-                    update_text_ui(results, Rc::clone(&commands));
+                    update_text_ui(results, Shared::clone(&commands), &World::new());
 
                     assert!(commands
                         .borrow()
@@ -718,20 +755,22 @@ mod tests {
                     let mut results = make_basic_results();
 
                     results[0].push(QueryResult::new(
-                        Entity(0),
-                        StoredComponentList::new(vec![Rc::new(RefCell::new(Box::new(Text {
-                            value: String::from("T"),
-                            anchor: UiAnchor::MiddleRight,
-                            justification: Alignment::Left,
-                            offset: IntCoords2d::zero(),
-                            foreground_color: None,
-                            background_color: None,
-                        })))]),
+                        Entity(0, 0),
+                        StoredComponentList::new(vec![Shared::new(SharedCell::new(Box::new(
+                            Text {
+                                value: String::from("T"),
+                                anchor: UiAnchor::MiddleRight,
+                                justification: Alignment::Left,
+                                offset: IntCoords2d::zero(),
+                                foreground_color: None,
+                                background_color: None,
+                            },
+                        )))]),
                     ));
 
-                    let commands = Rc::new(RefCell::new(GameCommandQueue::new()));
+                    let commands = Shared::new(SharedCell::new(GameCommandQueue::new()));
 
-                    update_text_ui(results, Rc::clone(&commands));
+                    update_text_ui(results, Shared::clone(&commands), &World::new());
 
                     assert!(commands
                         .borrow()
@@ -760,20 +799,22 @@ mod tests {
                     let mut results = make_basic_results();
 
                     results[0].push(QueryResult::new(
-                        Entity(0),
-                        StoredComponentList::new(vec![Rc::new(RefCell::new(Box::new(Text {
-                            value: String::from("T"),
-                            anchor: UiAnchor::BottomRight,
-                            justification: Alignment::Left,
-                            offset: IntCoords2d::zero(),
-                            foreground_color: None,
-                            background_color: None,
-                        })))]),
+                        Entity(0, 0),
+                        StoredComponentList::new(vec![Shared::new(SharedCell::new(Box::new(
+                            Text {
+                                value: String::from("T"),
+                                anchor: UiAnchor::BottomRight,
+                                justification: Alignment::Left,
+                                offset: IntCoords2d::zero(),
+                                foreground_color: None,
+                                background_color: None,
+                            },
+                        )))]),
                     ));
 
-                    let commands = Rc::new(RefCell::new(GameCommandQueue::new()));
+                    let commands = Shared::new(SharedCell::new(GameCommandQueue::new()));
 
-                    update_text_ui(results, Rc::clone(&commands));
+                    update_text_ui(results, Shared::clone(&commands), &World::new());
 
                     assert!(commands
                         .borrow()
@@ -802,20 +843,22 @@ mod tests {
                     let mut results = make_basic_results();
 
                     results[0].push(QueryResult::new(
-                        Entity(0),
-                        StoredComponentList::new(vec![Rc::new(RefCell::new(Box::new(Text {
-                            value: String::from("T"),
-                            anchor: UiAnchor::MiddleBottom,
-                            justification: Alignment::Left,
-                            offset: IntCoords2d::zero(),
-                            foreground_color: None,
-                            background_color: None,
-                        })))]),
+                        Entity(0, 0),
+                        StoredComponentList::new(vec![Shared::new(SharedCell::new(Box::new(
+                            Text {
+                                value: String::from("T"),
+                                anchor: UiAnchor::MiddleBottom,
+                                justification: Alignment::Left,
+                                offset: IntCoords2d::zero(),
+                                foreground_color: None,
+                                background_color: None,
+                            },
+                        )))]),
                     ));
 
-                    let commands = Rc::new(RefCell::new(GameCommandQueue::new()));
+                    let commands = Shared::new(SharedCell::new(GameCommandQueue::new()));
 
-                    update_text_ui(results, Rc::clone(&commands));
+                    update_text_ui(results, Shared::clone(&commands), &World::new());
 
                     assert!(commands
                         .borrow()
@@ -844,20 +887,22 @@ mod tests {
                     let mut results = make_basic_results();
 
                     results[0].push(QueryResult::new(
-                        Entity(0),
-                        StoredComponentList::new(vec![Rc::new(RefCell::new(Box::new(Text {
-                            value: String::from("T"),
-                            anchor: UiAnchor::BottomLeft,
-                            justification: Alignment::Left,
-                            offset: IntCoords2d::zero(),
-                            foreground_color: None,
-                            background_color: None,
-                        })))]),
+                        Entity(0, 0),
+                        StoredComponentList::new(vec![Shared::new(SharedCell::new(Box::new(
+                            Text {
+                                value: String::from("T"),
+                                anchor: UiAnchor::BottomLeft,
+                                justification: Alignment::Left,
+                                offset: IntCoords2d::zero(),
+                                foreground_color: None,
+                                background_color: None,
+                            },
+                        )))]),
                     ));
 
-                    let commands = Rc::new(RefCell::new(GameCommandQueue::new()));
+                    let commands = Shared::new(SharedCell::new(GameCommandQueue::new()));
 
-                    update_text_ui(results, Rc::clone(&commands));
+                    update_text_ui(results, Shared::clone(&commands), &World::new());
 
                     assert!(commands
                         .borrow()
@@ -886,20 +931,22 @@ mod tests {
                     let mut results = make_basic_results();
 
                     results[0].push(QueryResult::new(
-                        Entity(0),
-                        StoredComponentList::new(vec![Rc::new(RefCell::new(Box::new(Text {
-                            value: String::from("T"),
-                            anchor: UiAnchor::MiddleLeft,
-                            justification: Alignment::Left,
-                            offset: IntCoords2d::zero(),
-                            foreground_color: None,
-                            background_color: None,
-                        })))]),
+                        Entity(0, 0),
+                        StoredComponentList::new(vec![Shared::new(SharedCell::new(Box::new(
+                            Text {
+                                value: String::from("T"),
+                                anchor: UiAnchor::MiddleLeft,
+                                justification: Alignment::Left,
+                                offset: IntCoords2d::zero(),
+                                foreground_color: None,
+                                background_color: None,
+                            },
+                        )))]),
                     ));
 
-                    let commands = Rc::new(RefCell::new(GameCommandQueue::new()));
+                    let commands = Shared::new(SharedCell::new(GameCommandQueue::new()));
 
-                    update_text_ui(results, Rc::clone(&commands));
+                    update_text_ui(results, Shared::clone(&commands), &World::new());
 
                     assert!(commands
                         .borrow()
@@ -935,38 +982,38 @@ mod tests {
                 let results = vec![
                     QueryResultList::new(vec![]),
                     QueryResultList::new(vec![QueryResult::new(
-                        Entity(10),
+                        Entity(10, 0),
                         StoredComponentList::new(vec![
-                            Rc::new(RefCell::new(Box::new(WorldText {
+                            Shared::new(SharedCell::new(Box::new(WorldText {
                                 value: String::from("T"),
                                 justification: Alignment::Left,
                                 offset: IntCoords2d::zero(),
                                 background_color: None,
                                 foreground_color: None,
                             }))),
-                            Rc::new(RefCell::new(Box::new(TerminalTransform {
+                            Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                 coords: IntCoords2d::new(5, 3),
                             }))),
                         ]),
                     )]),
                     QueryResultList::new(vec![]),
                     QueryResultList::new(vec![QueryResult::new(
-                        Entity(1),
+                        Entity(1, 0),
                         StoredComponentList::new(vec![
-                            Rc::new(RefCell::new(Box::new(TerminalCamera {
+                            Shared::new(SharedCell::new(Box::new(TerminalCamera {
                                 field_of_view: Dimensions2d::new(10, 10),
                                 is_main: true,
                             }))),
-                            Rc::new(RefCell::new(Box::new(TerminalTransform {
+                            Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                 coords: IntCoords2d::zero(),
                             }))),
                         ]),
                     )]),
                 ];
 
-                let commands = Rc::new(RefCell::new(GameCommandQueue::new()));
+                let commands = Shared::new(SharedCell::new(GameCommandQueue::new()));
 
-                update_text_ui(results, Rc::clone(&commands));
+                update_text_ui(results, Shared::clone(&commands), &World::new());
 
                 assert!(commands
                     .borrow()
@@ -995,38 +1042,38 @@ mod tests {
                 let results = vec![
                     QueryResultList::new(vec![]),
                     QueryResultList::new(vec![QueryResult::new(
-                        Entity(10),
+                        Entity(10, 0),
                         StoredComponentList::new(vec![
-                            Rc::new(RefCell::new(Box::new(WorldText {
+                            Shared::new(SharedCell::new(Box::new(WorldText {
                                 value: String::from("T"),
                                 justification: Alignment::Left,
                                 offset: IntCoords2d::zero(),
                                 background_color: None,
                                 foreground_color: None,
                             }))),
-                            Rc::new(RefCell::new(Box::new(TerminalTransform {
+                            Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                 coords: IntCoords2d::new(5, 3),
                             }))),
                         ]),
                     )]),
                     QueryResultList::new(vec![]),
                     QueryResultList::new(vec![QueryResult::new(
-                        Entity(1),
+                        Entity(1, 0),
                         StoredComponentList::new(vec![
-                            Rc::new(RefCell::new(Box::new(TerminalCamera {
+                            Shared::new(SharedCell::new(Box::new(TerminalCamera {
                                 field_of_view: Dimensions2d::new(10, 10),
                                 is_main: true,
                             }))),
-                            Rc::new(RefCell::new(Box::new(TerminalTransform {
+                            Shared::new(SharedCell::new(Box::new(TerminalTransform {
                                 coords: IntCoords2d::new(-5, 8),
                             }))),
                         ]),
                     )]),
                 ];
 
-                let commands = Rc::new(RefCell::new(GameCommandQueue::new()));
+                let commands = Shared::new(SharedCell::new(GameCommandQueue::new()));
 
-                update_text_ui(results, Rc::clone(&commands));
+                update_text_ui(results, Shared::clone(&commands), &World::new());
 
                 assert!(commands
                     .borrow()
@@ -1049,6 +1096,79 @@ mod tests {
                     })
                     .is_some());
             }
+
+            #[test]
+            fn text_on_a_child_moves_with_its_parent_in_the_same_frame() {
+                use std::time::Duration;
+
+                use crate::{
+                    Game, GameOptions, Query, Renderer, TerminalRendererOptions,
+                    EVENT_BEFORE_UPDATE,
+                };
+
+                #[derive(Component)]
+                struct Mover {}
+
+                let mut game = Game::new(GameOptions {
+                    press_escape_to_quit: false,
+                    max_frame_rate: 60,
+                })
+                .add_init_system(System::new(vec![], |_, commands, _| {
+                    commands
+                        .borrow_mut()
+                        .issue(GameCommand::AddEntityWithChildren(
+                            vec![
+                                Box::new(Mover {}),
+                                Box::new(TerminalTransform {
+                                    coords: IntCoords2d::zero(),
+                                }),
+                            ],
+                            vec![vec![
+                                Box::new(WorldText {
+                                    value: String::from("T"),
+                                    justification: Alignment::Left,
+                                    offset: IntCoords2d::zero(),
+                                    background_color: None,
+                                    foreground_color: None,
+                                }),
+                                Box::new(TerminalTransform {
+                                    coords: IntCoords2d::new(1, 1),
+                                }),
+                            ]],
+                        ));
+                }))
+                .add_system(
+                    EVENT_BEFORE_UPDATE,
+                    System::new(
+                        vec![Query::new().has::<Mover>().has::<TerminalTransform>()],
+                        |results, _, _| {
+                            results[0].get_only_mut::<TerminalTransform>().coords +=
+                                IntCoords2d::right();
+                        },
+                    ),
+                )
+                .start_stepped(Renderer::Headless(TerminalRendererOptions {
+                    screen_resolution: Dimensions2d::new(10, 10),
+                    include_default_camera: true,
+                    default_foreground_color: None,
+                    default_background_color: None,
+                }));
+
+                for expected_x in [2, 3] {
+                    game.step(Duration::from_millis(16));
+
+                    assert_eq!(
+                        game.query(
+                            &Query::new()
+                                .has::<TerminalTextCharacter>()
+                                .has::<TerminalTransform>()
+                        )
+                        .get_only::<TerminalTransform>()
+                        .coords,
+                        IntCoords2d::new(expected_x, 1)
+                    );
+                }
+            }
         }
     }
 }
@@ -1,7 +1,7 @@
 use proc_macro::TokenStream;
 use quote::quote;
 
-#[proc_macro_derive(Component)]
+#[proc_macro_derive(Component, attributes(component))]
 pub fn component_macro_derive(input: TokenStream) -> TokenStream {
     // Construct a representation of Rust code as a syntax tree
     // that we can manipulate
@@ -11,16 +11,185 @@ pub fn component_macro_derive(input: TokenStream) -> TokenStream {
     impl_component_macro(&ast)
 }
 
+#[proc_macro_derive(Resource)]
+pub fn resource_macro_derive(input: TokenStream) -> TokenStream {
+    let ast = syn::parse(input).unwrap();
+
+    impl_resource_macro(&ast)
+}
+
+/// Components and resources are identified by their `TypeId` and handed around as `dyn Any`, both of which need the
+/// type to be 'static. That means every type parameter has to be 'static too. They also have to be `ParallelSafe`,
+/// which type parameters need to be for the type to be.
+fn with_bounded_type_params(generics: &syn::Generics) -> syn::Generics {
+    let mut generics = generics.clone();
+    for type_param in generics.type_params_mut() {
+        type_param.bounds.push(syn::parse_quote!('static));
+        type_param
+            .bounds
+            .push(syn::parse_quote!(thomas::ParallelSafe));
+    }
+
+    generics
+}
+
+/// Whether the component has opted into serialization with `#[component(serialize)]`.
+#[cfg(feature = "serde")]
+fn is_serializable(ast: &syn::DeriveInput) -> bool {
+    ast.attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("component"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(syn::Meta::List(list)) => Some(list.nested),
+            _ => None,
+        })
+        .flatten()
+        .any(|nested| {
+            matches!(nested, syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("serialize"))
+        })
+}
+
+/// The part of the `Component` implementation that lets a serializable component be written into snapshots. This is
+/// only generated when Thomas's `serde` feature is on, since that's the only time `Component` has the method.
+#[cfg(feature = "serde")]
+fn impl_component_serialization(ast: &syn::DeriveInput) -> proc_macro2::TokenStream {
+    if is_serializable(ast) {
+        quote! {
+            fn to_snapshot_value(&self) -> Option<thomas::SnapshotResult<thomas::SnapshotValue>> {
+                Some(thomas::to_snapshot_value(self))
+            }
+        }
+    } else {
+        quote! {}
+    }
+}
+
+#[cfg(not(feature = "serde"))]
+fn impl_component_serialization(_ast: &syn::DeriveInput) -> proc_macro2::TokenStream {
+    quote! {}
+}
+
+/// The type as it's written in the source, without the spaces that turning tokens back into a string adds around
+/// punctuation, so `Option < Rgb >` reads `Option<Rgb>`.
+fn type_name(ty: &syn::Type) -> String {
+    let mut type_name = String::new();
+    let mut previous = ' ';
+
+    for character in quote!(#ty).to_string().chars() {
+        let is_space_around_punctuation =
+            character == ' ' && (matches!(previous, '<' | '>' | '(' | '[' | '&' | ':' | '\''));
+        let is_punctuation_after_space =
+            previous == ' ' && matches!(character, '<' | '>' | ')' | ']' | ',' | ':' | ';');
+
+        if is_punctuation_after_space {
+            type_name.pop();
+        }
+
+        if !is_space_around_punctuation {
+            type_name.push(character);
+        }
+
+        previous = character;
+    }
+
+    type_name
+}
+
+/// The part of the `Component` implementation that gives tools access to the component's fields. Each field is read
+/// and written through a `FieldProbe`, which only allows it when the field's type implements `ReflectField`.
+fn impl_component_reflection(ast: &syn::DeriveInput) -> proc_macro2::TokenStream {
+    let fields: Vec<(String, proc_macro2::TokenStream, &syn::Type)> = match &ast.data {
+        syn::Data::Struct(data) => data
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| match &field.ident {
+                Some(ident) => (ident.to_string(), quote!(#ident), &field.ty),
+                None => {
+                    let index = syn::Index::from(index);
+
+                    (index.index.to_string(), quote!(#index), &field.ty)
+                }
+            })
+            .collect(),
+        _ => vec![],
+    };
+
+    let field_infos = fields.iter().map(|(name, _, ty)| {
+        let type_name = type_name(ty);
+
+        quote! { thomas::FieldInfo::new(#name, #type_name) }
+    });
+    let field_getters = fields.iter().map(|(name, member, _)| {
+        quote! { #name => (&thomas::FieldProbe(&self.#member)).field_value(), }
+    });
+    let field_setters = fields.iter().map(|(name, member, _)| {
+        quote! {
+            #name => match (&thomas::FieldProbe(&self.#member)).field_from_value(value) {
+                Some(new_value) => {
+                    self.#member = new_value;
+                    true
+                }
+                None => false,
+            },
+        }
+    });
+    let value = if fields.is_empty() {
+        quote!(_value)
+    } else {
+        quote!(value)
+    };
+
+    quote! {
+        fn fields(&self) -> &'static [thomas::FieldInfo] {
+            const FIELDS: &[thomas::FieldInfo] = &[#(#field_infos),*];
+
+            FIELDS
+        }
+
+        fn field(&self, name: &str) -> Option<thomas::FieldValue> {
+            use thomas::{OpaqueFieldProbe as _, ReflectedFieldProbe as _};
+
+            match name {
+                #(#field_getters)*
+                _ => None,
+            }
+        }
+
+        fn set_field(&mut self, name: &str, #value: thomas::FieldValue) -> bool {
+            use thomas::{OpaqueFieldProbe as _, ReflectedFieldProbe as _};
+
+            match name {
+                #(#field_setters)*
+                _ => false,
+            }
+        }
+    }
+}
+
 fn impl_component_macro(ast: &syn::DeriveInput) -> TokenStream {
     let struct_name = &ast.ident;
+    let generics = with_bounded_type_params(&ast.generics);
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+    let reflection = impl_component_reflection(ast);
+    let serialization = impl_component_serialization(ast);
+
     let gen = quote! {
-        impl Component for #struct_name {
+        impl #impl_generics Component for #struct_name #type_generics #where_clause {
             fn name() -> &'static str {
-                stringify!(#struct_name)
+                std::any::type_name::<Self>()
+            }
+
+            fn id() -> std::any::TypeId {
+                std::any::TypeId::of::<Self>()
             }
 
             fn component_name(&self) -> &'static str {
-                stringify!(#struct_name)
+                std::any::type_name::<Self>()
+            }
+
+            fn component_id(&self) -> std::any::TypeId {
+                std::any::TypeId::of::<Self>()
             }
 
             fn as_any(&self) -> &dyn std::any::Any {
@@ -32,7 +201,7 @@ fn impl_component_macro(ast: &syn::DeriveInput) -> TokenStream {
             }
 
             fn is_component_type(comp: &dyn Component) -> bool where Self: Sized{
-                comp.component_name() == Self::name()
+                comp.component_id() == Self::id()
             }
 
             fn cast(comp: &dyn Component) -> Option<&Self> where Self: Sized {
@@ -42,6 +211,46 @@ fn impl_component_macro(ast: &syn::DeriveInput) -> TokenStream {
             fn cast_mut(comp: &mut dyn Component) -> Option<&mut Self> where Self: Sized {
                 comp.as_any_mut().downcast_mut::<Self>()
             }
+
+            #reflection
+
+            #serialization
+        }
+    };
+
+    gen.into()
+}
+
+fn impl_resource_macro(ast: &syn::DeriveInput) -> TokenStream {
+    let struct_name = &ast.ident;
+    let generics = with_bounded_type_params(&ast.generics);
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+    let gen = quote! {
+        impl #impl_generics Resource for #struct_name #type_generics #where_clause {
+            fn name() -> &'static str {
+                stringify!(#struct_name)
+            }
+
+            fn id() -> std::any::TypeId {
+                std::any::TypeId::of::<Self>()
+            }
+
+            fn resource_name(&self) -> &'static str {
+                stringify!(#struct_name)
+            }
+
+            fn resource_id(&self) -> std::any::TypeId {
+                std::any::TypeId::of::<Self>()
+            }
+
+            fn as_any(&self) -> &dyn std::any::Any {
+                self
+            }
+
+            fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+                self
+            }
         }
     };
 